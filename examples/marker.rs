@@ -1,11 +1,15 @@
-use bevy::{color::palettes::css::RED, prelude::*};
+use bevy::color::palettes::css::RED;
+use bevy::prelude::*;
 use neko_derive::NekoMarker;
-use neko_maid::{components::NekoUITree, marker::{NekoMarker, MarkerAppExt}};
-
+use neko_maid::components::NekoUITree;
+use neko_maid::marker::MarkerAppExt;
 
 #[derive(Component, NekoMarker)]
 #[neko_marker("test")]
-pub struct Test;
+pub struct Test {
+    #[neko(rename = "frame-width", default = 2.0)]
+    width: f32,
+}
 
 fn main() {
     App::new()
@@ -24,10 +28,11 @@ fn setup(asset_server: Res<AssetServer>, mut commands: Commands) {
     commands.spawn(NekoUITree::new(handle));
 }
 
-pub fn spawned_test(
-    event: On<Add, Test>,
-    mut cmds: Commands,
-) {
+pub fn spawned_test(event: On<Add, Test>, tests: Query<&Test>, mut cmds: Commands) {
+    let Ok(test) = tests.get(event.entity) else {
+        return;
+    };
+
     // Could add any arbitrary logic here. We're gonna just build some UI manually.
 
     println!("Spawned test {}", event.entity);
@@ -41,11 +46,11 @@ pub fn spawned_test(
                 right: Val::Px(0.0),
                 width: Val::Auto,
                 height: Val::Auto,
-                border: UiRect::all(Val::Px(2.0)),
+                border: UiRect::all(Val::Px(test.width)),
                 ..Default::default()
             },
             BorderColor::all(RED),
             BackgroundColor(Color::NONE),
         ));
     });
-}
\ No newline at end of file
+}
@@ -1,6 +1,7 @@
 use bevy::prelude::*;
 use neko_maid::components::NekoUITree;
 use neko_maid::parse::value::PropertyValue;
+use neko_maid::value::IntoPropertyValue;
 
 fn main() {
     App::new()
@@ -22,7 +23,7 @@ pub fn update_color(time: Res<Time>, mut q: Query<&mut NekoUITree>) {
     for mut root in &mut q {
         let h = (time.elapsed_secs_f64() % 4.0) / 4.0 * 360.0;
         let color = Color::hsl(h as f32, 0.5, 0.3);
-        root.set_variable("color", PropertyValue::Color(color));
+        root.set_variable("color", color.into_property_value());
 
         let width = 400.0 + f64::sin(time.elapsed_secs_f64()) * 100.0;
         root.set_variable("width", PropertyValue::Number(width));
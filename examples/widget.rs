@@ -0,0 +1,84 @@
+use bevy::prelude::*;
+use neko_derive::{NekoEnum, NekoWidget};
+use neko_maid::components::NekoUITree;
+use neko_maid::widget::NativeWidgetAppExt;
+
+/// A health bar, filled according to its `value` property.
+#[derive(Component, NekoWidget)]
+#[neko(name = "health_bar")]
+#[require(Node = Node {
+    width: Val::Px(300.0),
+    height: Val::Px(24.0),
+    ..default()
+}, BackgroundColor(Color::BLACK))]
+pub struct HealthBar {
+    #[neko(default = 1.0)]
+    value: f32,
+
+    #[neko(rename = "fill", default = Color::srgb(0.8, 0.1, 0.1))]
+    fill_color: Color,
+
+    #[neko(rename = "fill-from")]
+    fill_from: FillFrom,
+
+    #[neko(skip)]
+    fill: Option<Entity>,
+}
+
+/// The side a [`HealthBar`] fills from, written as `left`, `right` or
+/// `middle`.
+#[derive(Default, Clone, Copy, NekoEnum)]
+pub enum FillFrom {
+    #[default]
+    Left,
+    Right,
+    #[neko(rename = "middle")]
+    Center,
+}
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(neko_maid::NekoMaidPlugin)
+        .add_native_widget::<HealthBar>()
+        .add_systems(Startup, setup)
+        .add_systems(Update, update_health_bars)
+        .run();
+}
+
+fn setup(asset_server: Res<AssetServer>, mut commands: Commands) {
+    commands.spawn(Camera2d);
+
+    let handle = asset_server.load("widget.neko_ui");
+    commands.spawn(NekoUITree::new(handle));
+}
+
+fn update_health_bars(
+    mut bars: Query<(Entity, &mut HealthBar, &mut Node), Changed<HealthBar>>,
+    mut commands: Commands,
+) {
+    for (entity, mut bar, mut node) in bars.iter_mut() {
+        node.justify_content = match bar.fill_from {
+            FillFrom::Left => JustifyContent::FlexStart,
+            FillFrom::Right => JustifyContent::FlexEnd,
+            FillFrom::Center => JustifyContent::Center,
+        };
+
+        if let Some(fill) = bar.fill {
+            commands.entity(fill).despawn();
+        }
+
+        let fill = commands
+            .spawn((
+                ChildOf(entity),
+                Node {
+                    width: Val::Percent(bar.value.clamp(0.0, 1.0) * 100.0),
+                    height: Val::Percent(100.0),
+                    ..default()
+                },
+                BackgroundColor(bar.fill_color),
+            ))
+            .id();
+        bar.bypass_change_detection().fill = Some(fill);
+    }
+}
@@ -1,38 +1,449 @@
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{parse_macro_input, DeriveInput, LitStr};
+use syn::{
+    Data,
+    DeriveInput,
+    Expr,
+    Field,
+    Fields,
+    Ident,
+    Lit,
+    LitStr,
+    Type,
+    UnOp,
+    parse_macro_input,
+};
 
-#[proc_macro_derive(NekoMarker, attributes(neko_marker))]
+#[proc_macro_derive(NekoMarker, attributes(neko_marker, neko))]
 pub fn derive_neko_marker(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
+    match neko_marker(&input) {
+        Ok(expanded) => expanded.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Generates the `NekoMarker` implementation for a struct.
+fn neko_marker(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
     let ident = &input.ident;
 
     // Find #[neko_marker("...")]
     let mut marker_value: Option<LitStr> = None;
-
     for attr in &input.attrs {
         if attr.path().is_ident("neko_marker") {
-            let lit: LitStr = attr
-                .parse_args()
-                .expect("neko_marker expects a string literal");
-            marker_value = Some(lit);
+            marker_value = Some(attr.parse_args()?);
         }
     }
 
-    let marker_value = marker_value
-        .expect("Missing #[neko_marker(\"...\")] attribute");
+    let Some(marker_value) = marker_value else {
+        return Err(syn::Error::new_spanned(
+            ident,
+            "missing #[neko_marker(\"...\")] attribute",
+        ));
+    };
+
+    let fields = field_properties(input, "NekoMarker")?;
+    let properties: Vec<_> = fields.properties().collect();
+    let defaults = fields.values(|_, ty, default| {
+        default.unwrap_or_else(|| quote! { <#ty as ::std::default::Default>::default() })
+    });
+    let values = fields.values(|property, ty, default| {
+        let default =
+            default.unwrap_or_else(|| quote! { <#ty as ::std::default::Default>::default() });
+        quote! {
+            ::neko_maid::value::NekoElementExt::get_as_or::<#ty>(element, #property, #default)
+        }
+    });
 
-    let expanded = quote! {
-        impl NekoMarker for #ident {
+    Ok(quote! {
+        impl ::neko_maid::marker::NekoMarker for #ident {
             fn new() -> Self {
-                Self
+                #defaults
+            }
+
+            fn from_element(element: &::neko_maid::parse::element::NekoElement) -> Self {
+                #values
             }
 
             fn id() -> &'static str {
                 #marker_value
             }
+
+            fn properties() -> &'static [&'static str] {
+                &[#(#properties),*]
+            }
+        }
+    })
+}
+
+#[proc_macro_derive(NekoWidget, attributes(neko))]
+pub fn derive_neko_widget(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match neko_widget(&input) {
+        Ok(expanded) => expanded.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Generates the `NekoWidget` implementation for a struct.
+fn neko_widget(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let ident = &input.ident;
+
+    // Find #[neko(name = "...")], defaulting to the struct name in snake case.
+    let mut name = LitStr::new(&snake_case(&ident.to_string()), ident.span());
+    for attr in &input.attrs {
+        if attr.path().is_ident("neko") {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("name") {
+                    name = meta.value()?.parse()?;
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `name = \"...\"`"))
+                }
+            })?;
+        }
+    }
+
+    let fields = field_properties(input, "NekoWidget")?;
+    let defaults = fields.fields.iter().filter_map(|field| {
+        let property = field.property.as_ref()?;
+        let default = field.default_value();
+        Some(quote! {
+            properties.insert(::std::string::String::from(#property), #default);
+        })
+    });
+    let accepted = fields.fields.iter().filter_map(|field| {
+        let property = field.property.as_ref()?;
+        let ty = field.ty;
+        Some(quote! {
+            values.insert(
+                ::std::string::String::from(#property),
+                <#ty as ::neko_maid::value::FromPropertyValue>::accepted_values(),
+            );
+        })
+    });
+    let construct = fields.values(|property, ty, _| {
+        quote! { ::neko_maid::value::NekoElementExt::get_as::<#ty>(element, #property) }
+    });
+
+    Ok(quote! {
+        impl ::neko_maid::widget::NekoWidget for #ident {
+            fn name() -> &'static str {
+                #name
+            }
+
+            fn default_properties() -> ::std::collections::HashMap<
+                ::std::string::String,
+                ::neko_maid::parse::value::PropertyValue,
+            > {
+                #[allow(unused_mut)]
+                let mut properties = ::std::collections::HashMap::new();
+                #(#defaults)*
+                properties
+            }
+
+            fn accepted_values() -> ::std::collections::HashMap<
+                ::std::string::String,
+                (
+                    &'static [::neko_maid::parse::property::PropertyType],
+                    &'static [&'static str],
+                ),
+            > {
+                #[allow(unused_mut)]
+                let mut values = ::std::collections::HashMap::new();
+                #(#accepted)*
+                values
+            }
+
+            fn from_element(element: &::neko_maid::parse::element::NekoElement) -> Self {
+                #construct
+            }
+        }
+    })
+}
+
+/// The fields of a struct, along with the element properties they are read
+/// from.
+struct StructFields<'a> {
+    /// Whether the struct is a unit struct.
+    is_unit: bool,
+
+    /// The named fields of the struct.
+    fields: Vec<FieldProperty<'a>>,
+}
+
+/// A named field of a struct, along with the element property it is read from.
+struct FieldProperty<'a> {
+    /// The name of the field.
+    ident: &'a Ident,
+
+    /// The type of the field.
+    ty: &'a Type,
+
+    /// The name of the property, or `None` if the field is skipped.
+    property: Option<LitStr>,
+
+    /// The default value of the property, if one is given.
+    default: Option<Expr>,
+}
+
+impl StructFields<'_> {
+    /// Returns the names of the properties read by the fields.
+    fn properties(&self) -> impl Iterator<Item = &LitStr> {
+        self.fields
+            .iter()
+            .filter_map(|field| field.property.as_ref())
+    }
+
+    /// Returns an expression constructing the struct, where each field is
+    /// read from its property by the given function. The function is given
+    /// the property, the field type and the default value, if any. Skipped
+    /// fields use their default value.
+    fn values(
+        &self,
+        read: impl Fn(&LitStr, &Type, Option<proc_macro2::TokenStream>) -> proc_macro2::TokenStream,
+    ) -> proc_macro2::TokenStream {
+        if self.is_unit {
+            return quote! { Self };
+        }
+
+        let values = self.fields.iter().map(|field| {
+            let ident = field.ident;
+            let value = match &field.property {
+                Some(property) => read(property, field.ty, field.default()),
+                None => quote! { ::std::default::Default::default() },
+            };
+            quote! { #ident: #value }
+        });
+        quote! { Self { #(#values),* } }
+    }
+}
+
+impl FieldProperty<'_> {
+    /// Returns the default value of the field, if one is given.
+    ///
+    /// Literals are converted through a property value, like any value read
+    /// from an element, so that `10` is accepted by a `Val` field and
+    /// `"right"` by an enum field. Other expressions are converted into the
+    /// field type with `From`, so that their type is checked at compile time.
+    fn default(&self) -> Option<proc_macro2::TokenStream> {
+        let ty = self.ty;
+        self.default.as_ref().map(|expr| match literal_value(expr) {
+            Some(value) => quote! {
+                <#ty as ::neko_maid::value::FromPropertyValue>::from_property_value(&#value)
+            },
+            None => quote! { <#ty as ::std::convert::From<_>>::from(#expr) },
+        })
+    }
+
+    /// Returns the default value of the property. Literals are used as they
+    /// are written, and other values are converted from the default value of
+    /// the field.
+    fn default_value(&self) -> proc_macro2::TokenStream {
+        if let Some(value) = self.default.as_ref().and_then(literal_value) {
+            return value;
+        }
+
+        let ty = self.ty;
+        let default = self
+            .default()
+            .unwrap_or_else(|| quote! { <#ty as ::std::default::Default>::default() });
+        quote! { ::neko_maid::value::IntoPropertyValue::into_property_value(#default) }
+    }
+}
+
+/// Returns the property value written by the given expression, if it is a
+/// number, string or boolean literal.
+fn literal_value(expr: &Expr) -> Option<proc_macro2::TokenStream> {
+    let value = quote! { ::neko_maid::parse::value::PropertyValue };
+    if is_numeric_literal(expr) {
+        return Some(quote! { #value::Number((#expr) as f64) });
+    }
+
+    match expr {
+        Expr::Lit(lit) => match &lit.lit {
+            Lit::Str(s) => Some(quote! { #value::String(::std::string::String::from(#s)) }),
+            Lit::Bool(b) => Some(quote! { #value::Bool(#b) }),
+            _ => None,
+        },
+        Expr::Group(group) => literal_value(&group.expr),
+        _ => None,
+    }
+}
+
+/// Checks whether the given expression is an integer or float literal,
+/// optionally negated.
+fn is_numeric_literal(expr: &Expr) -> bool {
+    match expr {
+        Expr::Lit(lit) => matches!(lit.lit, Lit::Int(_) | Lit::Float(_)),
+        Expr::Unary(unary) if matches!(unary.op, UnOp::Neg(_)) => is_numeric_literal(&unary.expr),
+        Expr::Group(group) => is_numeric_literal(&group.expr),
+        _ => false,
+    }
+}
+
+/// Finds the fields of a struct and the properties they are read from, given
+/// by `#[neko(rename = "...", default = ..., skip)]` on each field.
+fn field_properties<'a>(input: &'a DeriveInput, derive: &str) -> syn::Result<StructFields<'a>> {
+    let ident = &input.ident;
+
+    let (is_unit, fields) = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => (false, fields.named.iter().collect()),
+            Fields::Unit => (true, Vec::new()),
+            Fields::Unnamed(_) => {
+                return Err(syn::Error::new_spanned(
+                    ident,
+                    format!("{} requires a struct with named fields", derive),
+                ));
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                ident,
+                format!("{} can only be derived for structs", derive),
+            ));
         }
     };
 
-    expanded.into()
+    let fields = fields
+        .into_iter()
+        .map(|field: &Field| {
+            let field_ident = field.ident.as_ref().expect("named fields have idents");
+
+            let mut property = LitStr::new(
+                &field_ident.to_string().replace('_', "-"),
+                field_ident.span(),
+            );
+            let mut default: Option<Expr> = None;
+            let mut skip = false;
+
+            for attr in &field.attrs {
+                if attr.path().is_ident("neko") {
+                    attr.parse_nested_meta(|meta| {
+                        if meta.path.is_ident("rename") {
+                            property = meta.value()?.parse()?;
+                        } else if meta.path.is_ident("default") {
+                            default = Some(meta.value()?.parse()?);
+                        } else if meta.path.is_ident("skip") {
+                            skip = true;
+                        } else {
+                            return Err(meta.error("expected `rename`, `default` or `skip`"));
+                        }
+                        Ok(())
+                    })?;
+                }
+            }
+
+            Ok(FieldProperty {
+                ident: field_ident,
+                ty: &field.ty,
+                property: (!skip).then_some(property),
+                default,
+            })
+        })
+        .collect::<syn::Result<_>>()?;
+
+    Ok(StructFields { is_unit, fields })
+}
+
+#[proc_macro_derive(NekoEnum, attributes(neko))]
+pub fn derive_neko_enum(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match neko_enum(&input) {
+        Ok(expanded) => expanded.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Generates the `NekoEnum` implementation for an enum of unit variants.
+fn neko_enum(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let ident = &input.ident;
+
+    let Data::Enum(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            ident,
+            "NekoEnum can only be derived for enums",
+        ));
+    };
+
+    let mut variants = Vec::new();
+    let mut keywords: Vec<LitStr> = Vec::new();
+
+    for variant in &data.variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(syn::Error::new_spanned(
+                variant,
+                "NekoEnum requires all variants to be unit variants",
+            ));
+        }
+
+        // Find #[neko(rename = "...")], defaulting to the variant name in
+        // kebab case.
+        let variant_ident = &variant.ident;
+        let mut keyword = LitStr::new(
+            &snake_case(&variant_ident.to_string()).replace('_', "-"),
+            variant_ident.span(),
+        );
+        for attr in &variant.attrs {
+            if attr.path().is_ident("neko") {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("rename") {
+                        keyword = meta.value()?.parse()?;
+                        Ok(())
+                    } else {
+                        Err(meta.error("expected `rename = \"...\"`"))
+                    }
+                })?;
+            }
+        }
+
+        if keywords
+            .iter()
+            .any(|other| other.value() == keyword.value())
+        {
+            return Err(syn::Error::new_spanned(
+                variant,
+                format!("duplicate keyword `{}`", keyword.value()),
+            ));
+        }
+
+        variants.push(variant_ident);
+        keywords.push(keyword);
+    }
+
+    Ok(quote! {
+        impl ::neko_maid::value::NekoEnum for #ident {
+            const KEYWORDS: &'static [&'static str] = &[#(#keywords),*];
+
+            fn from_keyword(keyword: &str) -> ::std::option::Option<Self> {
+                match keyword {
+                    #(#keywords => ::std::option::Option::Some(Self::#variants),)*
+                    _ => ::std::option::Option::None,
+                }
+            }
+
+            fn keyword(&self) -> &'static str {
+                match self {
+                    #(Self::#variants => #keywords,)*
+                }
+            }
+        }
+    })
+}
+
+/// Converts a `PascalCase` identifier into `snake_case`.
+fn snake_case(ident: &str) -> String {
+    let mut snake = String::new();
+    for (i, c) in ident.chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 {
+                snake.push('_');
+            }
+            snake.extend(c.to_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+    snake
 }
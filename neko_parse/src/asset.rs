@@ -0,0 +1,217 @@
+//! Resolution of asset paths, such as the `src` of an image, relative to the
+//! module that declares them.
+//!
+//! Literal paths on native widgets are resolved as they are parsed. Paths that
+//! reach a native widget through a variable, such as the default of a custom
+//! widget property or an argument passed to a custom widget, are resolved once
+//! the whole module is parsed, by following the variables they pass through.
+
+use std::collections::HashMap;
+
+use crate::context::ParseContext;
+use crate::layout::Layout;
+use crate::native::{AssetKind, asset_property, is_asset_path};
+use crate::property::UnresolvedPropertyValue;
+use crate::value::PropertyValue;
+use crate::widget::{Widget, WidgetLayout};
+
+/// A map of variable names to the kind of asset whose path they hold.
+pub type AssetVariables = HashMap<String, AssetKind>;
+
+/// Resolves the literal asset paths in the value of the given property of a
+/// native widget, including any fallbacks in a comma list, and records them as
+/// assets of the module.
+pub(crate) fn resolve_asset_paths(
+    ctx: &mut ParseContext,
+    widget: &str,
+    property: &str,
+    value: &mut UnresolvedPropertyValue,
+) {
+    let Some(kind) = asset_property(widget, property) else {
+        return;
+    };
+
+    for value in path_values_mut(value) {
+        if let UnresolvedPropertyValue::Constant(PropertyValue::String(path)) = value
+            && is_asset_path(kind, path)
+        {
+            *path = ctx.resolve_asset_path(path);
+            ctx.add_asset(path.clone(), kind);
+        }
+    }
+}
+
+/// Returns the properties of each custom widget that hold asset paths, along
+/// with the kind of asset, by following them to the native widgets they are
+/// passed to.
+pub fn asset_parameters(widgets: &HashMap<String, Widget>) -> HashMap<String, AssetVariables> {
+    let mut parameters = HashMap::new();
+    for name in widgets.keys() {
+        widget_parameters(widgets, name, &mut parameters);
+    }
+    parameters
+}
+
+/// Computes the asset parameters of the custom widget with the given name,
+/// along with those of the custom widgets it uses. Widgets must not be
+/// recursive.
+fn widget_parameters(
+    widgets: &HashMap<String, Widget>,
+    name: &str,
+    parameters: &mut HashMap<String, AssetVariables>,
+) {
+    if parameters.contains_key(name) {
+        return;
+    }
+
+    let Some(Widget::Custom(widget)) = widgets.get(name) else {
+        return;
+    };
+
+    let mut layouts = vec![&widget.layout];
+    let mut variables = AssetVariables::new();
+    while let Some(layout) = layouts.pop() {
+        widget_parameters(widgets, &layout.widget, parameters);
+        collect_variables(
+            &layout.widget,
+            &layout.properties,
+            parameters,
+            &mut variables,
+        );
+        layouts.extend(layout.children.iter());
+    }
+
+    variables.retain(|variable, _| widget.default_properties.contains_key(variable));
+    parameters.insert(name.to_string(), variables);
+}
+
+/// Collects the variables used as asset paths in the properties of a single
+/// layout node, or of a style targeting the given widget.
+pub(crate) fn collect_variables(
+    widget: &str,
+    properties: &HashMap<String, UnresolvedPropertyValue>,
+    parameters: &HashMap<String, AssetVariables>,
+    variables: &mut AssetVariables,
+) {
+    for (property, value) in properties {
+        let kind = match parameters.get(widget) {
+            Some(widget_parameters) => widget_parameters.get(property).copied(),
+            None => asset_property(widget, property),
+        };
+
+        if let Some(kind) = kind {
+            for value in path_values(value) {
+                if let UnresolvedPropertyValue::Variable(variable) = value {
+                    variables.insert(variable.clone(), kind);
+                }
+            }
+        }
+    }
+}
+
+/// Resolves the literal asset paths passed to custom widgets in the given
+/// layout and its children, and collects the variables used as asset paths.
+pub(crate) fn resolve_layout_arguments(
+    layout: &mut Layout,
+    parameters: &HashMap<String, AssetVariables>,
+    variables: &mut AssetVariables,
+    resolve: &mut impl FnMut(&mut String, AssetKind),
+) {
+    resolve_arguments(
+        &layout.widget,
+        &mut layout.properties,
+        parameters,
+        variables,
+        resolve,
+    );
+
+    for child in &mut layout.children {
+        resolve_layout_arguments(child, parameters, variables, resolve);
+    }
+}
+
+/// Resolves the literal asset paths passed to custom widgets in the given
+/// widget layout and its children, and collects the variables used as asset
+/// paths.
+pub(crate) fn resolve_widget_arguments(
+    layout: &mut WidgetLayout,
+    parameters: &HashMap<String, AssetVariables>,
+    variables: &mut AssetVariables,
+    resolve: &mut impl FnMut(&mut String, AssetKind),
+) {
+    resolve_arguments(
+        &layout.widget,
+        &mut layout.properties,
+        parameters,
+        variables,
+        resolve,
+    );
+
+    for child in &mut layout.children {
+        resolve_widget_arguments(child, parameters, variables, resolve);
+    }
+}
+
+/// Resolves the literal asset paths passed to a custom widget, and collects the
+/// variables used as asset paths by a layout node.
+///
+/// The literal paths of native widgets are already resolved while parsing.
+fn resolve_arguments(
+    widget: &str,
+    properties: &mut HashMap<String, UnresolvedPropertyValue>,
+    parameters: &HashMap<String, AssetVariables>,
+    variables: &mut AssetVariables,
+    resolve: &mut impl FnMut(&mut String, AssetKind),
+) {
+    let Some(widget_parameters) = parameters.get(widget) else {
+        collect_variables(widget, properties, parameters, variables);
+        return;
+    };
+
+    for (property, value) in properties {
+        if let Some(kind) = widget_parameters.get(property) {
+            resolve_value(value, *kind, variables, resolve);
+        }
+    }
+}
+
+/// Resolves the literal asset paths in a value that holds an asset of the given
+/// kind, and collects the variables it refers to.
+pub(crate) fn resolve_value(
+    value: &mut UnresolvedPropertyValue,
+    kind: AssetKind,
+    variables: &mut AssetVariables,
+    resolve: &mut impl FnMut(&mut String, AssetKind),
+) {
+    for value in path_values_mut(value) {
+        match value {
+            UnresolvedPropertyValue::Constant(PropertyValue::String(path))
+                if is_asset_path(kind, path) =>
+            {
+                resolve(path, kind);
+            }
+            UnresolvedPropertyValue::Variable(variable) => {
+                variables.insert(variable.clone(), kind);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Returns the values of a property that may each be an asset path, which is
+/// the value itself, or each fallback of a comma list.
+fn path_values(value: &UnresolvedPropertyValue) -> &[UnresolvedPropertyValue] {
+    match value {
+        UnresolvedPropertyValue::CommaList(values) => values,
+        value => std::slice::from_ref(value),
+    }
+}
+
+/// Returns the values of a property that may each be an asset path, as in
+/// [`path_values`], for modification.
+fn path_values_mut(value: &mut UnresolvedPropertyValue) -> &mut [UnresolvedPropertyValue] {
+    match value {
+        UnresolvedPropertyValue::CommaList(values) => values,
+        value => std::slice::from_mut(value),
+    }
+}
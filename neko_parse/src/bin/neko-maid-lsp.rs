@@ -0,0 +1,5 @@
+//! A language server for NekoMaid UI files, communicating over stdio.
+
+fn main() {
+    neko_parse::lsp::server::run();
+}
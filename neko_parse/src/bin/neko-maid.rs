@@ -0,0 +1,289 @@
+//! Command-line tools for working with NekoMaid UI files.
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::{env, fs, io};
+
+use neko_parse::format::{FormatOptions, PropertyOrder, format};
+use neko_parse::lint::{LintOptions, Severity, lint};
+#[cfg(feature = "manifest")]
+use neko_parse::manifest::Manifest;
+#[cfg(feature = "schema")]
+use neko_parse::schema::Schema;
+#[cfg(all(feature = "schema", feature = "manifest"))]
+use neko_parse::widget::Widget;
+use neko_parse::workspace::Workspace;
+
+/// The usage message printed for invalid arguments.
+const USAGE: &str = "\
+Usage: neko-maid <command> [options] <paths...>
+
+Commands:
+  fmt                  Formats .neko_ui files in place.
+  check                Reports errors and likely mistakes in .neko_ui files.
+  schema               Prints a schema of the native widgets and of all
+                       widgets defined in the given .neko_ui files. Requires
+                       the schema feature.
+
+Options for fmt:
+  --check              Only check the formatting, failing if any file would
+                       be changed.
+  --indent <n>         The number of spaces per indentation level. (default: 2)
+  --sort-properties    Sorts consecutive properties alphabetically.
+
+Options for check:
+  --manifest <file>    Reads the native widgets, global properties, and class
+                       markers registered by the application from a JSON
+                       manifest. Requires the manifest feature.
+  --marker <name>      Registers a class marker, so that the class is not
+                       reported as unknown. Can be given multiple times.
+  --deny-warnings      Fails if any warnings are reported.
+
+Options for schema:
+  --manifest <file>    Reads the native widgets and global properties
+                       registered by the application from a JSON manifest.
+                       Requires the manifest feature.
+  --format <format>    The output format, either json or ron. (default: json)
+
+Directories are searched recursively for .neko_ui files.";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let result = match args.first().map(String::as_str) {
+        Some("fmt") => run_fmt(&args[1 ..]),
+        Some("check") => run_check(&args[1 ..]),
+        #[cfg(feature = "schema")]
+        Some("schema") => run_schema(&args[1 ..]),
+        #[cfg(not(feature = "schema"))]
+        Some("schema") => Err(String::from(
+            "The schema command requires the schema feature",
+        )),
+        _ => Err(USAGE.to_string()),
+    };
+
+    match result {
+        Ok(code) => code,
+        Err(message) => {
+            eprintln!("{}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Runs the `fmt` command with the given arguments.
+fn run_fmt(args: &[String]) -> Result<ExitCode, String> {
+    let mut options = FormatOptions::default();
+    let mut check = false;
+    let mut paths = Vec::new();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--check" => check = true,
+            "--sort-properties" => options.property_order = PropertyOrder::Alphabetical,
+            "--indent" => {
+                options.indent = args
+                    .next()
+                    .and_then(|n| n.parse().ok())
+                    .ok_or_else(|| USAGE.to_string())?;
+            }
+            flag if flag.starts_with("--") => return Err(USAGE.to_string()),
+            path => paths.push(PathBuf::from(path)),
+        }
+    }
+
+    if paths.is_empty() {
+        return Err(USAGE.to_string());
+    }
+
+    let files = collect_files(&paths).map_err(|e| format!("Failed to read files: {}", e))?;
+    let mut failed = false;
+
+    for file in files {
+        let code = match fs::read_to_string(&file) {
+            Ok(code) => code,
+            Err(e) => {
+                eprintln!("{}: {}", file.display(), e);
+                failed = true;
+                continue;
+            }
+        };
+
+        let formatted = match format(&code, &options) {
+            Ok(formatted) => formatted,
+            Err(e) => {
+                eprintln!("{}: {}", file.display(), e);
+                failed = true;
+                continue;
+            }
+        };
+
+        if formatted == code {
+            continue;
+        }
+
+        if check {
+            println!("{} is not formatted", file.display());
+            failed = true;
+        } else if let Err(e) = fs::write(&file, formatted) {
+            eprintln!("{}: {}", file.display(), e);
+            failed = true;
+        } else {
+            println!("Formatted {}", file.display());
+        }
+    }
+
+    match failed {
+        true => Ok(ExitCode::FAILURE),
+        false => Ok(ExitCode::SUCCESS),
+    }
+}
+
+/// Runs the `check` command with the given arguments.
+fn run_check(args: &[String]) -> Result<ExitCode, String> {
+    let mut options = LintOptions::default();
+    let mut workspace = Workspace::new();
+    let mut deny_warnings = false;
+    let mut paths = Vec::new();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--deny-warnings" => deny_warnings = true,
+            #[cfg(feature = "manifest")]
+            "--manifest" => {
+                let manifest = load_manifest(args.next())?;
+                manifest.register(&mut workspace);
+                options.markers.extend(manifest.markers);
+            }
+            "--marker" => {
+                let marker = args.next().ok_or_else(|| USAGE.to_string())?;
+                options.markers.insert(marker.clone());
+            }
+            flag if flag.starts_with("--") => return Err(USAGE.to_string()),
+            path => paths.push(PathBuf::from(path)),
+        }
+    }
+
+    if paths.is_empty() {
+        return Err(USAGE.to_string());
+    }
+
+    let files = collect_files(&paths).map_err(|e| format!("Failed to read files: {}", e))?;
+    let diagnostics = lint(&mut workspace, &files, &options);
+
+    for diagnostic in &diagnostics {
+        println!("{}", diagnostic);
+    }
+
+    let errors = diagnostics
+        .iter()
+        .filter(|d| d.severity == Severity::Error)
+        .count();
+    let warnings = diagnostics.len() - errors;
+
+    println!(
+        "Checked {} files: {} errors, {} warnings",
+        files.len(),
+        errors,
+        warnings
+    );
+
+    match errors > 0 || (deny_warnings && warnings > 0) {
+        true => Ok(ExitCode::FAILURE),
+        false => Ok(ExitCode::SUCCESS),
+    }
+}
+
+/// Runs the `schema` command with the given arguments.
+#[cfg(feature = "schema")]
+fn run_schema(args: &[String]) -> Result<ExitCode, String> {
+    let mut ron = false;
+    let mut workspace = Workspace::new();
+    let mut schema = Schema::native();
+    let mut paths = Vec::new();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            #[cfg(feature = "manifest")]
+            "--manifest" => {
+                let manifest = load_manifest(args.next())?;
+                manifest.register(&mut workspace);
+                for widget in manifest.native_widgets() {
+                    schema.add_widget(&Widget::Native(widget), true);
+                }
+            }
+            "--format" => match args.next().map(String::as_str) {
+                Some("json") => ron = false,
+                Some("ron") => ron = true,
+                _ => return Err(USAGE.to_string()),
+            },
+            flag if flag.starts_with("--") => return Err(USAGE.to_string()),
+            path => paths.push(PathBuf::from(path)),
+        }
+    }
+
+    let files = collect_files(&paths).map_err(|e| format!("Failed to read files: {}", e))?;
+    let mut failed = false;
+
+    for file in files {
+        match workspace.load(&file) {
+            Ok(module) => schema.add_module(&module),
+            Err(e) => {
+                eprintln!("{}", e);
+                failed = true;
+            }
+        }
+    }
+
+    match ron {
+        true => println!("{}", schema.to_ron()),
+        false => println!("{}", schema.to_json()),
+    }
+
+    match failed {
+        true => Ok(ExitCode::FAILURE),
+        false => Ok(ExitCode::SUCCESS),
+    }
+}
+
+/// Loads the manifest at the path given as the argument of `--manifest`.
+#[cfg(feature = "manifest")]
+fn load_manifest(path: Option<&String>) -> Result<Manifest, String> {
+    let path = path.ok_or_else(|| USAGE.to_string())?;
+    Manifest::load(Path::new(path)).map_err(|e| e.to_string())
+}
+
+/// Collects all NekoMaid UI files from the given paths, searching directories
+/// recursively. The returned files are sorted.
+fn collect_files(paths: &[PathBuf]) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+
+    for path in paths {
+        if path.is_dir() {
+            collect_dir(path, &mut files)?;
+        } else {
+            files.push(path.clone());
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+/// Recursively collects all NekoMaid UI files within the given directory.
+fn collect_dir(dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        if path.is_dir() {
+            collect_dir(&path, files)?;
+        } else if path.extension().is_some_and(|ext| ext == "neko_ui") {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
@@ -1,13 +1,14 @@
 //! Represents a hierarchy of classes applied to a widget for styling purposes.
 
-use bevy::platform::collections::HashSet;
+use std::collections::HashSet;
 
-use crate::parse::context::{NekoResult, ParseContext};
-use crate::parse::style::{Selector, SelectorPart};
-use crate::parse::token::TokenType;
+use crate::context::{NekoResult, ParseContext};
+use crate::style::{Selector, SelectorPart};
+use crate::token::TokenType;
 
 /// Represents a path of classes applied to a widget hierarchy.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ClassPath {
     /// The hierarchy of classes in the class path.
     ///
@@ -88,6 +89,7 @@ impl ClassPath {
 
 /// Represents a set of classes applied to a widget.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ClassSet {
     /// The widget type.
     pub widget: String,
@@ -0,0 +1,127 @@
+//! A minimal color type for color literals in NekoMaid UI files.
+
+use std::fmt;
+
+/// A color in the sRGB color space, with an alpha channel.
+///
+/// All channels are in the range `0.0 ..= 1.0`.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rgba {
+    /// The red channel.
+    pub red: f32,
+
+    /// The green channel.
+    pub green: f32,
+
+    /// The blue channel.
+    pub blue: f32,
+
+    /// The alpha channel.
+    pub alpha: f32,
+}
+
+impl Rgba {
+    /// A fully transparent color.
+    pub const NONE: Rgba = Rgba::new(0.0, 0.0, 0.0, 0.0);
+
+    /// Opaque white.
+    pub const WHITE: Rgba = Rgba::new(1.0, 1.0, 1.0, 1.0);
+
+    /// Opaque black.
+    pub const BLACK: Rgba = Rgba::new(0.0, 0.0, 0.0, 1.0);
+
+    /// Creates a new color from the given channels.
+    pub const fn new(red: f32, green: f32, blue: f32, alpha: f32) -> Self {
+        Self {
+            red,
+            green,
+            blue,
+            alpha,
+        }
+    }
+
+    /// Creates a new color from the given 8-bit channels.
+    pub fn rgba_u8(red: u8, green: u8, blue: u8, alpha: u8) -> Self {
+        Self::new(
+            red as f32 / 255.0,
+            green as f32 / 255.0,
+            blue as f32 / 255.0,
+            alpha as f32 / 255.0,
+        )
+    }
+
+    /// Parses a color from CSS-style hexadecimal notation, in the form `RGB`,
+    /// `RGBA`, `RRGGBB`, or `RRGGBBAA`, with an optional leading `#`.
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        let hex = hex.strip_prefix('#').unwrap_or(hex);
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+
+        let digit = |i: usize| u8::from_str_radix(&hex[i .. i + 1], 16).ok();
+        let byte = |i: usize| u8::from_str_radix(&hex[i .. i + 2], 16).ok();
+
+        match hex.len() {
+            3 | 4 => {
+                let channel = |i: usize| digit(i).map(|d| (d << 4) | d);
+                let alpha = if hex.len() == 4 { channel(3)? } else { 255 };
+                Some(Self::rgba_u8(channel(0)?, channel(1)?, channel(2)?, alpha))
+            }
+            6 | 8 => {
+                let alpha = if hex.len() == 8 { byte(6)? } else { 255 };
+                Some(Self::rgba_u8(byte(0)?, byte(2)?, byte(4)?, alpha))
+            }
+            _ => None,
+        }
+    }
+
+    /// Converts this color into 8-bit channels.
+    pub fn to_u8_array(&self) -> [u8; 4] {
+        [self.red, self.green, self.blue, self.alpha]
+            .map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8)
+    }
+
+    /// Converts this color into CSS-style hexadecimal notation. The alpha
+    /// channel is omitted if the color is opaque.
+    pub fn to_hex(&self) -> String {
+        let [r, g, b, a] = self.to_u8_array();
+        match a {
+            255 => format!("#{r:02X}{g:02X}{b:02X}"),
+            _ => format!("#{r:02X}{g:02X}{b:02X}{a:02X}"),
+        }
+    }
+}
+
+impl fmt::Display for Rgba {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_hex())
+    }
+}
+
+impl From<[f32; 4]> for Rgba {
+    fn from([red, green, blue, alpha]: [f32; 4]) -> Self {
+        Self::new(red, green, blue, alpha)
+    }
+}
+
+impl From<Rgba> for [f32; 4] {
+    fn from(color: Rgba) -> Self {
+        [color.red, color.green, color.blue, color.alpha]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_hex_colors() {
+        assert_eq!(Rgba::from_hex("#fff"), Some(Rgba::WHITE));
+        assert_eq!(Rgba::from_hex("0000"), Some(Rgba::NONE));
+        assert_eq!(Rgba::from_hex("#614a31").unwrap().to_hex(), "#614A31");
+        assert_eq!(Rgba::from_hex("#614a3180").unwrap().to_hex(), "#614A3180");
+        assert_eq!(Rgba::from_hex("#12345"), None);
+        assert_eq!(Rgba::from_hex("#ggg"), None);
+    }
+}
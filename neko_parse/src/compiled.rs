@@ -0,0 +1,105 @@
+//! The precompiled binary format for NekoMaid UI modules.
+//!
+//! A compiled module is a fully parsed [`Module`], with all of its imports
+//! already resolved, so loading it skips tokenizing and parsing entirely.
+//! Compiled files use the `.neko_uic` extension.
+//!
+//! The encoding is a short header, followed by the module encoded with
+//! [postcard](https://docs.rs/postcard). The header contains a magic number
+//! and a format version, which is bumped whenever the layout of the module
+//! changes, so stale files are rejected instead of being misread.
+
+use crate::module::Module;
+
+/// The magic number at the start of every compiled module.
+pub const MAGIC: [u8; 4] = *b"NEKO";
+
+/// The version of the compiled module format.
+pub const FORMAT_VERSION: u16 = 9;
+
+/// The length of the header preceding the encoded module.
+const HEADER_LEN: usize = MAGIC.len() + size_of::<u16>();
+
+impl Module {
+    /// Encodes this module into the precompiled binary format.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, CompiledModuleError> {
+        let mut bytes = Vec::with_capacity(HEADER_LEN);
+        bytes.extend_from_slice(&MAGIC);
+        bytes.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+        Ok(postcard::to_extend(self, bytes)?)
+    }
+
+    /// Decodes a module from the precompiled binary format.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CompiledModuleError> {
+        if bytes.len() < HEADER_LEN || bytes[.. MAGIC.len()] != MAGIC {
+            return Err(CompiledModuleError::InvalidHeader);
+        }
+
+        let version = u16::from_le_bytes([bytes[MAGIC.len()], bytes[MAGIC.len() + 1]]);
+        if version != FORMAT_VERSION {
+            return Err(CompiledModuleError::UnsupportedVersion {
+                found: version,
+                expected: FORMAT_VERSION,
+            });
+        }
+
+        Ok(postcard::from_bytes(&bytes[HEADER_LEN ..])?)
+    }
+}
+
+/// Errors that can occur while encoding or decoding a compiled module.
+#[derive(Debug, thiserror::Error)]
+pub enum CompiledModuleError {
+    /// The data does not start with the compiled module header.
+    #[error("Not a compiled NekoMaid UI module")]
+    InvalidHeader,
+
+    /// The module was compiled with a different version of the format.
+    #[error("Unsupported compiled module version {found} (expected {expected})")]
+    UnsupportedVersion {
+        /// The version found in the header.
+        found: u16,
+
+        /// The version supported by this crate.
+        expected: u16,
+    },
+
+    /// The module could not be encoded or decoded.
+    #[error("Invalid module encoding: {0}")]
+    Encoding(#[from] postcard::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::NekoMaidParser;
+    use crate::native::NATIVE_WIDGETS;
+
+    #[test]
+    fn round_trips_modules() {
+        let code = include_str!("../../assets/example.neko_ui");
+        let mut parser = NekoMaidParser::tokenize(code).unwrap();
+        for native in NATIVE_WIDGETS.iter() {
+            parser.register_native_widget(native.clone());
+        }
+        let module = parser.finish().unwrap();
+
+        let bytes = module.to_bytes().unwrap();
+        assert_eq!(Module::from_bytes(&bytes).unwrap(), module);
+    }
+
+    #[test]
+    fn rejects_invalid_headers() {
+        assert!(matches!(
+            Module::from_bytes(b"not a module"),
+            Err(CompiledModuleError::InvalidHeader)
+        ));
+
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend_from_slice(&(FORMAT_VERSION + 1).to_le_bytes());
+        assert!(matches!(
+            Module::from_bytes(&bytes),
+            Err(CompiledModuleError::UnsupportedVersion { .. })
+        ));
+    }
+}
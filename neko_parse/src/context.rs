@@ -0,0 +1,625 @@
+//! Temporary context for parsing NekoMaid UI files.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::NekoMaidParseError;
+use crate::asset::{
+    AssetVariables,
+    asset_parameters,
+    collect_variables,
+    resolve_layout_arguments,
+    resolve_value,
+    resolve_widget_arguments,
+};
+use crate::element::{NekoElementBuilder, build_element};
+use crate::font::FontFace;
+use crate::layout::Layout;
+use crate::module::Module;
+use crate::native::AssetKind;
+use crate::property::UnresolvedPropertyValue;
+use crate::style::Style;
+use crate::token::{Token, TokenPosition, TokenType, TokenValue};
+use crate::visibility::{Visibility, hide_private_items};
+use crate::widget::{Widget, check_widget_recursion};
+
+/// A reference to a widget or widget property encountered while parsing.
+///
+/// References are only validated once the entire module has been parsed, so
+/// that widgets may be used before they are defined.
+#[derive(Debug, Clone, PartialEq)]
+enum Reference {
+    /// A reference to a widget by name.
+    Widget {
+        /// The name of the referenced widget.
+        widget: String,
+
+        /// The position of the reference in the source code.
+        position: TokenPosition,
+    },
+
+    /// A reference to a property of a widget.
+    Property {
+        /// The name of the widget the property belongs to.
+        widget: String,
+
+        /// The name of the referenced property.
+        property: String,
+
+        /// The position of the reference in the source code.
+        position: TokenPosition,
+    },
+}
+
+/// Context for parsing NekoMaid UI files.
+pub struct ParseContext {
+    /// A map of defined variables and their values.
+    variables: HashMap<String, UnresolvedPropertyValue>,
+
+    /// A list of defined styles.
+    styles: Vec<Style>,
+
+    /// A list of defined layouts.
+    layouts: Vec<Layout>,
+
+    /// A list of defined named layouts.
+    named_layouts: Vec<(String, Layout)>,
+
+    /// A map of available widgets.
+    widgets: HashMap<String, Widget>,
+
+    /// The names of variables that are not exported to importing modules.
+    private_variables: HashSet<String>,
+
+    /// The names of widgets that are not exported to importing modules.
+    private_widgets: HashSet<String>,
+
+    /// The names of variables declared in this module, rather than imported.
+    declared_variables: HashSet<String>,
+
+    /// The names of widgets declared in this module, rather than imported.
+    declared_widgets: HashSet<String>,
+
+    /// A map of declared font faces.
+    fonts: HashMap<String, FontFace>,
+
+    /// A map of referenced asset paths and the kind of asset they refer to.
+    assets: HashMap<String, AssetKind>,
+
+    /// A list of modules that can be imported.
+    modules: HashMap<String, Module>,
+
+    /// The tokens being parsed, along with the doc comment preceding each
+    /// token, if any.
+    tokens: VecDeque<(Token, Option<String>)>,
+
+    /// A list of elements imported from other modules.
+    imported_elements: Vec<NekoElementBuilder>,
+
+    /// A map of named elements imported from other modules.
+    imported_named_elements: HashMap<String, NekoElementBuilder>,
+
+    /// A list of widget and property references awaiting validation.
+    references: Vec<Reference>,
+
+    /// The names of properties that are accepted by every widget, in addition
+    /// to the properties they define.
+    global_properties: HashSet<String>,
+
+    /// The function used to resolve asset paths relative to the module, if
+    /// any.
+    asset_path_resolver: Option<AssetPathResolver>,
+}
+
+/// A function that resolves an asset path written in a module, such as the
+/// `src` of an image, into the path that is loaded.
+pub type AssetPathResolver = Box<dyn Fn(&str) -> String + Send + Sync>;
+
+impl ParseContext {
+    /// Creates a new, empty [`ParseContext`].
+    ///
+    /// A file retriever function can be provided to enable importing of
+    /// external NekoMaid UI modules.
+    pub fn new(tokens: Vec<Token>) -> Self {
+        let mut documented_tokens = VecDeque::with_capacity(tokens.len());
+        let mut doc: Option<String> = None;
+
+        for token in tokens {
+            if token.token_type == TokenType::DocComment {
+                let line = token.value.as_string().unwrap_or_default();
+                doc = Some(match doc {
+                    Some(doc) => format!("{}\n{}", doc, line),
+                    None => line,
+                });
+                continue;
+            }
+
+            documented_tokens.push_back((token, doc.take()));
+        }
+
+        Self {
+            variables: HashMap::new(),
+            styles: Vec::new(),
+            layouts: Vec::new(),
+            named_layouts: Vec::new(),
+            widgets: HashMap::new(),
+            private_variables: HashSet::new(),
+            private_widgets: HashSet::new(),
+            declared_variables: HashSet::new(),
+            declared_widgets: HashSet::new(),
+            fonts: HashMap::new(),
+            assets: HashMap::new(),
+            modules: HashMap::new(),
+            tokens: documented_tokens,
+            imported_elements: Vec::new(),
+            imported_named_elements: HashMap::new(),
+            references: Vec::new(),
+            global_properties: HashSet::new(),
+            asset_path_resolver: None,
+        }
+    }
+
+    /// Peeks at the next token without advancing the index.
+    pub fn peek(&mut self) -> Option<&Token> {
+        self.tokens.front().map(|(token, _)| token)
+    }
+
+    /// Peeks at the token `n` positions ahead of the next token without
+    /// advancing the index. `peek_nth(0)` is equivalent to `peek()`.
+    pub fn peek_nth(&mut self, n: usize) -> Option<&Token> {
+        self.tokens.get(n).map(|(token, _)| token)
+    }
+
+    /// Returns the doc comment directly preceding the next token, if any.
+    pub fn peek_doc(&self) -> Option<String> {
+        self.tokens.front().and_then(|(_, doc)| doc.clone())
+    }
+
+    /// Attaches a doc comment to the next token, unless it already has one.
+    ///
+    /// This is used to carry doc comments past modifiers, such as `private`,
+    /// to the declaration they document.
+    pub fn attach_doc(&mut self, doc: Option<String>) {
+        if let Some((_, next_doc)) = self.tokens.front_mut()
+            && next_doc.is_none()
+        {
+            *next_doc = doc;
+        }
+    }
+
+    /// Advances to the next token and returns it.
+    pub fn consume(&mut self) -> Result<Token, NekoMaidParseError> {
+        self.tokens
+            .pop_front()
+            .map(|(token, _)| token)
+            .ok_or(NekoMaidParseError::EndOfStream)
+    }
+
+    /// Checks if the next token matches the given type and advances if it does,
+    /// returning the token's value.
+    pub fn maybe_consume(&mut self, test: TokenType) -> Option<TokenValue> {
+        let next = self.peek()?;
+        if next.token_type == test {
+            Some(self.consume().unwrap().value)
+        } else {
+            None
+        }
+    }
+
+    /// Expects the next token to be of the given type, advancing the index and
+    /// returning the token's value. Returns an error if the next token does not
+    /// match the expected type.
+    pub fn expect(&mut self, expected: TokenType) -> Result<TokenValue, NekoMaidParseError> {
+        let next = self.consume()?;
+
+        if next.token_type == expected {
+            Ok(next.value)
+        } else {
+            Err(NekoMaidParseError::UnexpectedToken {
+                expected: vec![expected.type_name().to_string()],
+                found: next.token_type.type_name().to_string(),
+                position: next.position,
+            })
+        }
+    }
+
+    /// Expects the next token to be of the given type, advancing the index and
+    /// returning the token's value as a string. Returns an error if the next
+    /// token does not match the expected type or cannot be converted to a
+    /// string.
+    pub fn expect_as_string(&mut self, expected: TokenType) -> Result<String, NekoMaidParseError> {
+        let next_pos = self.next_position().unwrap_or_default();
+        let next = self.consume()?;
+
+        if next.token_type == expected {
+            match next.value {
+                TokenValue::String(s) => Ok(s),
+                _ => Err(NekoMaidParseError::InvalidTokenValue {
+                    expected: "string".to_string(),
+                    found: format!("{:?}", next.value),
+                    position: next_pos,
+                }),
+            }
+        } else {
+            Err(NekoMaidParseError::UnexpectedToken {
+                expected: vec![expected.type_name().to_string()],
+                found: next.token_type.type_name().to_string(),
+                position: next.position,
+            })
+        }
+    }
+
+    /// Sets the value of a defined variable. If the variable already exists,
+    /// its value and visibility are updated.
+    pub fn set_variable(
+        &mut self,
+        name: String,
+        value: UnresolvedPropertyValue,
+        visibility: Visibility,
+    ) {
+        match visibility {
+            Visibility::Public => self.private_variables.remove(&name),
+            Visibility::Private => self.private_variables.insert(name.clone()),
+        };
+
+        self.variables.insert(name, value);
+    }
+
+    /// Declares a variable in this module. Unlike imported variables, asset
+    /// paths in its value are resolved relative to this module.
+    pub fn declare_variable(
+        &mut self,
+        name: String,
+        value: UnresolvedPropertyValue,
+        visibility: Visibility,
+    ) {
+        self.declared_variables.insert(name.clone());
+        self.set_variable(name, value, visibility);
+    }
+
+    /// Gets the value of a defined variable by its name.
+    pub fn get_variable(&self, name: &str) -> Option<&UnresolvedPropertyValue> {
+        self.variables.get(name)
+    }
+
+    /// Converts this parse context into a [`Module`].
+    ///
+    /// All widget and property references are validated at this point, as
+    /// every declaration in the module is now known.
+    pub fn into_module(mut self) -> NekoResult<Module> {
+        self.validate_references()?;
+        check_widget_recursion(&self.widgets)?;
+        self.resolve_asset_variables();
+
+        let mut elements = self.imported_elements;
+
+        for layout in self.layouts {
+            let element = build_element(&self.styles, &self.widgets, layout, None)?;
+            elements.push(element);
+        }
+
+        let mut named_elements = self.imported_named_elements;
+
+        for (name, layout) in self.named_layouts {
+            let element = build_element(&self.styles, &self.widgets, layout, None)?;
+            named_elements.insert(name, element);
+        }
+
+        Ok(Module {
+            variables: self.variables,
+            styles: self.styles,
+            widgets: self.widgets,
+            elements,
+            named_elements,
+            private_variables: self.private_variables,
+            private_widgets: self.private_widgets,
+            fonts: self.fonts,
+            assets: self.assets,
+        })
+    }
+
+    /// Gets the next token position in the token stream, or `None` if there are
+    /// no more tokens.
+    pub fn next_position(&mut self) -> Option<TokenPosition> {
+        self.peek().map(|t| t.position)
+    }
+
+    /// Adds a widget definition to the list of available widgets.
+    pub fn add_widget(&mut self, widget: Widget, visibility: Visibility) {
+        let name = widget.name().to_string();
+
+        match visibility {
+            Visibility::Public => self.private_widgets.remove(&name),
+            Visibility::Private => self.private_widgets.insert(name.clone()),
+        };
+
+        self.widgets.insert(name, widget);
+    }
+
+    /// Declares a widget in this module. Unlike imported widgets, asset paths
+    /// in its property defaults are resolved relative to this module.
+    pub fn declare_widget(&mut self, widget: Widget, visibility: Visibility) {
+        self.declared_widgets.insert(widget.name().to_string());
+        self.add_widget(widget, visibility);
+    }
+
+    /// Gets the widget definition for the given widget name, if it exists.
+    pub fn get_widget(&self, widget: &str) -> Option<&Widget> {
+        self.widgets.get(widget)
+    }
+
+    /// Adds a property that is accepted by every widget, in addition to the
+    /// properties they define.
+    pub fn add_global_property(&mut self, property: String) {
+        self.global_properties.insert(property);
+    }
+
+    /// Sets the function used to resolve asset paths written in this module.
+    pub fn set_asset_path_resolver(&mut self, resolver: AssetPathResolver) {
+        self.asset_path_resolver = Some(resolver);
+    }
+
+    /// Resolves an asset path written in this module. Paths are returned as
+    /// they are if no resolver is set.
+    pub fn resolve_asset_path(&self, path: &str) -> String {
+        match &self.asset_path_resolver {
+            Some(resolver) => resolver(path),
+            None => path.to_string(),
+        }
+    }
+
+    /// Resolves the asset paths that reach native widgets through variables,
+    /// such as a path passed to a custom widget that uses it as the `src` of
+    /// an image.
+    ///
+    /// Only the widgets, layouts, and variables declared in this module are
+    /// resolved, as imported ones were resolved relative to their own module.
+    fn resolve_asset_variables(&mut self) {
+        let parameters = asset_parameters(&self.widgets);
+        let mut variables = AssetVariables::new();
+
+        let mut widgets = std::mem::take(&mut self.widgets);
+        let mut layouts = std::mem::take(&mut self.layouts);
+        let mut named_layouts = std::mem::take(&mut self.named_layouts);
+        let mut resolve = |path: &mut String, kind| {
+            if let Some(resolver) = &self.asset_path_resolver {
+                *path = resolver(path);
+            }
+            self.assets.insert(path.clone(), kind);
+        };
+
+        for (name, widget) in &mut widgets {
+            let Widget::Custom(widget) = widget else {
+                continue;
+            };
+            if !self.declared_widgets.contains(name) {
+                continue;
+            }
+
+            let mut used = AssetVariables::new();
+            resolve_widget_arguments(&mut widget.layout, &parameters, &mut used, &mut resolve);
+
+            for (variable, kind) in used {
+                match widget.default_properties.get_mut(&variable) {
+                    Some(value) => resolve_value(value, kind, &mut variables, &mut resolve),
+                    None => {
+                        variables.insert(variable, kind);
+                    }
+                }
+            }
+        }
+
+        for layout in layouts
+            .iter_mut()
+            .chain(named_layouts.iter_mut().map(|(_, layout)| layout))
+        {
+            resolve_layout_arguments(layout, &parameters, &mut variables, &mut resolve);
+        }
+
+        for style in &self.styles {
+            if let Some(part) = style.selector().hierarchy.last() {
+                collect_variables(
+                    &part.widget,
+                    style.unresolved_properties(),
+                    &parameters,
+                    &mut variables,
+                );
+            }
+        }
+
+        // Module-level variables may refer to each other, so the variables
+        // they refer to are resolved in turn.
+        let mut pending: Vec<_> = variables.into_iter().collect();
+        let mut visited = HashSet::new();
+        while let Some((name, kind)) = pending.pop() {
+            if !visited.insert(name.clone()) || !self.declared_variables.contains(&name) {
+                continue;
+            }
+
+            let Some(mut value) = self.variables.remove(&name) else {
+                continue;
+            };
+
+            let mut referenced = AssetVariables::new();
+            resolve_value(&mut value, kind, &mut referenced, &mut resolve);
+            self.variables.insert(name, value);
+            pending.extend(referenced);
+        }
+
+        self.widgets = widgets;
+        self.layouts = layouts;
+        self.named_layouts = named_layouts;
+    }
+
+    /// Adds a font face declaration. If a font face with the same name already
+    /// exists, it is replaced.
+    pub fn add_font(&mut self, font: FontFace) {
+        self.fonts.insert(font.name.clone(), font);
+    }
+
+    /// Records an asset path referenced by the module. If the path was already
+    /// recorded, its kind is replaced.
+    pub fn add_asset(&mut self, path: String, kind: AssetKind) {
+        self.assets.insert(path, kind);
+    }
+
+    /// Records a reference to a widget. The widget does not need to be defined
+    /// yet; the reference is validated when the module is finished.
+    pub fn reference_widget(&mut self, widget: String, position: TokenPosition) {
+        self.references.push(Reference::Widget { widget, position });
+    }
+
+    /// Records a reference to a property of a widget. The reference is
+    /// validated when the module is finished.
+    pub fn reference_property(
+        &mut self,
+        widget: String,
+        property: String,
+        position: TokenPosition,
+    ) {
+        self.references.push(Reference::Property {
+            widget,
+            property,
+            position,
+        });
+    }
+
+    /// Validates all recorded widget and property references against the
+    /// widgets available in this context.
+    fn validate_references(&self) -> NekoResult<()> {
+        for reference in &self.references {
+            match reference {
+                Reference::Widget { widget, position } => {
+                    if self.get_widget(widget).is_none() {
+                        return Err(NekoMaidParseError::UnknownWidget {
+                            widget: widget.clone(),
+                            position: *position,
+                        });
+                    }
+                }
+                Reference::Property {
+                    widget,
+                    property,
+                    position,
+                } => {
+                    let Some(definition) = self.get_widget(widget) else {
+                        return Err(NekoMaidParseError::UnknownWidget {
+                            widget: widget.clone(),
+                            position: *position,
+                        });
+                    };
+
+                    if !definition.has_property(property)
+                        && !self.global_properties.contains(property)
+                    {
+                        return Err(NekoMaidParseError::InvalidProperty {
+                            property: property.clone(),
+                            widget: widget.clone(),
+                            position: *position,
+                        });
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Adds a style definition to the list of styles. If two styles have equal
+    /// selectors, visibility, and scope, they will be merged together. In the
+    /// case of property conflicts, the properties of the later-added style
+    /// will take precedence.
+    pub fn add_style(&mut self, style: Style) {
+        for existing_style in &mut self.styles {
+            if existing_style.selector() == style.selector()
+                && existing_style.visibility() == style.visibility()
+                && existing_style.scope() == style.scope()
+            {
+                existing_style.merge(style);
+                return;
+            }
+        }
+
+        self.styles.push(style);
+    }
+
+    /// Adds a layout to the list of elements.
+    pub fn add_layout(&mut self, layout: Layout) {
+        self.layouts.push(layout);
+    }
+
+    /// Adds a named layout to the list of elements. If a layout with the same
+    /// name already exists, it is replaced when the module is finished.
+    pub fn add_named_layout(&mut self, name: String, layout: Layout) {
+        self.named_layouts.push((name, layout));
+    }
+
+    /// Attempts to import a module by its name. The module must have been
+    /// previously added to this context via [`add_module`].
+    ///
+    /// Importing a module will destroy temporary metadata associated with it,
+    /// and prevent it from being imported again.
+    ///
+    /// Private variables and widgets of the module are imported under names
+    /// that cannot be referenced from this context, and private styles of the
+    /// module only apply to the elements created by the widgets of the module.
+    pub fn import_module(
+        &mut self,
+        name: &str,
+        pos: TokenPosition,
+    ) -> Result<(), NekoMaidParseError> {
+        let Some(mut module) = self.modules.remove(name) else {
+            return Err(NekoMaidParseError::ModuleNotFound {
+                name: name.to_string(),
+                position: pos,
+            });
+        };
+
+        hide_private_items(&mut module, name);
+
+        for (var_name, var_value) in module.variables {
+            let visibility = match module.private_variables.contains(&var_name) {
+                true => Visibility::Private,
+                false => Visibility::Public,
+            };
+            self.declared_variables.remove(&var_name);
+            self.set_variable(var_name, var_value, visibility);
+        }
+
+        for style in module.styles {
+            self.add_style(style);
+        }
+
+        for font in module.fonts.into_values() {
+            self.add_font(font);
+        }
+
+        for (path, kind) in module.assets {
+            self.add_asset(path, kind);
+        }
+
+        self.imported_elements.extend(module.elements);
+        self.imported_named_elements.extend(module.named_elements);
+
+        for (widget_name, widget) in module.widgets {
+            let visibility = match module.private_widgets.contains(&widget_name) {
+                true => Visibility::Private,
+                false => Visibility::Public,
+            };
+            self.declared_widgets.remove(&widget_name);
+            self.add_widget(widget, visibility);
+        }
+
+        Ok(())
+    }
+
+    /// Adds a module to this context under the given name.
+    ///
+    /// This does not import the module; it simply makes it available for import
+    /// within this context if requested.
+    pub fn add_module(&mut self, name: String, module: Module) {
+        self.modules.insert(name, module);
+    }
+}
+
+/// A specialized result type for NekoMaid parsing operations.
+pub type NekoResult<T> = Result<T, NekoMaidParseError>;
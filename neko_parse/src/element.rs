@@ -1,22 +1,22 @@
 //! A module for parsing and representing NekoMaid UI finalized elements.
 
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
-use bevy::ecs::component::Component;
-use bevy::platform::collections::{HashMap, HashSet};
-
-use crate::parse::NekoMaidParseError;
-use crate::parse::class::{ClassPath, ClassSet};
-use crate::parse::context::NekoResult;
-use crate::parse::layout::Layout;
-use crate::parse::property::UnresolvedPropertyValue;
-use crate::parse::style::Style;
-use crate::parse::token::TokenPosition;
-use crate::parse::value::PropertyValue;
-use crate::parse::widget::{NativeWidget, Widget, WidgetLayout};
+use crate::NekoMaidParseError;
+use crate::class::{ClassPath, ClassSet};
+use crate::context::NekoResult;
+use crate::layout::Layout;
+use crate::property::{UnresolvedPropertyValue, rename_variables};
+use crate::shorthand::{self, expand_shorthands};
+use crate::style::Style;
+use crate::token::TokenPosition;
+use crate::value::PropertyValue;
+use crate::widget::{NativeWidget, Widget, WidgetLayout};
 
 /// A temporary builder for NekoMaid UI elements for easier construction.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NekoElementBuilder {
     /// The native widget associated with this element.
     pub native_widget: NativeWidget,
@@ -28,8 +28,21 @@ pub struct NekoElementBuilder {
     pub children: Vec<NekoElementBuilder>,
 }
 
+impl NekoElementBuilder {
+    /// Renames all variables referenced by this element and its children,
+    /// using the given map of old names to new names.
+    pub(crate) fn rename_variables(&mut self, renames: &HashMap<String, String>) {
+        self.element.rename_variables(renames);
+
+        for child in &mut self.children {
+            child.rename_variables(renames);
+        }
+    }
+}
+
 /// A component representing a NekoMaid UI element.
-#[derive(Debug, Component, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NekoElement {
     /// The class path of this element.
     pub(crate) classpath: ClassPath,
@@ -93,7 +106,7 @@ impl NekoElement {
 
     /// Sets a property directly on this element, overriding all styles.
     pub fn set_property(&mut self, name: String, value: PropertyValue) {
-        self.properties.insert(name, value);
+        shorthand::set_property(&mut self.properties, name, value);
     }
 
     /// Gets a property defined by the current style of this element.
@@ -102,19 +115,8 @@ impl NekoElement {
     /// element. It is recommended to only check for properties when
     /// necessary (e.g. on class changes or style updates).
     pub fn get_property(&self, name: &str) -> Option<&PropertyValue> {
-        if let Some(value) = self.properties.get(name) {
-            return Some(value);
-        };
-
-        for style in &self.styles {
-            if let Some(value) = style.get_property(name)
-                && self.classpath.matches(style.selector())
-            {
-                return Some(value);
-            }
-        }
-
-        self.default_properties.get(name)
+        self.get_property_no_default(name)
+            .or_else(|| self.default_properties.get(name))
     }
 
     /// Resolve properties for this element
@@ -123,10 +125,13 @@ impl NekoElement {
             style.resolve(variables)?;
         }
 
+        // The arguments of nested widgets may refer to the properties of the
+        // widgets around them, so each variable is resolved after the local
+        // variables it refers to, which shadow the outer ones.
         let mut variables = variables.clone();
-        for (name, value) in &self.variables {
-            let prop = value.resolve(&variables)?;
-            variables.insert(name.clone(), prop);
+        for name in local_order(&self.variables) {
+            let prop = self.variables[name].resolve(&variables)?;
+            variables.insert(name.to_string(), prop);
         }
 
         for (name, value) in &self.unresolved_properties {
@@ -134,48 +139,46 @@ impl NekoElement {
             self.properties.insert(name.clone(), prop);
         }
 
+        expand_shorthands(&mut self.properties);
         Ok(())
     }
 
-    /// Attempts to get a property and automatically convert it to the desired
-    /// type. If the property is not found, returns the default value for the
-    /// type.
-    pub fn get_as<'a, O>(&'a self, name: &str) -> O
-    where
-        O: From<&'a PropertyValue> + Default,
-    {
-        self.get_property(name).map(Into::into).unwrap_or_default()
-    }
+    /// Renames all variables referenced by this element, using the given map
+    /// of old names to new names.
+    ///
+    /// Variables shadowed by the widget properties in scope for this element
+    /// are not renamed.
+    pub(crate) fn rename_variables(&mut self, renames: &HashMap<String, String>) {
+        for style in &mut self.styles {
+            style.rename_variables(renames);
+        }
 
-    /// Attempts to get a property and automatically convert it to the desired
-    /// type. If the property is not found, returns the provided default value.
-    pub fn get_as_or<'a, O>(&'a self, name: &str, def: O) -> O
-    where
-        O: From<&'a PropertyValue>,
-    {
-        self.get_property(name).map(Into::into).unwrap_or(def)
+        let renames = renames
+            .iter()
+            .filter(|(name, _)| !self.variables.contains_key(*name))
+            .map(|(name, new_name)| (name.clone(), new_name.clone()))
+            .collect();
+
+        rename_variables(&mut self.variables, &renames);
+        rename_variables(&mut self.unresolved_properties, &renames);
     }
 
-    /// Attempts to get a property, ignoring all default values provided by the
-    /// native widget, and automatically convert it to the desired type. If the
-    /// property is not found, returns the provided value.
-    pub fn get_no_default<'a, O>(&'a self, name: &str, def: O) -> O
-    where
-        O: From<&'a PropertyValue>,
-    {
+    /// Gets a property, ignoring all default values provided by the native
+    /// widget. Explicitly set properties take priority over styles.
+    pub fn get_property_no_default(&self, name: &str) -> Option<&PropertyValue> {
         if let Some(value) = self.properties.get(name) {
-            return value.into();
+            return Some(value);
         };
 
         for style in &self.styles {
             if let Some(value) = style.get_property(name)
                 && self.classpath.matches(style.selector())
             {
-                return value.into();
+                return Some(value);
             }
         }
 
-        def
+        None
     }
 }
 
@@ -183,6 +186,56 @@ impl NekoElement {
 pub fn build_element(
     styles: &[Style],
     widgets: &HashMap<String, Widget>,
+    layout: Layout,
+    classpath: Option<ClassPath>,
+) -> NekoResult<NekoElementBuilder> {
+    let scope = StyleScope {
+        styles,
+        module: None,
+    };
+    build_scoped_element(scope, widgets, layout, classpath)
+}
+
+/// The styles available while building an element, along with the module
+/// whose widget creates the element.
+#[derive(Clone, Copy)]
+struct StyleScope<'a> {
+    /// All styles available to the module being built.
+    styles: &'a [Style],
+
+    /// The module defining the widget that creates the element, or `None` for
+    /// the elements of the module being built.
+    module: Option<&'a str>,
+}
+
+impl<'a> StyleScope<'a> {
+    /// Returns the scope of the elements created by a custom widget defined in
+    /// the given module.
+    fn within<'b>(self, module: Option<&'b str>) -> StyleScope<'b>
+    where
+        'a: 'b,
+    {
+        StyleScope {
+            styles: self.styles,
+            module,
+        }
+    }
+
+    /// Adds the styles that apply within this scope to the given element.
+    fn apply(self, element: &mut NekoElement) {
+        for style in self.styles {
+            if style.applies_in(self.module) {
+                element.try_add_style(style);
+            }
+        }
+    }
+}
+
+/// Builds a [`NekoElementBuilder`] from the given layout, created within the
+/// given scope.
+fn build_scoped_element(
+    scope: StyleScope,
+    widgets: &HashMap<String, Widget>,
     mut layout: Layout,
     classpath: Option<ClassPath>,
 ) -> NekoResult<NekoElementBuilder> {
@@ -210,8 +263,8 @@ pub fn build_element(
 
             let mut children = Vec::new();
             for child in layout.children {
-                children.push(build_element(
-                    styles,
+                children.push(build_scoped_element(
+                    scope,
                     widgets,
                     child,
                     Some(classpath.clone()),
@@ -227,9 +280,7 @@ pub fn build_element(
                 default_properties: native_widget.default_properties.clone(),
             };
 
-            for style in styles {
-                element.try_add_style(style);
-            }
+            scope.apply(&mut element);
 
             Ok(NekoElementBuilder {
                 element,
@@ -246,7 +297,8 @@ pub fn build_element(
 
             build_widget(
                 &local_variables,
-                styles,
+                scope.within(custom_widget.scope.as_deref()),
+                scope,
                 widgets,
                 custom_widget.layout,
                 &mut layout.children,
@@ -256,11 +308,56 @@ pub fn build_element(
     }
 }
 
-/// Builds a [`NekoElementBuilder`] from the given styles and custom widget
-/// layout.
+/// Returns the names of the given local variables, ordered so that each comes
+/// after the other local variables it refers to.
+///
+/// A variable referring to itself, or to a variable that refers back to it,
+/// refers to the variable of the same name in the outer scopes instead.
+/// Variables are visited by name, so the order does not depend on the order
+/// of the map.
+fn local_order(locals: &HashMap<String, UnresolvedPropertyValue>) -> Vec<&str> {
+    /// Appends the given variable to the order, after its dependencies.
+    fn visit<'a>(
+        name: &'a str,
+        locals: &'a HashMap<String, UnresolvedPropertyValue>,
+        visited: &mut HashSet<&'a str>,
+        order: &mut Vec<&'a str>,
+    ) {
+        if !visited.insert(name) {
+            return;
+        }
+
+        let mut dependencies = locals[name].variables();
+        dependencies.sort_unstable();
+        for dependency in dependencies {
+            if let Some((dependency, _)) = locals.get_key_value(dependency) {
+                visit(dependency, locals, visited, order);
+            }
+        }
+
+        order.push(name);
+    }
+
+    let mut names = locals.keys().map(String::as_str).collect::<Vec<_>>();
+    names.sort_unstable();
+
+    let mut visited = HashSet::new();
+    let mut order = Vec::with_capacity(names.len());
+    for name in names {
+        visit(name, locals, &mut visited, &mut order);
+    }
+    order
+}
+
+/// Builds a [`NekoElementBuilder`] from the given custom widget layout.
+///
+/// The nodes of the layout are created within `scope`, while the children
+/// passed to the widget, which are placed at its output, are created within
+/// `outer_scope`.
 fn build_widget(
     variables: &HashMap<String, UnresolvedPropertyValue>,
-    styles: &[Style],
+    scope: StyleScope,
+    outer_scope: StyleScope,
     widgets: &HashMap<String, Widget>,
     layout: WidgetLayout,
     original_children: &mut Vec<Layout>,
@@ -291,8 +388,9 @@ fn build_widget(
             let mut children = Vec::new();
             for child in layout.children {
                 children.push(build_widget(
-                    &variables,
-                    styles,
+                    variables,
+                    scope,
+                    outer_scope,
                     widgets,
                     child,
                     original_children,
@@ -302,8 +400,8 @@ fn build_widget(
 
             if layout.is_output {
                 for child in original_children.drain(..) {
-                    children.push(build_element(
-                        styles,
+                    children.push(build_scoped_element(
+                        outer_scope,
                         widgets,
                         child,
                         Some(classpath.clone()),
@@ -320,9 +418,7 @@ fn build_widget(
                 default_properties: native_widget.default_properties.clone(),
             };
 
-            for style in styles {
-                element.try_add_style(style);
-            }
+            scope.apply(&mut element);
 
             for (name, value) in layout.properties {
                 element
@@ -349,7 +445,8 @@ fn build_widget(
 
             build_widget(
                 &local_variables,
-                styles,
+                scope.within(custom_widget.scope.as_deref()),
+                outer_scope,
                 widgets,
                 custom_widget.layout,
                 original_children,
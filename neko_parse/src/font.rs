@@ -0,0 +1,121 @@
+//! Font face declarations, which give names to font files.
+//!
+//! A font face is declared at the top level of a module, and can then be used
+//! by name in the `font` property of text widgets:
+//!
+//! ```text
+//! font-face body {
+//!   src: "fonts/Inter.ttf";
+//! }
+//!
+//! style p {
+//!   font: body;
+//! }
+//! ```
+
+use crate::NekoMaidParseError;
+use crate::context::{NekoResult, ParseContext};
+use crate::native::AssetKind;
+use crate::property::{UnresolvedPropertyValue, parse_unresolved_property};
+use crate::token::TokenType;
+use crate::value::PropertyValue;
+
+/// A named font, declared with `font-face`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FontFace {
+    /// The name of the font, as used by the `font` property.
+    pub name: String,
+
+    /// The asset path of the font file.
+    pub src: String,
+
+    /// The doc comment of the declaration, if any.
+    pub doc: Option<String>,
+}
+
+/// Parses a font face declaration from the input and returns a [`FontFace`].
+pub fn parse_font_face(ctx: &mut ParseContext) -> NekoResult<FontFace> {
+    let doc = ctx.peek_doc();
+    ctx.expect(TokenType::FontFaceKeyword)?;
+
+    let position = ctx.next_position().unwrap_or_default();
+    let name = ctx.expect_as_string(TokenType::Identifier)?;
+    ctx.expect(TokenType::OpenBrace)?;
+
+    let mut src = None;
+    while ctx.maybe_consume(TokenType::CloseBrace).is_none() {
+        let property_position = ctx.next_position().unwrap_or_default();
+        let property = parse_unresolved_property(ctx)?;
+
+        match (property.name.as_str(), property.value) {
+            ("src", UnresolvedPropertyValue::Constant(PropertyValue::String(path))) => {
+                src = Some(path);
+            }
+            ("src", value) => {
+                return Err(NekoMaidParseError::InvalidTokenValue {
+                    expected: "string".to_string(),
+                    found: value.to_string(),
+                    position: property_position,
+                });
+            }
+            _ => {
+                return Err(NekoMaidParseError::InvalidProperty {
+                    property: property.name,
+                    widget: "font-face".to_string(),
+                    position: property_position,
+                });
+            }
+        }
+    }
+
+    let Some(src) = src else {
+        return Err(NekoMaidParseError::MissingFontSource {
+            font: name,
+            position,
+        });
+    };
+
+    let src = ctx.resolve_asset_path(&src);
+    ctx.add_asset(src.clone(), AssetKind::Font);
+
+    Ok(FontFace { name, src, doc })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::NekoMaidParser;
+
+    #[test]
+    fn font_faces_are_parsed() {
+        let code = r#"
+            /// The body font.
+            font-face body {
+              src: "fonts/Inter.ttf";
+            }
+        "#;
+
+        let module = NekoMaidParser::tokenize(code).unwrap().finish().unwrap();
+        assert_eq!(
+            module.fonts["body"],
+            FontFace {
+                name: "body".to_string(),
+                src: "fonts/Inter.ttf".to_string(),
+                doc: Some("The body font.".to_string()),
+            }
+        );
+
+        let code = "font-face body { size: 12; }";
+        assert!(matches!(
+            NekoMaidParser::tokenize(code).unwrap().finish(),
+            Err(NekoMaidParseError::InvalidProperty { property, .. }) if property == "size"
+        ));
+
+        let code = "font-face body {}";
+        assert!(matches!(
+            NekoMaidParser::tokenize(code).unwrap().finish(),
+            Err(NekoMaidParseError::MissingFontSource { font, .. }) if font == "body"
+        ));
+    }
+}
@@ -0,0 +1,203 @@
+//! This module implements a canonical formatter for NekoMaid UI files.
+//!
+//! Files are parsed into a lossless [`SyntaxTree`], which keeps all comments
+//! and the original order of declarations, and then printed back with
+//! consistent indentation and spacing.
+//!
+//! ```
+//! use neko_parse::format::{FormatOptions, format};
+//!
+//! let code = "layout div{width:100%;}";
+//! let formatted = format(code, &FormatOptions::default()).unwrap();
+//! assert_eq!(formatted, "layout div {\n  width: 100%;\n}\n");
+//! ```
+
+use crate::context::NekoResult;
+use crate::format::printer::print_tree;
+use crate::format::syntax::SyntaxTree;
+
+pub mod printer;
+pub mod syntax;
+
+/// Options for formatting NekoMaid UI files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatOptions {
+    /// The number of spaces used for each level of indentation.
+    pub indent: usize,
+
+    /// The order in which properties are printed within a block.
+    pub property_order: PropertyOrder,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            indent: 2,
+            property_order: PropertyOrder::Preserve,
+        }
+    }
+}
+
+/// The order in which properties are printed within a block.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PropertyOrder {
+    /// Properties are printed in the order they were written.
+    #[default]
+    Preserve,
+
+    /// Consecutive properties are sorted alphabetically by name.
+    Alphabetical,
+}
+
+/// Formats the given NekoMaid UI code using the given options.
+///
+/// Returns an error if the code cannot be tokenized, or if its braces are
+/// unbalanced. The code is otherwise not required to be valid.
+pub fn format(code: &str, options: &FormatOptions) -> NekoResult<String> {
+    let tree = SyntaxTree::parse(code)?;
+    Ok(print_tree(&tree, options))
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::NekoMaidParser;
+    use crate::native::NATIVE_WIDGETS;
+
+    #[test]
+    fn format_example_is_idempotent() {
+        let code = include_str!("../../../assets/example.neko_ui");
+        let options = FormatOptions::default();
+
+        let formatted = format(code, &options).unwrap();
+        assert_eq!(format(&formatted, &options).unwrap(), formatted);
+    }
+
+    #[test]
+    fn format_preserves_comments() {
+        let code = r#"
+// header comment
+
+
+import "lib";
+/// A title.
+def title {
+    var text = "";   // the text
+    /* the layout */ layout p { text : $text ; output; }
+}
+style div +a !b{color:#fff;}
+"#;
+
+        let expected = r#"// header comment
+
+import "lib";
+/// A title.
+def title {
+  var text = ""; // the text
+  /* the layout */
+  layout p {
+    text: $text;
+    output;
+  }
+}
+style div +a !b {
+  color: #fff;
+}
+"#;
+
+        assert_eq!(format(code, &FormatOptions::default()).unwrap(), expected);
+    }
+
+    #[test]
+    fn format_sorts_properties() {
+        let code = r#"
+layout div {
+  width: 10px; // width
+  height: 5px;
+
+  margin: 0;
+  class menu;
+  display: flex;
+}
+"#;
+
+        let expected = r#"layout div {
+  height: 5px;
+  width: 10px; // width
+
+  margin: 0;
+  class menu;
+  display: flex;
+}
+"#;
+
+        let options = FormatOptions {
+            indent: 2,
+            property_order: PropertyOrder::Alphabetical,
+        };
+
+        assert_eq!(format(code, &options).unwrap(), expected);
+    }
+
+    #[test]
+    fn format_keeps_shorthands_in_source_order() {
+        let code = r#"
+style div {
+  padding-left: 2px;
+  padding: 4px;
+  width: 10px;
+  border-color-top: #000;
+  border: 1px #fff;
+  margin: 0;
+  margin-top: 8px;
+}
+"#;
+
+        let expected = r#"style div {
+  border-color-top: #000;
+  border: 1px #fff;
+  margin: 0;
+  margin-top: 8px;
+  padding-left: 2px;
+  padding: 4px;
+  width: 10px;
+}
+"#;
+
+        let options = FormatOptions {
+            indent: 2,
+            property_order: PropertyOrder::Alphabetical,
+        };
+
+        let formatted = format(code, &options).unwrap();
+        assert_eq!(formatted, expected);
+
+        let properties = |code: &str| {
+            let mut parser = NekoMaidParser::tokenize(code).unwrap();
+            for native in NATIVE_WIDGETS.iter() {
+                parser.register_native_widget(native.clone());
+            }
+            parser.finish().unwrap().styles[0].properties().clone()
+        };
+        assert_eq!(properties(&formatted), properties(code));
+    }
+
+    #[test]
+    fn format_spaces_function_arguments() {
+        let code = "layout div { grid-template-rows: repeat( 3 ,minmax(10px,1fr) ) auto; }";
+        let expected = r#"layout div {
+  grid-template-rows: repeat(3, minmax(10px, 1fr)) auto;
+}
+"#;
+
+        assert_eq!(format(code, &FormatOptions::default()).unwrap(), expected);
+    }
+
+    #[test]
+    fn format_rejects_unbalanced_braces() {
+        assert!(format("layout div {", &FormatOptions::default()).is_err());
+        assert!(format("layout div {}}", &FormatOptions::default()).is_err());
+    }
+}
@@ -0,0 +1,160 @@
+//! Prints a [`SyntaxTree`] back into canonically formatted source code.
+
+use crate::format::syntax::{SyntaxNode, SyntaxTree, is_comment};
+use crate::format::{FormatOptions, PropertyOrder};
+use crate::shorthand::properties_overlap;
+use crate::token::TokenType;
+use crate::tokenizer::SourceToken;
+
+/// Prints the given syntax tree using the given formatting options.
+pub fn print_tree(tree: &SyntaxTree, options: &FormatOptions) -> String {
+    let mut out = String::new();
+    print_nodes(&tree.nodes, 0, options, &mut out);
+    out
+}
+
+/// Prints a list of nodes at the given indentation depth.
+fn print_nodes(nodes: &[SyntaxNode], depth: usize, options: &FormatOptions, out: &mut String) {
+    let indent = " ".repeat(depth * options.indent);
+
+    for node in order_nodes(nodes, options) {
+        match node {
+            SyntaxNode::Statement(tokens) => {
+                out.push_str(&indent);
+                out.push_str(&join_tokens(tokens, &indent));
+                out.push('\n');
+            }
+            SyntaxNode::Block { header, children } => {
+                out.push_str(&indent);
+                out.push_str(&join_tokens(header, &indent));
+
+                if children.is_empty() {
+                    out.push_str(" {}\n");
+                } else {
+                    out.push_str(" {\n");
+                    print_nodes(children, depth + 1, options, out);
+                    out.push_str(&indent);
+                    out.push_str("}\n");
+                }
+            }
+            SyntaxNode::Comment {
+                token,
+                trailing: true,
+            } if out.ends_with('\n') => {
+                out.pop();
+                out.push(' ');
+                out.push_str(&token.text);
+                out.push('\n');
+            }
+            SyntaxNode::Comment { token, .. } => {
+                out.push_str(&indent);
+                out.push_str(&token.text);
+                out.push('\n');
+            }
+            SyntaxNode::BlankLine => out.push('\n'),
+        }
+    }
+}
+
+/// Returns the given nodes in the order they should be printed.
+///
+/// When sorting properties, only runs of consecutive property statements are
+/// reordered, with each property keeping its trailing comment. Blank lines and
+/// standalone comments act as separators between runs. Properties that set
+/// the same value, such as a shorthand and its longhands, keep their source
+/// order, as the later one takes priority.
+fn order_nodes<'a>(nodes: &'a [SyntaxNode], options: &FormatOptions) -> Vec<&'a SyntaxNode> {
+    if options.property_order == PropertyOrder::Preserve {
+        return nodes.iter().collect();
+    }
+
+    let mut ordered = Vec::with_capacity(nodes.len());
+    let mut run: Vec<Vec<&SyntaxNode>> = Vec::new();
+
+    for node in nodes {
+        match node {
+            SyntaxNode::Statement(_) if node.property_name().is_some() => run.push(vec![node]),
+            SyntaxNode::Comment { trailing: true, .. } if !run.is_empty() => {
+                run.last_mut().unwrap().push(node);
+            }
+            _ => {
+                flush_run(&mut run, &mut ordered);
+                ordered.push(node);
+            }
+        }
+    }
+
+    flush_run(&mut run, &mut ordered);
+    ordered
+}
+
+/// Sorts a run of property statements by name, and appends them to the list of
+/// ordered nodes.
+///
+/// Each step takes the first property by name that no earlier overlapping
+/// property is still waiting for.
+fn flush_run<'a>(run: &mut Vec<Vec<&'a SyntaxNode>>, ordered: &mut Vec<&'a SyntaxNode>) {
+    let name = |property: &[&'a SyntaxNode]| -> &'a str {
+        property[0].property_name().unwrap_or_default()
+    };
+
+    while !run.is_empty() {
+        let next = (0 .. run.len())
+            .filter(|&i| {
+                !run[.. i]
+                    .iter()
+                    .any(|earlier| properties_overlap(name(earlier), name(&run[i])))
+            })
+            .min_by_key(|&i| name(&run[i]))
+            .unwrap();
+
+        ordered.extend(run.remove(next));
+    }
+}
+
+/// Joins the tokens of a statement or block header with canonical spacing.
+///
+/// Line comments within a statement force a line break, which continues at
+/// the given indentation.
+fn join_tokens(tokens: &[SourceToken], indent: &str) -> String {
+    let mut out = String::new();
+    let mut previous: Option<TokenType> = None;
+
+    for token in tokens {
+        let token_type = token.token.token_type;
+
+        match previous {
+            None => {}
+            Some(TokenType::Comment) => {
+                out.push('\n');
+                out.push_str(indent);
+            }
+            Some(previous) if needs_space(previous, token_type) => out.push(' '),
+            Some(_) => {}
+        }
+
+        out.push_str(&token.text);
+        previous = Some(token_type);
+    }
+
+    out
+}
+
+/// Returns true if a space should be printed between two adjacent tokens.
+fn needs_space(previous: TokenType, next: TokenType) -> bool {
+    if is_comment(previous) || is_comment(next) {
+        return true;
+    }
+
+    !matches!(
+        previous,
+        TokenType::Plus | TokenType::Exclamation | TokenType::OpenParen
+    ) && !matches!(
+        next,
+        TokenType::Semicolon
+            | TokenType::Colon
+            | TokenType::OpenParen
+            | TokenType::CloseParen
+            | TokenType::Comma
+    )
+}
@@ -0,0 +1,177 @@
+//! A lossless syntax tree for NekoMaid UI files.
+//!
+//! Unlike the parser in [`crate`], the syntax tree does not interpret
+//! the meaning of any declarations. It only records the structure of the file,
+//! statements and blocks, along with all comments and the order in which
+//! everything was written.
+
+use std::iter::Peekable;
+use std::vec::IntoIter;
+
+use crate::NekoMaidParseError;
+use crate::context::NekoResult;
+use crate::token::TokenType;
+use crate::tokenizer::{SourceToken, Tokenizer};
+
+/// A lossless syntax tree of a NekoMaid UI file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyntaxTree {
+    /// The top-level nodes of the file.
+    pub nodes: Vec<SyntaxNode>,
+}
+
+impl SyntaxTree {
+    /// Parses the given NekoMaid UI code into a syntax tree.
+    pub fn parse(code: &str) -> NekoResult<Self> {
+        let tokens = Tokenizer::tokenize_source(code)?;
+        let mut tokens = tokens.into_iter().peekable();
+        let mut last_line = 0;
+
+        let nodes = parse_nodes(&mut tokens, &mut last_line, false)?;
+        Ok(Self { nodes })
+    }
+}
+
+/// A node within a [`SyntaxTree`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum SyntaxNode {
+    /// A statement terminated by a semicolon, such as an import, a variable, or
+    /// a property. The semicolon is included in the tokens.
+    Statement(Vec<SourceToken>),
+
+    /// A block, such as a style, a layout, or a widget definition.
+    Block {
+        /// The tokens preceding the opening brace.
+        header: Vec<SourceToken>,
+
+        /// The nodes within the braces.
+        children: Vec<SyntaxNode>,
+    },
+
+    /// A line comment, block comment, or doc comment.
+    Comment {
+        /// The comment token.
+        token: SourceToken,
+
+        /// Whether the comment is on the same line as the previous node.
+        trailing: bool,
+    },
+
+    /// One or more blank lines separating two nodes.
+    BlankLine,
+}
+
+impl SyntaxNode {
+    /// Returns the name of the property declared by this node, if this node is
+    /// a property statement in the form `name: value;`.
+    pub fn property_name(&self) -> Option<&str> {
+        let SyntaxNode::Statement(tokens) = self else {
+            return None;
+        };
+
+        match tokens.as_slice() {
+            [name, colon, ..]
+                if name.token.token_type == TokenType::Identifier
+                    && colon.token.token_type == TokenType::Colon =>
+            {
+                Some(&name.text)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Parses a list of nodes, until the end of the input or, if `nested` is set,
+/// until a closing brace.
+fn parse_nodes(
+    tokens: &mut Peekable<IntoIter<SourceToken>>,
+    last_line: &mut usize,
+    nested: bool,
+) -> NekoResult<Vec<SyntaxNode>> {
+    let mut nodes = Vec::new();
+
+    while let Some(next) = tokens.peek() {
+        let token_type = next.token.token_type;
+        let line = next.token.position.line;
+
+        if token_type == TokenType::CloseBrace {
+            if nested {
+                return Ok(nodes);
+            }
+
+            return Err(NekoMaidParseError::UnexpectedToken {
+                expected: vec![TokenType::EndOfStream.type_name().to_string()],
+                found: token_type.type_name().to_string(),
+                position: next.token.position,
+            });
+        }
+
+        if !nodes.is_empty() && line > *last_line + 1 {
+            nodes.push(SyntaxNode::BlankLine);
+        }
+
+        if is_comment(token_type) {
+            let trailing = !nodes.is_empty() && line == *last_line;
+            let token = tokens.next().unwrap();
+            *last_line = token.end_line();
+            nodes.push(SyntaxNode::Comment { token, trailing });
+            continue;
+        }
+
+        nodes.push(parse_node(tokens, last_line)?);
+    }
+
+    if nested {
+        return Err(NekoMaidParseError::EndOfStream);
+    }
+
+    Ok(nodes)
+}
+
+/// Parses a single statement or block.
+fn parse_node(
+    tokens: &mut Peekable<IntoIter<SourceToken>>,
+    last_line: &mut usize,
+) -> NekoResult<SyntaxNode> {
+    let mut header = Vec::new();
+
+    while let Some(token) = tokens.next() {
+        *last_line = token.end_line();
+
+        match token.token.token_type {
+            TokenType::Semicolon => {
+                header.push(token);
+                return Ok(SyntaxNode::Statement(header));
+            }
+            TokenType::OpenBrace => {
+                let children = parse_nodes(tokens, last_line, true)?;
+
+                let close = tokens.next().ok_or(NekoMaidParseError::EndOfStream)?;
+                *last_line = close.end_line();
+
+                return Ok(SyntaxNode::Block { header, children });
+            }
+            TokenType::CloseBrace => {
+                return Err(NekoMaidParseError::UnexpectedToken {
+                    expected: vec![
+                        TokenType::Semicolon.type_name().to_string(),
+                        TokenType::OpenBrace.type_name().to_string(),
+                    ],
+                    found: token.token.token_type.type_name().to_string(),
+                    position: token.token.position,
+                });
+            }
+            _ => header.push(token),
+        }
+    }
+
+    Err(NekoMaidParseError::EndOfStream)
+}
+
+/// Returns true if the given token type is any kind of comment.
+pub(super) fn is_comment(token_type: TokenType) -> bool {
+    matches!(
+        token_type,
+        TokenType::Comment | TokenType::BlockComment | TokenType::DocComment
+    )
+}
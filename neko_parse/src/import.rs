@@ -1,8 +1,8 @@
 //! Handles parsing for import statements and predicts the imports required by a
 //! set of tokens.
 
-use crate::parse::context::{NekoResult, ParseContext};
-use crate::parse::token::{Token, TokenType, TokenValue};
+use crate::context::{NekoResult, ParseContext};
+use crate::token::{Token, TokenType, TokenValue};
 
 /// Predicts the imports required by the given tokens.
 ///
@@ -10,16 +10,16 @@ use crate::parse::token::{Token, TokenType, TokenValue};
 pub fn predict_imports(tokens: &[Token]) -> Vec<String> {
     let mut imports = Vec::new();
 
-    for i in 0 .. tokens.len() - 1 {
-        if tokens[i].token_type != TokenType::ImportKeyword {
+    for pair in tokens.windows(2) {
+        if pair[0].token_type != TokenType::ImportKeyword {
             continue;
         }
 
-        if tokens[i + 1].token_type != TokenType::StringLiteral {
+        if pair[1].token_type != TokenType::StringLiteral {
             continue;
         }
 
-        let TokenValue::String(name) = &tokens[i + 1].value else {
+        let TokenValue::String(name) = &pair[1].value else {
             continue;
         };
 
@@ -1,15 +1,17 @@
 //! Defines the layout structure and parsing logic for NekoMaid UI files.
 
-use bevy::platform::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet};
 
-use crate::parse::NekoMaidParseError;
-use crate::parse::class::parse_class;
-use crate::parse::context::{NekoResult, ParseContext};
-use crate::parse::property::{UnresolvedPropertyValue, parse_unresolved_property};
-use crate::parse::token::TokenType;
+use crate::asset::resolve_asset_paths;
+use crate::class::parse_class;
+use crate::context::{NekoResult, ParseContext};
+use crate::property::{UnresolvedPropertyValue, parse_unresolved_property};
+use crate::token::TokenType;
+use crate::{NekoMaidParseError, shorthand};
 
 /// Represents a layout in the UI.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Layout {
     /// The widget type.
     pub widget: String,
@@ -40,6 +42,28 @@ impl Layout {
     }
 }
 
+/// Parses the `layout` keyword of a root layout, followed by an optional layout
+/// name, in the form `layout <name> <widget> { ... }`.
+///
+/// Returns the name of the layout, if one was given.
+pub fn parse_layout_name(ctx: &mut ParseContext) -> NekoResult<Option<String>> {
+    ctx.expect(TokenType::LayoutKeyword)?;
+
+    let is_named = ctx
+        .peek_nth(0)
+        .is_some_and(|t| t.token_type == TokenType::Identifier)
+        && ctx
+            .peek_nth(1)
+            .is_some_and(|t| t.token_type == TokenType::Identifier);
+
+    if !is_named {
+        return Ok(None);
+    }
+
+    let name = ctx.expect_as_string(TokenType::Identifier)?;
+    Ok(Some(name))
+}
+
 /// Parses a layout from the input and returns a [`Layout`].
 pub fn parse_layout(ctx: &mut ParseContext) -> NekoResult<Layout> {
     ctx.maybe_consume(TokenType::LayoutKeyword);
@@ -48,12 +72,7 @@ pub fn parse_layout(ctx: &mut ParseContext) -> NekoResult<Layout> {
     let widget_position = ctx.next_position().unwrap_or_default();
     let widget = ctx.expect_as_string(TokenType::Identifier)?;
 
-    if ctx.get_widget(&widget).is_none() {
-        return Err(NekoMaidParseError::UnknownWidget {
-            widget,
-            position: widget_position,
-        });
-    };
+    ctx.reference_widget(widget.clone(), widget_position);
 
     let mut layout = Layout::new(widget.clone());
 
@@ -63,21 +82,11 @@ pub fn parse_layout(ctx: &mut ParseContext) -> NekoResult<Layout> {
         match next.token_type {
             TokenType::Identifier => {
                 let name_position = ctx.next_position().unwrap_or_default();
-                let property = parse_unresolved_property(ctx)?;
-
-                if !ctx
-                    .get_widget(&widget)
-                    .expect("Widget should exist as checked earlier")
-                    .has_property(&property.name)
-                {
-                    return Err(NekoMaidParseError::InvalidProperty {
-                        property: property.name,
-                        widget: widget.clone(),
-                        position: name_position,
-                    });
-                }
-
-                layout.properties.insert(property.name, property.value);
+                let mut property = parse_unresolved_property(ctx)?;
+                ctx.reference_property(widget.clone(), property.name.clone(), name_position);
+                resolve_asset_paths(ctx, &widget, &property.name, &mut property.value);
+
+                shorthand::insert_property(&mut layout.properties, property.name, property.value);
             }
             TokenType::ClassKeyword => {
                 let class = parse_class(ctx)?;
@@ -1,25 +1,48 @@
-//! This module implements the parsing functionality for NekoMaid UI files.
-//! It provides functions to read and interpret `.neko_ui` files.
-
-use crate::parse::context::{NekoResult, ParseContext};
-use crate::parse::import::predict_imports;
-use crate::parse::module::Module;
-use crate::parse::token::TokenPosition;
-use crate::parse::tokenizer::{TokenizeError, Tokenizer};
-use crate::parse::widget::{NativeWidget, Widget};
-
+//! The parser and tooling for NekoMaid UI files.
+//!
+//! This crate implements the tokenizer, parser, and module model for
+//! `.neko_ui` files, along with the formatter, linter, and language server
+//! built on top of them. It does not depend on Bevy, so it can be used by
+//! editors and command-line tools without pulling in the engine.
+#![warn(missing_docs)]
+#![warn(clippy::missing_docs_in_private_items)]
+
+use crate::context::{AssetPathResolver, NekoResult, ParseContext};
+use crate::import::predict_imports;
+use crate::module::Module;
+use crate::token::TokenPosition;
+use crate::tokenizer::{TokenizeError, Tokenizer};
+use crate::visibility::Visibility;
+use crate::widget::{NativeWidget, Widget};
+
+pub mod asset;
 pub mod class;
+pub mod color;
+#[cfg(feature = "serde")]
+pub mod compiled;
 pub mod context;
 pub mod element;
+pub mod font;
+pub mod format;
 pub mod import;
 pub mod layout;
+pub mod lint;
+pub mod lsp;
+#[cfg(feature = "manifest")]
+pub mod manifest;
+pub mod markup;
 pub mod module;
+pub mod native;
 pub mod property;
+pub mod schema;
+pub mod shorthand;
 pub mod style;
 pub mod token;
 pub mod tokenizer;
 pub mod value;
+pub mod visibility;
 pub mod widget;
+pub mod workspace;
 
 /// A parser for NekoMaid UI files.
 pub struct NekoMaidParser {
@@ -44,7 +67,23 @@ impl NekoMaidParser {
 
     /// Registers a native widget within this parser's context.
     pub fn register_native_widget(&mut self, widget: NativeWidget) {
-        self.context.add_widget(Widget::Native(widget));
+        self.context
+            .add_widget(Widget::Native(widget), Visibility::Public);
+    }
+
+    /// Registers a property that is accepted by every widget, in addition to
+    /// the properties they define, such as a property read by a marker.
+    pub fn register_global_property(&mut self, property: impl Into<String>) {
+        self.context.add_global_property(property.into());
+    }
+
+    /// Sets the function used to resolve asset paths, such as the `src` of an
+    /// image or the file of a font, that are written in this module.
+    ///
+    /// This allows paths to be resolved relative to the file being parsed.
+    /// Paths are not changed if no resolver is set.
+    pub fn set_asset_path_resolver(&mut self, resolver: AssetPathResolver) {
+        self.context.set_asset_path_resolver(resolver);
     }
 
     /// Predicts the imports required by the given tokens.
@@ -151,6 +190,20 @@ pub enum NekoMaidParseError {
         position: TokenPosition,
     },
 
+    /// An error indicating that a widget contains itself within its own
+    /// layout, either directly or through other widgets.
+    #[error("Recursive widget definition '{widget}' ({cycle}) at {position}")]
+    RecursiveWidget {
+        /// The name of the recursive widget.
+        widget: String,
+
+        /// The chain of widgets forming the cycle.
+        cycle: String,
+
+        /// The position of the widget definition in the source code.
+        position: TokenPosition,
+    },
+
     /// An error indicating that a module could not be found.
     #[error("Module not found: {name}, at {position}")]
     ModuleNotFound {
@@ -180,6 +233,16 @@ pub enum NekoMaidParseError {
         position: TokenPosition,
     },
 
+    /// An error indicating that a font face declaration has no source file.
+    #[error("Font face '{font}' has no src, at {position}")]
+    MissingFontSource {
+        /// The name of the font face.
+        font: String,
+
+        /// The position of the font face declaration in the source code.
+        position: TokenPosition,
+    },
+
     /// An error indicating that a layout has no output slot.
     #[error("Layout has no output slot: {widget} at {position}")]
     LayoutHasNoOutput {
@@ -190,3 +253,29 @@ pub enum NekoMaidParseError {
         position: TokenPosition,
     },
 }
+
+impl NekoMaidParseError {
+    /// Returns the position in the source code where the error occurred, if
+    /// known.
+    pub fn position(&self) -> Option<TokenPosition> {
+        match self {
+            NekoMaidParseError::TokenizerError(TokenizeError::UnexpectedCharacter {
+                position,
+                ..
+            })
+            | NekoMaidParseError::UnexpectedToken { position, .. }
+            | NekoMaidParseError::InvalidTokenValue { position, .. }
+            | NekoMaidParseError::InvalidProperty { position, .. }
+            | NekoMaidParseError::VariableNotFound { position, .. }
+            | NekoMaidParseError::IncompleteWidgetDefinition { position, .. }
+            | NekoMaidParseError::UnknownWidget { position, .. }
+            | NekoMaidParseError::RecursiveWidget { position, .. }
+            | NekoMaidParseError::ModuleNotFound { position, .. }
+            | NekoMaidParseError::MultipleLayoutsDefined { position }
+            | NekoMaidParseError::MultipleWidgetOutputsDefined { position, .. }
+            | NekoMaidParseError::LayoutHasNoOutput { position, .. }
+            | NekoMaidParseError::MissingFontSource { position, .. } => Some(*position),
+            NekoMaidParseError::EndOfStream => None,
+        }
+    }
+}
@@ -0,0 +1,655 @@
+//! This module implements a validator and linter for NekoMaid UI files.
+//!
+//! Every file is loaded through a [`Workspace`], reporting any parse errors,
+//! and then checked for common mistakes that are not errors in themselves:
+//!
+//! - variables, widget properties, and widgets that are never used,
+//! - styles whose selectors can never match any element,
+//! - properties that are set more than once within the same block,
+//! - classes that are neither styled nor registered as markers.
+//!
+//! Public declarations are considered used if they are used by any of the
+//! checked files, while private declarations must be used within their own
+//! file.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::class::ClassPath;
+use crate::element::NekoElementBuilder;
+use crate::format::syntax::{SyntaxNode, SyntaxTree};
+use crate::module::Module;
+use crate::style::{Selector, SelectorPart};
+use crate::token::{TokenPosition, TokenType};
+use crate::tokenizer::SourceToken;
+use crate::widget::Widget;
+use crate::workspace::{Workspace, WorkspaceError};
+
+/// Options for linting NekoMaid UI files.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct LintOptions {
+    /// The names of the class markers registered by the application.
+    ///
+    /// Classes with a marker are not reported as unknown, even if no style
+    /// refers to them.
+    pub markers: HashSet<String>,
+}
+
+/// A problem found within a NekoMaid UI file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    /// The path of the file containing the problem.
+    pub path: PathBuf,
+
+    /// The position of the problem within the file, if known.
+    pub position: Option<TokenPosition>,
+
+    /// The severity of the problem.
+    pub severity: Severity,
+
+    /// A description of the problem.
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.path.display())?;
+
+        if let Some(position) = self.position {
+            write!(f, ":{}:{}", position.line, position.column)?;
+        }
+
+        write!(f, ": {}: {}", self.severity, self.message)
+    }
+}
+
+/// The severity of a [`Diagnostic`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Severity {
+    /// A likely mistake, which does not prevent the file from loading.
+    Warning,
+
+    /// An error which prevents the file from loading.
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// Checks the given files, returning all problems found, sorted by file and
+/// position.
+pub fn lint(
+    workspace: &mut Workspace,
+    files: &[PathBuf],
+    options: &LintOptions,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut scans = Vec::new();
+    let mut modules = Vec::new();
+
+    for path in files {
+        match workspace.load(path) {
+            Ok(module) => modules.push(module),
+            Err(error) => diagnostics.push(load_error(path, error)),
+        }
+
+        let Ok(code) = workspace.source(path) else {
+            continue;
+        };
+
+        if let Ok(tree) = SyntaxTree::parse(&code) {
+            let mut scan = FileScan::default();
+            let scope = Scope {
+                module_level: true,
+                style: None,
+            };
+
+            scan.scan_nodes(&tree.nodes, &scope);
+            scans.push((path.clone(), scan));
+        }
+    }
+
+    let usage = Usage::collect(&scans, &modules);
+    for (path, scan) in &scans {
+        scan.report(path, &usage, options, &mut diagnostics);
+    }
+
+    diagnostics.sort_by(|a, b| {
+        (&a.path, a.position.map(|p| (p.line, p.column)))
+            .cmp(&(&b.path, b.position.map(|p| (p.line, p.column))))
+    });
+    diagnostics
+}
+
+/// Converts an error loading the file at the given path into a diagnostic.
+fn load_error(path: &Path, error: WorkspaceError) -> Diagnostic {
+    let (path, position, message) = match error {
+        WorkspaceError::Parse { path, error } => (path, error.position(), error.to_string()),
+        error => (path.to_path_buf(), None, error.to_string()),
+    };
+
+    Diagnostic {
+        path,
+        position,
+        severity: Severity::Error,
+        message,
+    }
+}
+
+/// A named declaration within a file.
+#[derive(Debug, Clone, PartialEq)]
+struct Declaration {
+    /// The declared name.
+    name: String,
+
+    /// The position of the declared name.
+    position: TokenPosition,
+
+    /// Whether the declaration is private to its file.
+    private: bool,
+}
+
+/// The block a node is nested within.
+#[derive(Debug, Default, Clone)]
+struct Scope {
+    /// Whether the node is a module-level declaration.
+    module_level: bool,
+
+    /// The widget names of the enclosing style selectors, if within a style.
+    style: Option<Vec<String>>,
+}
+
+/// The declarations and references found within a single file.
+#[derive(Debug, Default)]
+struct FileScan {
+    /// The module-level variables.
+    variables: Vec<Declaration>,
+
+    /// The widget definitions.
+    widgets: Vec<Declaration>,
+
+    /// The names of all referenced variables.
+    variable_refs: HashSet<String>,
+
+    /// The names of all referenced widgets.
+    widget_refs: HashSet<String>,
+
+    /// The classes applied to layout elements.
+    classes: Vec<(String, TokenPosition)>,
+
+    /// The classes referenced by style selectors.
+    styled_classes: HashSet<String>,
+
+    /// The widget hierarchy of each style selector, with the position of its
+    /// last widget.
+    selectors: Vec<(Vec<String>, TokenPosition)>,
+
+    /// Warnings that only depend on the file itself.
+    warnings: Vec<(TokenPosition, String)>,
+}
+
+impl FileScan {
+    /// Scans the given nodes, which share a single block.
+    fn scan_nodes(&mut self, nodes: &[SyntaxNode], scope: &Scope) {
+        let mut properties = HashSet::new();
+
+        for node in nodes {
+            match node {
+                SyntaxNode::Statement(tokens) => {
+                    self.scan_references(tokens);
+                    self.scan_statement(tokens, scope, &mut properties);
+                }
+                SyntaxNode::Block { header, children } => {
+                    self.scan_references(header);
+                    self.scan_block(header, children, scope);
+                }
+                SyntaxNode::Comment { .. } | SyntaxNode::BlankLine => {}
+            }
+        }
+    }
+
+    /// Records all variables referenced by the given tokens.
+    fn scan_references(&mut self, tokens: &[SourceToken]) {
+        for token in tokens {
+            if token.token.token_type == TokenType::Variable {
+                self.variable_refs.insert(token.text[1 ..].to_string());
+            }
+        }
+    }
+
+    /// Scans a single statement within a block.
+    fn scan_statement(
+        &mut self,
+        tokens: &[SourceToken],
+        scope: &Scope,
+        properties: &mut HashSet<String>,
+    ) {
+        let (private, tokens) = strip_private(tokens);
+
+        match tokens {
+            [var, name, ..]
+                if var.token.token_type == TokenType::VarKeyword && scope.module_level =>
+            {
+                self.variables.push(Declaration {
+                    name: name.text.clone(),
+                    position: name.token.position,
+                    private,
+                });
+            }
+            [name, colon, ..]
+                if colon.token.token_type == TokenType::Colon
+                    && !properties.insert(name.text.clone()) =>
+            {
+                self.warnings.push((
+                    name.token.position,
+                    format!("Property '{}' is set more than once", name.text),
+                ));
+            }
+            [class, name, ..]
+                if class.token.token_type == TokenType::ClassKeyword && scope.style.is_none() =>
+            {
+                self.classes.push((name.text.clone(), name.token.position));
+            }
+            _ => {}
+        }
+    }
+
+    /// Scans a block and its children.
+    fn scan_block(&mut self, header: &[SourceToken], children: &[SyntaxNode], scope: &Scope) {
+        let (private, header) = strip_private(header);
+        let Some(keyword) = header.first().map(|t| t.token.token_type) else {
+            return;
+        };
+
+        let widgets = selector_widgets(header);
+
+        match (keyword, &scope.style) {
+            (TokenType::DefKeyword, _) => {
+                if let Some(name) = header.get(1) {
+                    self.widgets.push(Declaration {
+                        name: name.text.clone(),
+                        position: name.token.position,
+                        private,
+                    });
+                    self.scan_def(&name.text, children);
+                }
+            }
+            (TokenType::StyleKeyword, _) | (TokenType::WithKeyword, Some(_)) => {
+                let mut path = scope.style.clone().unwrap_or_default();
+
+                if let Some(widget) = widgets.first() {
+                    self.widget_refs.insert(widget.text.clone());
+                    path.push(widget.text.clone());
+                    self.selectors.push((path.clone(), widget.token.position));
+                }
+
+                for pair in header.windows(2) {
+                    if matches!(
+                        pair[0].token.token_type,
+                        TokenType::Plus | TokenType::Exclamation
+                    ) {
+                        self.styled_classes.insert(pair[1].text.clone());
+                    }
+                }
+
+                let scope = Scope {
+                    module_level: false,
+                    style: Some(path),
+                };
+
+                self.scan_nodes(children, &scope);
+            }
+            _ => {
+                if let Some(widget) = widgets.last() {
+                    self.widget_refs.insert(widget.text.clone());
+                }
+
+                self.scan_nodes(children, &Scope::default());
+            }
+        }
+    }
+
+    /// Scans the body of a widget definition, reporting unused widget
+    /// properties.
+    fn scan_def(&mut self, widget: &str, children: &[SyntaxNode]) {
+        let mut used = HashSet::new();
+        collect_variable_refs(children, &mut used);
+
+        for node in children {
+            match node {
+                SyntaxNode::Statement(tokens) => {
+                    self.scan_references(tokens);
+
+                    if let [var, name, ..] = tokens.as_slice()
+                        && var.token.token_type == TokenType::VarKeyword
+                        && !used.contains(&name.text)
+                    {
+                        self.warnings.push((
+                            name.token.position,
+                            format!(
+                                "Property '{}' of widget '{}' is never used",
+                                name.text, widget
+                            ),
+                        ));
+                    }
+                }
+                SyntaxNode::Block { header, children } => {
+                    self.scan_references(header);
+                    self.scan_block(header, children, &Scope::default());
+                }
+                SyntaxNode::Comment { .. } | SyntaxNode::BlankLine => {}
+            }
+        }
+    }
+
+    /// Reports all problems found within this file.
+    fn report(
+        &self,
+        path: &Path,
+        usage: &Usage,
+        options: &LintOptions,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) {
+        let mut warn = |position: TokenPosition, message: String| {
+            diagnostics.push(Diagnostic {
+                path: path.to_path_buf(),
+                position: Some(position),
+                severity: Severity::Warning,
+                message,
+            });
+        };
+
+        for (position, message) in &self.warnings {
+            warn(*position, message.clone());
+        }
+
+        for variable in &self.variables {
+            let used = match variable.private {
+                true => self.variable_refs.contains(&variable.name),
+                false => usage.variables.contains(&variable.name),
+            };
+
+            if !used {
+                warn(
+                    variable.position,
+                    format!("Variable '{}' is never used", variable.name),
+                );
+            }
+        }
+
+        for widget in &self.widgets {
+            let used = match widget.private {
+                true => self.widget_refs.contains(&widget.name),
+                false => usage.widgets.contains(&widget.name),
+            };
+
+            if !used {
+                warn(
+                    widget.position,
+                    format!("Widget '{}' is never used", widget.name),
+                );
+            }
+        }
+
+        for (hierarchy, position) in &self.selectors {
+            if !usage.matches(hierarchy) {
+                warn(
+                    *position,
+                    format!(
+                        "Style '{}' never matches any element",
+                        hierarchy.join(" with ")
+                    ),
+                );
+            }
+        }
+
+        for (class, position) in &self.classes {
+            if !usage.styled_classes.contains(class) && !options.markers.contains(class) {
+                warn(
+                    *position,
+                    format!("Class '{}' has no style or marker", class),
+                );
+            }
+        }
+    }
+}
+
+/// The names used across all checked files.
+#[derive(Debug, Default)]
+struct Usage {
+    /// The names of all referenced variables.
+    variables: HashSet<String>,
+
+    /// The names of all referenced widgets.
+    widgets: HashSet<String>,
+
+    /// The classes referenced by any style selector.
+    styled_classes: HashSet<String>,
+
+    /// The class paths of all elements that can be spawned.
+    classpaths: Vec<ClassPath>,
+}
+
+impl Usage {
+    /// Collects the names used by the given files and modules.
+    fn collect(scans: &[(PathBuf, FileScan)], modules: &[Module]) -> Self {
+        let mut usage = Usage::default();
+
+        for (_, scan) in scans {
+            usage.variables.extend(scan.variable_refs.iter().cloned());
+            usage.widgets.extend(scan.widget_refs.iter().cloned());
+            usage
+                .styled_classes
+                .extend(scan.styled_classes.iter().cloned());
+        }
+
+        for module in modules {
+            let mut elements: Vec<_> = module
+                .elements
+                .iter()
+                .chain(module.named_elements.values())
+                .cloned()
+                .collect();
+
+            // Widgets can be spawned on their own as tree roots.
+            for widget in module.widgets.values() {
+                if let Widget::Custom(custom) = widget
+                    && let Ok(element) = module.build_widget(&custom.name, &HashMap::new())
+                {
+                    elements.push(element);
+                }
+            }
+
+            for element in &elements {
+                collect_classpaths(element, &mut usage.classpaths);
+            }
+        }
+
+        usage
+    }
+
+    /// Checks if any element could match a selector with the given widget
+    /// hierarchy, regardless of its classes.
+    ///
+    /// Classes are ignored since they can be changed at runtime.
+    fn matches(&self, hierarchy: &[String]) -> bool {
+        let selector = Selector {
+            hierarchy: hierarchy
+                .iter()
+                .map(|widget| SelectorPart {
+                    widget: widget.clone(),
+                    whitelist: HashSet::new(),
+                    blacklist: HashSet::new(),
+                })
+                .collect(),
+        };
+
+        self.classpaths
+            .iter()
+            .any(|path| path.partial_matches(&selector))
+    }
+}
+
+/// Collects the class paths of the given element and all of its descendants.
+fn collect_classpaths(element: &NekoElementBuilder, classpaths: &mut Vec<ClassPath>) {
+    classpaths.push(element.element.classpath().clone());
+
+    for child in &element.children {
+        collect_classpaths(child, classpaths);
+    }
+}
+
+/// Collects the names of all variables referenced within the given nodes.
+fn collect_variable_refs(nodes: &[SyntaxNode], refs: &mut HashSet<String>) {
+    for node in nodes {
+        let tokens = match node {
+            SyntaxNode::Statement(tokens) => tokens,
+            SyntaxNode::Block { header, children } => {
+                collect_variable_refs(children, refs);
+                header
+            }
+            SyntaxNode::Comment { .. } | SyntaxNode::BlankLine => continue,
+        };
+
+        for token in tokens {
+            if token.token.token_type == TokenType::Variable {
+                refs.insert(token.text[1 ..].to_string());
+            }
+        }
+    }
+}
+
+/// Splits a leading `private` keyword from the given tokens.
+fn strip_private(tokens: &[SourceToken]) -> (bool, &[SourceToken]) {
+    match tokens.first() {
+        Some(first) if first.token.token_type == TokenType::PrivateKeyword => (true, &tokens[1 ..]),
+        _ => (false, tokens),
+    }
+}
+
+/// Returns the widget names within a block header, skipping class names.
+fn selector_widgets(header: &[SourceToken]) -> Vec<&SourceToken> {
+    let mut widgets = Vec::new();
+    let mut previous = None;
+
+    for token in header {
+        if token.token.token_type == TokenType::Identifier
+            && !matches!(previous, Some(TokenType::Plus | TokenType::Exclamation))
+        {
+            widgets.push(token);
+        }
+
+        previous = Some(token.token.token_type);
+    }
+
+    widgets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Lints the given files, returning the messages of all diagnostics.
+    fn lint_sources(files: &[(&str, &str)], options: &LintOptions) -> Vec<String> {
+        let mut workspace = Workspace::new();
+        let mut paths = Vec::new();
+
+        for (path, code) in files {
+            workspace.set_source(Path::new(path), code.to_string());
+            paths.push(PathBuf::from(path));
+        }
+
+        lint(&mut workspace, &paths, options)
+            .into_iter()
+            .map(|d| d.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn reports_parse_errors() {
+        let messages = lint_sources(
+            &[("ui/a.neko_ui", "layout foo {}")],
+            &LintOptions::default(),
+        );
+        assert_eq!(
+            messages,
+            vec!["ui/a.neko_ui:1:8: error: Unknown widget 'foo' at line 1, col 8-10"]
+        );
+    }
+
+    #[test]
+    fn reports_lint_warnings() {
+        let code = r#"
+var used = 1px;
+private var unused = 2px;
+
+def title {
+  var text = "";
+  var size = 10px;
+  layout p {
+    text: $text;
+    output;
+  }
+}
+
+private def orphan {
+  layout div { output; }
+}
+
+style span {
+  width: 1px;
+}
+
+style div +menu {
+  with p {
+    width: $used;
+  }
+}
+
+layout div {
+  class menu;
+  class shiny;
+  class marked;
+  width: 1px;
+  width: 2px;
+  with title {}
+}
+"#;
+
+        let options = LintOptions {
+            markers: HashSet::from_iter([String::from("marked")]),
+        };
+
+        let messages = lint_sources(&[("ui/a.neko_ui", code)], &options);
+        assert_eq!(
+            messages,
+            vec![
+                "ui/a.neko_ui:3:13: warning: Variable 'unused' is never used",
+                "ui/a.neko_ui:7:7: warning: Property 'size' of widget 'title' is never used",
+                "ui/a.neko_ui:14:13: warning: Widget 'orphan' is never used",
+                "ui/a.neko_ui:18:7: warning: Style 'span' never matches any element",
+                "ui/a.neko_ui:30:9: warning: Class 'shiny' has no style or marker",
+                "ui/a.neko_ui:33:3: warning: Property 'width' is set more than once",
+            ]
+        );
+    }
+
+    #[test]
+    fn public_declarations_can_be_used_by_other_files() {
+        let library = "var accent = #fff;\ndef title { layout p { color: $accent; output; } }\n";
+        let menu = "import \"lib\";\nlayout title {}\n";
+
+        let messages = lint_sources(
+            &[("ui/lib.neko_ui", library), ("ui/menu.neko_ui", menu)],
+            &LintOptions::default(),
+        );
+        assert!(messages.is_empty(), "{:?}", messages);
+    }
+}
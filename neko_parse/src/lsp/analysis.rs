@@ -0,0 +1,755 @@
+//! Source analysis for editor features: completion, hover, and
+//! go-to-definition.
+//!
+//! All positions use the same one-based lines and columns as
+//! [`TokenPosition`], with columns counted in characters.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::NekoMaidParser;
+use crate::module::Module;
+use crate::token::{TokenPosition, TokenType};
+use crate::tokenizer::{SourceToken, Tokenizer};
+use crate::widget::Widget;
+use crate::workspace::{Workspace, resolve_import};
+
+lazy_static! {
+    static ref VARIABLE_PREFIX: Regex = Regex::new(r"\$[a-zA-Z0-9_-]*$").unwrap();
+    static ref CLASS_PREFIX: Regex = Regex::new(r"(?:\bclass\s+|[+!]\s*)[a-zA-Z0-9_-]*$").unwrap();
+    static ref WIDGET_PREFIX: Regex =
+        Regex::new(r"\b(?:with|style|layout(?:\s+[a-zA-Z_][a-zA-Z0-9_-]*)?)\s+[a-zA-Z0-9_-]*$")
+            .unwrap();
+    static ref VALUE_PREFIX: Regex =
+        Regex::new(r#"^\s*([a-zA-Z0-9_-]+)\s*:[^;{}$"]*?[a-zA-Z0-9_-]*$"#).unwrap();
+    static ref STATEMENT_PREFIX: Regex = Regex::new(r"^\s*(?:private\s+)?[a-zA-Z0-9_-]*$").unwrap();
+}
+
+/// The keywords that can start a top-level declaration.
+const MODULE_KEYWORDS: &[&str] = &[
+    "import",
+    "var",
+    "def",
+    "style",
+    "layout",
+    "font-face",
+    "private",
+];
+
+/// A suggested completion at a position within a document.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Completion {
+    /// The text to insert.
+    pub label: String,
+
+    /// The kind of item being completed.
+    pub kind: CompletionKind,
+
+    /// Additional information about the item, such as a default value.
+    pub detail: Option<String>,
+}
+
+/// The kind of item suggested by a [`Completion`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CompletionKind {
+    /// A language keyword.
+    Keyword,
+
+    /// A widget name.
+    Widget,
+
+    /// A property name of a widget.
+    Property,
+
+    /// A variable name.
+    Variable,
+
+    /// A class name.
+    Class,
+}
+
+/// A named symbol within a document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Symbol {
+    /// A variable reference, such as `$color`.
+    Variable(String),
+
+    /// The name of a property, such as `width` in `width: 10px;`.
+    Property(String),
+
+    /// Any other identifier, which may refer to a widget.
+    Widget(String),
+}
+
+/// Returns the completions available at the given position within the code.
+///
+/// The module provides the widgets and variables in scope, and is usually the
+/// last successfully parsed version of the document.
+pub fn completions(code: &str, line: usize, column: usize, module: &Module) -> Vec<Completion> {
+    let prefix = line_prefix(code, line, column);
+    let blocks = enclosing_blocks(code, line, column);
+
+    if VARIABLE_PREFIX.is_match(prefix) {
+        return variable_completions(&blocks, module);
+    }
+
+    if CLASS_PREFIX.is_match(prefix) {
+        return class_completions(code, module);
+    }
+
+    if WIDGET_PREFIX.is_match(prefix) {
+        return widget_completions(module);
+    }
+
+    if let Some(captures) = VALUE_PREFIX.captures(prefix) {
+        return value_completions(&blocks, module, &captures[1]);
+    }
+
+    if STATEMENT_PREFIX.is_match(prefix) {
+        return statement_completions(&blocks, module);
+    }
+
+    Vec::new()
+}
+
+/// Returns the hover text for the symbol at the given position, formatted as
+/// Markdown.
+pub fn hover(code: &str, line: usize, column: usize, module: &Module) -> Option<String> {
+    let blocks = enclosing_blocks(code, line, column);
+
+    match symbol_at(code, line, column)? {
+        Symbol::Variable(name) => {
+            let value = enclosing_def(&blocks)
+                .and_then(|def| match module.widgets.get(&def) {
+                    Some(Widget::Custom(custom)) => custom.default_properties.get(&name),
+                    _ => None,
+                })
+                .or_else(|| module.variables.get(&name))?;
+
+            Some(format!("```\nvar {} = {};\n```", name, value))
+        }
+        Symbol::Property(name) => {
+            let widget = module.widgets.get(&enclosing_widget(&blocks)?)?;
+            let mut text = format!("```\n{}: {};\n```", name, property_default(widget, &name)?);
+
+            if let Widget::Custom(custom) = widget
+                && let Some(doc) = custom.property_docs.get(&name)
+            {
+                text.push_str("\n\n");
+                text.push_str(doc);
+            }
+
+            Some(text)
+        }
+        Symbol::Widget(name) => Some(describe_widget(module.widgets.get(&name)?)),
+    }
+}
+
+/// Finds the declaration of the symbol at the given position within the file
+/// at the given path, searching the file itself and then its imports.
+///
+/// Returns the path of the file containing the declaration, along with the
+/// position of the declared name.
+pub fn definition(
+    workspace: &Workspace,
+    path: &Path,
+    line: usize,
+    column: usize,
+) -> Option<(PathBuf, TokenPosition)> {
+    let code = workspace.source(path).ok()?;
+    let symbol = symbol_at(&code, line, column)?;
+
+    let blocks = enclosing_blocks(&code, line, column);
+    let scope = match &symbol {
+        Symbol::Property(_) => Some(enclosing_widget(&blocks)?),
+        _ => enclosing_def(&blocks),
+    };
+
+    let mut visited = HashSet::new();
+    find_in_file(
+        workspace,
+        path,
+        &code,
+        &symbol,
+        scope.as_deref(),
+        &mut visited,
+    )
+}
+
+/// Returns the symbol at the given position within the code, if any.
+pub fn symbol_at(code: &str, line: usize, column: usize) -> Option<Symbol> {
+    let text: Vec<char> = code.lines().nth(line.checked_sub(1)?)?.chars().collect();
+    let is_word = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '-';
+
+    let cursor = column.saturating_sub(1).min(text.len());
+    let mut start = cursor;
+    while start > 0 && is_word(text[start - 1]) {
+        start -= 1;
+    }
+
+    let mut end = cursor;
+    while end < text.len() && is_word(text[end]) {
+        end += 1;
+    }
+
+    if start == end {
+        return None;
+    }
+
+    let name: String = text[start .. end].iter().collect();
+
+    if start > 0 && text[start - 1] == '$' {
+        return Some(Symbol::Variable(name));
+    }
+
+    let next = text[end ..].iter().find(|c| !c.is_whitespace());
+    match next {
+        Some(':') => Some(Symbol::Property(name)),
+        _ => Some(Symbol::Widget(name)),
+    }
+}
+
+/// Returns the text of the given line before the given column.
+fn line_prefix(code: &str, line: usize, column: usize) -> &str {
+    let Some(text) = line.checked_sub(1).and_then(|line| code.lines().nth(line)) else {
+        return "";
+    };
+
+    match text.char_indices().nth(column.saturating_sub(1)) {
+        Some((index, _)) => &text[.. index],
+        None => text,
+    }
+}
+
+/// Returns the byte offset of the given position within the code.
+fn offset_of(code: &str, line: usize, column: usize) -> usize {
+    let mut offset = 0;
+
+    for (index, text) in code.split_inclusive('\n').enumerate() {
+        if index + 1 == line {
+            return offset + line_prefix(text, 1, column).len();
+        }
+
+        offset += text.len();
+    }
+
+    code.len()
+}
+
+/// Returns the headers of all blocks enclosing the given position, from the
+/// outermost to the innermost block.
+///
+/// If the code before the position cannot be tokenized, for example because
+/// the cursor is within an unterminated string, only the code before the
+/// current line is considered.
+fn enclosing_blocks(code: &str, line: usize, column: usize) -> Vec<Vec<SourceToken>> {
+    let tokens = Tokenizer::tokenize_source(&code[.. offset_of(code, line, column)])
+        .or_else(|_| Tokenizer::tokenize_source(&code[.. offset_of(code, line, 1)]))
+        .unwrap_or_default();
+
+    let mut blocks = Vec::new();
+    let mut current = Vec::new();
+
+    for token in tokens {
+        match token.token.token_type {
+            TokenType::OpenBrace => blocks.push(std::mem::take(&mut current)),
+            TokenType::CloseBrace => {
+                blocks.pop();
+                current.clear();
+            }
+            TokenType::Semicolon => current.clear(),
+            TokenType::Comment | TokenType::BlockComment | TokenType::DocComment => {}
+            _ => current.push(token),
+        }
+    }
+
+    blocks
+}
+
+/// Returns the tokens of a block header, without a leading `private` keyword.
+fn header_tokens(header: &[SourceToken]) -> &[SourceToken] {
+    match header.first() {
+        Some(first) if first.token.token_type == TokenType::PrivateKeyword => &header[1 ..],
+        _ => header,
+    }
+}
+
+/// Returns the name of the widget definition enclosing the innermost block.
+fn enclosing_def(blocks: &[Vec<SourceToken>]) -> Option<String> {
+    blocks
+        .iter()
+        .find_map(|header| match header_tokens(header) {
+            [def, name, ..] if def.token.token_type == TokenType::DefKeyword => {
+                Some(name.text.clone())
+            }
+            _ => None,
+        })
+}
+
+/// Returns the name of the widget targeted by the innermost block, which is
+/// either a layout element or a style.
+fn enclosing_widget(blocks: &[Vec<SourceToken>]) -> Option<String> {
+    let mut in_style = false;
+    let mut widget = None;
+
+    for header in blocks {
+        let header = header_tokens(header);
+        let mut identifiers = header
+            .iter()
+            .skip(1)
+            .filter(|t| t.token.token_type == TokenType::Identifier);
+
+        widget = match header.first().map(|t| t.token.token_type) {
+            Some(TokenType::StyleKeyword) => {
+                in_style = true;
+                identifiers.next()
+            }
+            Some(TokenType::WithKeyword) if in_style => identifiers.next(),
+            Some(TokenType::WithKeyword | TokenType::LayoutKeyword) => identifiers.next_back(),
+            _ => None,
+        }
+        .map(|t| t.text.clone());
+    }
+
+    widget
+}
+
+/// Returns the variables available within the innermost block.
+fn variable_completions(blocks: &[Vec<SourceToken>], module: &Module) -> Vec<Completion> {
+    let mut completions: Vec<_> = module
+        .variables
+        .iter()
+        .filter(|(name, _)| !name.contains("::"))
+        .map(|(name, value)| Completion {
+            label: name.clone(),
+            kind: CompletionKind::Variable,
+            detail: Some(value.to_string()),
+        })
+        .collect();
+
+    if let Some(def) = enclosing_def(blocks)
+        && let Some(Widget::Custom(custom)) = module.widgets.get(&def)
+    {
+        completions.retain(|c| !custom.default_properties.contains_key(&c.label));
+        completions.extend(
+            custom
+                .default_properties
+                .iter()
+                .map(|(name, value)| Completion {
+                    label: name.clone(),
+                    kind: CompletionKind::Variable,
+                    detail: Some(value.to_string()),
+                }),
+        );
+    }
+
+    sorted(completions)
+}
+
+/// Returns the classes used anywhere within the code or the module styles,
+/// excluding the private styles of imported modules.
+fn class_completions(code: &str, module: &Module) -> Vec<Completion> {
+    let mut classes = HashSet::new();
+
+    for style in module.styles.iter().filter(|style| style.scope().is_none()) {
+        for part in &style.selector().hierarchy {
+            classes.extend(part.whitelist.iter().cloned());
+            classes.extend(part.blacklist.iter().cloned());
+        }
+    }
+
+    let tokens = Tokenizer::tokenize_source(code).unwrap_or_default();
+    for pair in tokens.windows(2) {
+        if matches!(
+            pair[0].token.token_type,
+            TokenType::ClassKeyword | TokenType::Plus | TokenType::Exclamation
+        ) && pair[1].token.token_type == TokenType::Identifier
+        {
+            classes.insert(pair[1].text.clone());
+        }
+    }
+
+    sorted(
+        classes
+            .into_iter()
+            .map(|label| Completion {
+                label,
+                kind: CompletionKind::Class,
+                detail: None,
+            })
+            .collect(),
+    )
+}
+
+/// Returns all widgets available within the module.
+fn widget_completions(module: &Module) -> Vec<Completion> {
+    sorted(
+        module
+            .widgets
+            .values()
+            .filter(|widget| !widget.name().contains("::"))
+            .map(|widget| Completion {
+                label: widget.name().to_string(),
+                kind: CompletionKind::Widget,
+                detail: match widget {
+                    Widget::Custom(custom) => custom.doc.clone(),
+                    Widget::Native(_) => Some(String::from("native widget")),
+                },
+            })
+            .collect(),
+    )
+}
+
+/// Returns the keywords and properties that can start a statement within the
+/// innermost block.
+fn statement_completions(blocks: &[Vec<SourceToken>], module: &Module) -> Vec<Completion> {
+    let keyword = |label: &str| Completion {
+        label: label.to_string(),
+        kind: CompletionKind::Keyword,
+        detail: None,
+    };
+
+    let Some(innermost) = blocks.last() else {
+        return MODULE_KEYWORDS.iter().map(|k| keyword(k)).collect();
+    };
+
+    let in_def = enclosing_def(blocks).is_some();
+    let in_style = blocks.iter().any(|header| {
+        header_tokens(header).first().map(|t| t.token.token_type) == Some(TokenType::StyleKeyword)
+    });
+
+    let mut completions = match header_tokens(innermost).first().map(|t| t.token.token_type) {
+        Some(TokenType::DefKeyword) => vec![keyword("var"), keyword("layout")],
+        _ if in_style => vec![keyword("with")],
+        _ if in_def => vec![keyword("with"), keyword("class"), keyword("output")],
+        _ => vec![keyword("with"), keyword("class")],
+    };
+
+    if let Some(widget) = enclosing_widget(blocks).and_then(|w| module.widgets.get(&w).cloned()) {
+        let properties = property_names(&widget).into_iter().map(|name| Completion {
+            detail: property_default(&widget, &name),
+            label: name,
+            kind: CompletionKind::Property,
+        });
+
+        completions.extend(sorted(properties.collect()));
+    }
+
+    completions
+}
+
+/// Returns the keywords accepted by the given property of the native widget
+/// targeted by the innermost block.
+fn value_completions(
+    blocks: &[Vec<SourceToken>],
+    module: &Module,
+    property: &str,
+) -> Vec<Completion> {
+    let widget = enclosing_widget(blocks).and_then(|w| module.widgets.get(&w));
+    let Some(Widget::Native(native)) = widget else {
+        return Vec::new();
+    };
+
+    sorted(
+        native
+            .property_keywords(property)
+            .iter()
+            .map(|keyword| Completion {
+                label: keyword.clone(),
+                kind: CompletionKind::Keyword,
+                detail: None,
+            })
+            .collect(),
+    )
+}
+
+/// Returns the names of all properties of the given widget.
+fn property_names(widget: &Widget) -> Vec<String> {
+    match widget {
+        Widget::Custom(custom) => custom.default_properties.keys().cloned().collect(),
+        Widget::Native(native) => native.default_properties.keys().cloned().collect(),
+    }
+}
+
+/// Returns the default value of the given property of a widget, as written
+/// in source code.
+fn property_default(widget: &Widget, property: &str) -> Option<String> {
+    match widget {
+        Widget::Custom(custom) => custom
+            .default_properties
+            .get(property)
+            .map(|v| v.to_string()),
+        Widget::Native(native) => native
+            .default_properties
+            .get(property)
+            .map(|v| v.to_string()),
+    }
+}
+
+/// Describes the given widget and its properties, formatted as Markdown.
+fn describe_widget(widget: &Widget) -> String {
+    let mut names = property_names(widget);
+    names.sort();
+
+    let mut text = match widget {
+        Widget::Custom(custom) => format!("```\ndef {} {{\n", custom.name),
+        Widget::Native(native) => format!("```\n// native widget\ndef {} {{\n", native.name),
+    };
+
+    for name in names {
+        let value = property_default(widget, &name).unwrap_or_default();
+        text.push_str(&format!("  var {} = {};\n", name, value));
+    }
+
+    text.push_str("}\n```");
+
+    if let Widget::Custom(custom) = widget
+        && let Some(doc) = &custom.doc
+    {
+        text.push_str("\n\n");
+        text.push_str(doc);
+    }
+
+    text
+}
+
+/// Sorts the given completions by label.
+fn sorted(mut completions: Vec<Completion>) -> Vec<Completion> {
+    completions.sort_by(|a, b| a.label.cmp(&b.label));
+    completions
+}
+
+/// Searches the given file and its imports for the declaration of a symbol.
+///
+/// Variables are first searched within the widget definition named by
+/// `scope`, and properties are only searched within it.
+fn find_in_file(
+    workspace: &Workspace,
+    path: &Path,
+    code: &str,
+    symbol: &Symbol,
+    scope: Option<&str>,
+    visited: &mut HashSet<PathBuf>,
+) -> Option<(PathBuf, TokenPosition)> {
+    if !visited.insert(path.to_path_buf()) {
+        return None;
+    }
+
+    if let Some(position) = find_declaration(code, symbol, scope) {
+        return Some((path.to_path_buf(), position));
+    }
+
+    // Widget parameters are never imported, only module-level variables.
+    let scope = match symbol {
+        Symbol::Property(_) => scope,
+        _ => None,
+    };
+
+    let parser = NekoMaidParser::tokenize(code).ok()?;
+    for import in parser.predict_imports() {
+        let import_path = resolve_import(path, import);
+        let Ok(import_code) = workspace.source(&import_path) else {
+            continue;
+        };
+
+        let found = find_in_file(
+            workspace,
+            &import_path,
+            &import_code,
+            symbol,
+            scope,
+            visited,
+        );
+        if found.is_some() {
+            return found;
+        }
+    }
+
+    None
+}
+
+/// Finds the declaration of a symbol within the given code.
+fn find_declaration(code: &str, symbol: &Symbol, scope: Option<&str>) -> Option<TokenPosition> {
+    let tokens: Vec<_> = Tokenizer::tokenize_source(code)
+        .ok()?
+        .into_iter()
+        .filter(|t| {
+            !matches!(
+                t.token.token_type,
+                TokenType::Comment | TokenType::BlockComment | TokenType::DocComment
+            )
+        })
+        .collect();
+
+    let mut depth = 0;
+    let mut current_def: Option<&str> = None;
+    let mut global = None;
+
+    for (index, token) in tokens.iter().enumerate() {
+        let name = tokens
+            .get(index + 1)
+            .filter(|t| t.token.token_type == TokenType::Identifier);
+
+        match token.token.token_type {
+            TokenType::OpenBrace => depth += 1,
+            TokenType::CloseBrace => {
+                depth -= 1;
+                if depth == 0 {
+                    current_def = None;
+                }
+            }
+            TokenType::DefKeyword if depth == 0 => {
+                current_def = name.map(|n| n.text.as_str());
+
+                if let (Symbol::Widget(widget), Some(name)) = (symbol, name)
+                    && &name.text == widget
+                {
+                    return Some(name.token.position);
+                }
+            }
+            TokenType::VarKeyword => {
+                let Some(name) = name else {
+                    continue;
+                };
+
+                match symbol {
+                    Symbol::Variable(var) | Symbol::Property(var) if &name.text == var => {
+                        if depth == 1 && current_def.is_some() && current_def == scope {
+                            return Some(name.token.position);
+                        }
+
+                        if depth == 0 && matches!(symbol, Symbol::Variable(_)) {
+                            global = global.or(Some(name.token.position));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    global
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::native::NATIVE_WIDGETS;
+
+    /// A document used to test the analysis of editor features.
+    const CODE: &str = r#"var accent = #f00;
+
+/// A title.
+def title {
+  var text = "Title";
+  layout p {
+    text: $text;
+    color: $accent;
+    output;
+  }
+}
+
+layout div {
+  with title {
+    text: "Hello";
+  }
+}
+"#;
+
+    /// Parses the test document.
+    fn module() -> Module {
+        let mut parser = NekoMaidParser::tokenize(CODE).unwrap();
+        for native in NATIVE_WIDGETS.iter() {
+            parser.register_native_widget(native.clone());
+        }
+        parser.finish().unwrap()
+    }
+
+    /// Returns the labels of the given completions.
+    fn labels(completions: Vec<Completion>) -> Vec<String> {
+        completions.into_iter().map(|c| c.label).collect()
+    }
+
+    #[test]
+    fn completes_by_context() {
+        let module = module();
+
+        let variables = labels(completions(CODE, 7, 12, &module));
+        assert!(variables.contains(&String::from("text")));
+        assert!(variables.contains(&String::from("accent")));
+
+        let properties = labels(completions(CODE, 15, 5, &module));
+        assert!(properties.contains(&String::from("text")));
+        assert!(properties.contains(&String::from("with")));
+        assert!(!properties.contains(&String::from("width")));
+
+        let widgets = labels(completions(CODE, 14, 8, &module));
+        assert!(widgets.contains(&String::from("title")));
+        assert!(widgets.contains(&String::from("div")));
+
+        let keywords = labels(completions(CODE, 12, 1, &module));
+        assert!(keywords.contains(&String::from("import")));
+    }
+
+    #[test]
+    fn completes_keywords_of_native_properties() {
+        let module = module();
+        let code = "layout div {\n  display: g\n  width: \n  with title {\n    text: \n";
+
+        let keywords = labels(completions(code, 2, 13, &module));
+        assert_eq!(keywords, ["block", "flex", "grid", "none"]);
+
+        assert_eq!(labels(completions(code, 3, 10, &module)), ["auto"]);
+        assert!(completions(code, 5, 11, &module).is_empty());
+    }
+
+    #[test]
+    fn hover_shows_defaults() {
+        let module = module();
+
+        let text = hover(CODE, 15, 5, &module).unwrap();
+        assert!(text.contains(r#"text: "Title";"#));
+
+        let text = hover(CODE, 14, 10, &module).unwrap();
+        assert!(text.contains("def title"));
+        assert!(text.contains("A title."));
+    }
+
+    #[test]
+    fn finds_definitions() {
+        let mut workspace = Workspace::new();
+        let path = Path::new("ui/menu.neko_ui");
+        workspace.set_source(path, CODE.to_string());
+
+        let (_, position) = definition(&workspace, path, 14, 10).unwrap();
+        assert_eq!((position.line, position.column), (4, 5));
+
+        let (_, position) = definition(&workspace, path, 7, 13).unwrap();
+        assert_eq!((position.line, position.column), (5, 7));
+
+        let (_, position) = definition(&workspace, path, 8, 15).unwrap();
+        assert_eq!((position.line, position.column), (1, 5));
+
+        let (_, position) = definition(&workspace, path, 15, 6).unwrap();
+        assert_eq!((position.line, position.column), (5, 7));
+    }
+
+    #[test]
+    fn finds_definitions_in_imports() {
+        let mut workspace = Workspace::new();
+        let path = Path::new("ui/menu.neko_ui");
+        workspace.set_source(
+            path,
+            String::from("import \"lib/title\";\nlayout title {}\n"),
+        );
+        workspace.set_source(Path::new("ui/lib/title.neko_ui"), CODE.to_string());
+
+        let (file, position) = definition(&workspace, path, 2, 9).unwrap();
+        assert_eq!(file, PathBuf::from("ui/lib/title.neko_ui"));
+        assert_eq!((position.line, position.column), (4, 5));
+    }
+}
@@ -0,0 +1,9 @@
+//! A language server for NekoMaid UI files.
+//!
+//! The [`analysis`] module implements the editor features on top of the
+//! parser, while the [`server`] module, enabled by the `lsp` feature, exposes
+//! them over the Language Server Protocol.
+
+pub mod analysis;
+#[cfg(feature = "lsp")]
+pub mod server;
@@ -0,0 +1,433 @@
+//! The language server, communicating with the editor over stdio.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use lsp_server::{Connection, Message, Notification, Request, RequestId, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument,
+    DidCloseTextDocument,
+    DidOpenTextDocument,
+    DidSaveTextDocument,
+    Notification as _,
+    PublishDiagnostics,
+};
+use lsp_types::request::{Completion, GotoDefinition, HoverRequest, Request as _};
+use lsp_types::{
+    CompletionItem,
+    CompletionItemKind,
+    CompletionOptions,
+    Diagnostic,
+    DiagnosticSeverity,
+    GotoDefinitionResponse,
+    Hover,
+    HoverContents,
+    HoverProviderCapability,
+    InitializeParams,
+    Location,
+    MarkupContent,
+    MarkupKind,
+    OneOf,
+    Position,
+    PublishDiagnosticsParams,
+    Range,
+    SaveOptions,
+    ServerCapabilities,
+    TextDocumentPositionParams,
+    TextDocumentSyncCapability,
+    TextDocumentSyncKind,
+    TextDocumentSyncOptions,
+    TextDocumentSyncSaveOptions,
+    Uri,
+};
+
+use crate::NekoMaidParser;
+use crate::lsp::analysis::{self, CompletionKind};
+use crate::manifest::{MANIFEST_FILE, Manifest};
+use crate::module::Module;
+use crate::native::NATIVE_WIDGETS;
+use crate::token::TokenPosition;
+use crate::workspace::{Workspace, WorkspaceError};
+
+/// Runs the language server over stdio until the editor shuts it down.
+///
+/// The native widgets and global properties registered by the application are
+/// read from the [`MANIFEST_FILE`] in the root of each workspace folder, if
+/// present, when the server starts.
+pub fn run() {
+    let (connection, io_threads) = Connection::stdio();
+
+    let capabilities = ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Options(
+            TextDocumentSyncOptions {
+                open_close: Some(true),
+                change: Some(TextDocumentSyncKind::FULL),
+                save: Some(TextDocumentSyncSaveOptions::SaveOptions(SaveOptions {
+                    include_text: Some(false),
+                })),
+                ..Default::default()
+            },
+        )),
+        completion_provider: Some(CompletionOptions {
+            trigger_characters: Some(vec![
+                String::from("$"),
+                String::from("+"),
+                String::from("!"),
+            ]),
+            ..Default::default()
+        }),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        definition_provider: Some(OneOf::Left(true)),
+        ..Default::default()
+    };
+
+    let capabilities = serde_json::to_value(capabilities).unwrap();
+    let params = match connection.initialize(capabilities) {
+        Ok(params) => params,
+        Err(e) => {
+            eprintln!("Failed to initialize the language server: {}", e);
+            return;
+        }
+    };
+
+    let mut workspace = Workspace::new();
+    let folders = serde_json::from_value::<InitializeParams>(params)
+        .ok()
+        .and_then(|params| params.workspace_folders)
+        .unwrap_or_default();
+
+    for folder in folders {
+        let Some(path) = uri_to_path(&folder.uri).map(|p| p.join(MANIFEST_FILE)) else {
+            continue;
+        };
+
+        if path.is_file() {
+            match Manifest::load(&path) {
+                Ok(manifest) => manifest.register(&mut workspace),
+                Err(e) => eprintln!("{}: {}", path.display(), e),
+            }
+        }
+    }
+
+    let mut server = Server {
+        connection,
+        workspace,
+        modules: HashMap::new(),
+    };
+
+    if let Err(e) = server.main_loop() {
+        eprintln!("Language server error: {}", e);
+    }
+
+    drop(server);
+    io_threads.join().ok();
+}
+
+/// The state of the language server.
+struct Server {
+    /// The connection to the editor.
+    connection: Connection,
+
+    /// The workspace containing all open documents.
+    workspace: Workspace,
+
+    /// The last successfully parsed module of each open document, used to
+    /// provide completions while the document contains errors.
+    modules: HashMap<PathBuf, Module>,
+}
+
+impl Server {
+    /// Handles messages from the editor until it requests a shutdown.
+    fn main_loop(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        while let Ok(message) = self.connection.receiver.recv() {
+            match message {
+                Message::Request(request) => {
+                    if self.connection.handle_shutdown(&request)? {
+                        return Ok(());
+                    }
+                    self.handle_request(request)?;
+                }
+                Message::Notification(notification) => self.handle_notification(notification)?,
+                Message::Response(_) => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Handles a request from the editor, sending back a response.
+    fn handle_request(&mut self, request: Request) -> Result<(), Box<dyn std::error::Error>> {
+        let result = match request.method.as_str() {
+            Completion::METHOD => {
+                let params: lsp_types::CompletionParams = serde_json::from_value(request.params)?;
+                serde_json::to_value(self.completion(params.text_document_position))?
+            }
+            HoverRequest::METHOD => {
+                let params: lsp_types::HoverParams = serde_json::from_value(request.params)?;
+                serde_json::to_value(self.hover(params.text_document_position_params))?
+            }
+            GotoDefinition::METHOD => {
+                let params: lsp_types::GotoDefinitionParams =
+                    serde_json::from_value(request.params)?;
+                serde_json::to_value(self.definition(params.text_document_position_params))?
+            }
+            _ => {
+                let response = Response::new_err(
+                    request.id,
+                    lsp_server::ErrorCode::MethodNotFound as i32,
+                    format!("Unsupported request: {}", request.method),
+                );
+                self.connection.sender.send(Message::Response(response))?;
+                return Ok(());
+            }
+        };
+
+        self.respond(request.id, result)
+    }
+
+    /// Sends a successful response to the editor.
+    fn respond(
+        &self,
+        id: RequestId,
+        result: serde_json::Value,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let response = Response {
+            id,
+            result: Some(result),
+            error: None,
+        };
+        self.connection.sender.send(Message::Response(response))?;
+        Ok(())
+    }
+
+    /// Handles a notification from the editor.
+    fn handle_notification(
+        &mut self,
+        notification: Notification,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params: lsp_types::DidOpenTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+                let uri = params.text_document.uri;
+
+                if let Some(path) = uri_to_path(&uri) {
+                    self.workspace.set_source(&path, params.text_document.text);
+                    self.publish_diagnostics(uri, &path)?;
+                }
+            }
+            DidChangeTextDocument::METHOD => {
+                let params: lsp_types::DidChangeTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+
+                if let Some(path) = uri_to_path(&params.text_document.uri)
+                    && let Some(change) = params.content_changes.into_iter().last()
+                {
+                    self.workspace.set_source(&path, change.text);
+                }
+            }
+            DidSaveTextDocument::METHOD => {
+                let params: lsp_types::DidSaveTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+                let uri = params.text_document.uri;
+
+                if let Some(path) = uri_to_path(&uri) {
+                    self.publish_diagnostics(uri, &path)?;
+                }
+            }
+            DidCloseTextDocument::METHOD => {
+                let params: lsp_types::DidCloseTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+
+                if let Some(path) = uri_to_path(&params.text_document.uri) {
+                    self.workspace.remove_source(&path);
+                    self.modules.remove(&path);
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Parses the document at the given path and publishes its errors.
+    fn publish_diagnostics(
+        &mut self,
+        uri: Uri,
+        path: &Path,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let diagnostics = match self.workspace.load(path) {
+            Ok(module) => {
+                self.modules.insert(path.to_path_buf(), module);
+                Vec::new()
+            }
+            Err(error) => vec![self.diagnostic(path, &error)],
+        };
+
+        let params = PublishDiagnosticsParams {
+            uri,
+            diagnostics,
+            version: None,
+        };
+
+        let notification = Notification::new(PublishDiagnostics::METHOD.to_string(), params);
+        self.connection
+            .sender
+            .send(Message::Notification(notification))?;
+        Ok(())
+    }
+
+    /// Converts an error loading the document at the given path into a
+    /// diagnostic.
+    ///
+    /// Errors within imported files are reported at the import statement.
+    fn diagnostic(&self, path: &Path, error: &WorkspaceError) -> Diagnostic {
+        let (position, message) = match error {
+            WorkspaceError::Parse { error, .. } => (error.position(), error.to_string()),
+            WorkspaceError::Import { import, .. } => {
+                (self.import_position(path, import), error.to_string())
+            }
+            _ => (None, error.to_string()),
+        };
+
+        Diagnostic {
+            range: position.map(to_range).unwrap_or_default(),
+            severity: Some(DiagnosticSeverity::ERROR),
+            source: Some(String::from("neko-maid")),
+            message,
+            ..Default::default()
+        }
+    }
+
+    /// Finds the position of the import statement with the given name within
+    /// the document at the given path.
+    fn import_position(&self, path: &Path, import: &str) -> Option<TokenPosition> {
+        let code = self.workspace.source(path).ok()?;
+        let quoted = format!("\"{}\"", import);
+
+        code.lines().enumerate().find_map(|(line, text)| {
+            let column = text.find(&quoted)?;
+            Some(TokenPosition {
+                line: line + 1,
+                column: text[.. column].chars().count() + 1,
+                length: quoted.chars().count(),
+            })
+        })
+    }
+
+    /// Returns the module used to analyze the document at the given path.
+    ///
+    /// If the document has never been parsed successfully, a module with only
+    /// the native widgets, including those declared by the manifest, is
+    /// returned.
+    fn module(&mut self, path: &Path) -> Module {
+        if let Ok(module) = self.workspace.load(path) {
+            self.modules.insert(path.to_path_buf(), module);
+        }
+
+        if let Some(module) = self.modules.get(path) {
+            return module.clone();
+        }
+
+        let mut parser = NekoMaidParser::tokenize("").unwrap();
+        for native in NATIVE_WIDGETS.iter().chain(self.workspace.native_widgets()) {
+            parser.register_native_widget(native.clone());
+        }
+        parser.finish().unwrap()
+    }
+
+    /// Handles a completion request.
+    fn completion(&mut self, params: TextDocumentPositionParams) -> Option<Vec<CompletionItem>> {
+        let path = uri_to_path(&params.text_document.uri)?;
+        let code = self.workspace.source(&path).ok()?;
+        let module = self.module(&path);
+        let (line, column) = from_position(params.position);
+
+        let items = analysis::completions(&code, line, column, &module)
+            .into_iter()
+            .map(|completion| CompletionItem {
+                label: completion.label,
+                kind: Some(match completion.kind {
+                    CompletionKind::Keyword => CompletionItemKind::KEYWORD,
+                    CompletionKind::Widget => CompletionItemKind::CLASS,
+                    CompletionKind::Property => CompletionItemKind::PROPERTY,
+                    CompletionKind::Variable => CompletionItemKind::VARIABLE,
+                    CompletionKind::Class => CompletionItemKind::ENUM_MEMBER,
+                }),
+                detail: completion.detail,
+                ..Default::default()
+            })
+            .collect();
+
+        Some(items)
+    }
+
+    /// Handles a hover request.
+    fn hover(&mut self, params: TextDocumentPositionParams) -> Option<Hover> {
+        let path = uri_to_path(&params.text_document.uri)?;
+        let code = self.workspace.source(&path).ok()?;
+        let module = self.module(&path);
+        let (line, column) = from_position(params.position);
+
+        let text = analysis::hover(&code, line, column, &module)?;
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: text,
+            }),
+            range: None,
+        })
+    }
+
+    /// Handles a go-to-definition request.
+    fn definition(&self, params: TextDocumentPositionParams) -> Option<GotoDefinitionResponse> {
+        let path = uri_to_path(&params.text_document.uri)?;
+        let (line, column) = from_position(params.position);
+
+        let (file, position) = analysis::definition(&self.workspace, &path, line, column)?;
+        Some(GotoDefinitionResponse::Scalar(Location {
+            uri: path_to_uri(&file)?,
+            range: to_range(position),
+        }))
+    }
+}
+
+/// Converts a `file://` URI into a file system path.
+fn uri_to_path(uri: &Uri) -> Option<PathBuf> {
+    if uri.scheme()?.as_str() != "file" {
+        return None;
+    }
+
+    let path = uri.path().as_estr().decode().into_string().ok()?;
+    Some(PathBuf::from(path.as_ref()))
+}
+
+/// Converts a file system path into a `file://` URI.
+fn path_to_uri(path: &Path) -> Option<Uri> {
+    let path = std::path::absolute(path).ok()?;
+    let path = path.to_str()?.replace('%', "%25").replace(' ', "%20");
+    Uri::from_str(&format!("file://{}", path)).ok()
+}
+
+/// Converts a zero-based LSP position into a one-based line and column.
+fn from_position(position: Position) -> (usize, usize) {
+    (position.line as usize + 1, position.character as usize + 1)
+}
+
+/// Converts a token position into a zero-based LSP range.
+fn to_range(position: TokenPosition) -> Range {
+    let line = position.line.saturating_sub(1) as u32;
+    let start = position.column.saturating_sub(1) as u32;
+
+    Range {
+        start: Position {
+            line,
+            character: start,
+        },
+        end: Position {
+            line,
+            character: start + position.length as u32,
+        },
+    }
+}
@@ -0,0 +1,354 @@
+//! A description of the native widgets, global properties, and class markers
+//! registered by an application, for use by tooling.
+//!
+//! Applications may register native widgets and class markers of their own,
+//! which the command-line tools and the language server cannot discover on
+//! their own. A manifest declares them in a JSON file, such as:
+//!
+//! ```json
+//! {
+//!   "widgets": {
+//!     "health_bar": { "value": 1, "fill": "#cc1a1a", "fill-from": "left" }
+//!   },
+//!   "keywords": {
+//!     "health_bar": { "fill-from": ["left", "right", "middle"] }
+//!   },
+//!   "properties": ["frame-width"],
+//!   "markers": ["test"]
+//! }
+//! ```
+//!
+//! Widget properties are given with their default values, which may be
+//! numbers, booleans, colors written as `#` followed by hexadecimal digits, or
+//! strings. Properties that take keywords, such as enum fields, list the
+//! keywords they accept so that tooling can suggest and describe them.
+//!
+//! Rather than being written by hand, the manifest is best written from the
+//! widgets and markers the application registers, such as with
+//! `ManifestAppExt::manifest` in the Bevy plugin.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use serde::{Deserialize, Deserializer, Serializer};
+
+use crate::color::Rgba;
+use crate::value::PropertyValue;
+use crate::widget::NativeWidget;
+use crate::workspace::Workspace;
+
+/// The name of the manifest file looked up in the root of a workspace.
+pub const MANIFEST_FILE: &str = "neko_maid.json";
+
+/// The native widgets, global properties, and class markers registered by an
+/// application.
+#[derive(Debug, Default, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Manifest {
+    /// The native widgets registered by the application, mapping each widget
+    /// name to its properties and their default values.
+    pub widgets: BTreeMap<String, BTreeMap<String, ManifestValue>>,
+
+    /// The keywords accepted by the properties of the native widgets, mapping
+    /// each widget name to its properties and their keywords. Properties that
+    /// are not listed accept any string.
+    pub keywords: BTreeMap<String, BTreeMap<String, Vec<String>>>,
+
+    /// The properties accepted by every widget, such as the properties of
+    /// class markers.
+    pub properties: Vec<String>,
+
+    /// The names of the class markers registered by the application.
+    pub markers: Vec<String>,
+}
+
+/// The default value of a native widget property within a [`Manifest`].
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(untagged)]
+pub enum ManifestValue {
+    /// A boolean value.
+    Bool(bool),
+
+    /// A numeric value.
+    Number(f64),
+
+    /// A color value, written as a string in hexadecimal notation with a
+    /// leading `#`.
+    #[serde(
+        serialize_with = "serialize_color",
+        deserialize_with = "deserialize_color"
+    )]
+    Color(Rgba),
+
+    /// A string value.
+    String(String),
+}
+
+impl ManifestValue {
+    /// Converts a default property value, returning `None` if the value
+    /// cannot be written in a manifest.
+    pub fn from_property_value(value: &PropertyValue) -> Option<Self> {
+        match value {
+            PropertyValue::Bool(value) => Some(ManifestValue::Bool(*value)),
+            PropertyValue::Number(value) => Some(ManifestValue::Number(*value)),
+            PropertyValue::Color(value) => Some(ManifestValue::Color(*value)),
+            PropertyValue::String(value) => Some(ManifestValue::String(value.clone())),
+            _ => None,
+        }
+    }
+}
+
+/// Writes a color in hexadecimal notation.
+fn serialize_color<S: Serializer>(color: &Rgba, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&color.to_hex())
+}
+
+/// Reads a color written in hexadecimal notation with a leading `#`. Other
+/// strings are rejected, so that they are read as string values.
+fn deserialize_color<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Rgba, D::Error> {
+    let hex = String::deserialize(deserializer)?;
+    hex.strip_prefix('#')
+        .and_then(Rgba::from_hex)
+        .ok_or_else(|| serde::de::Error::custom(format!("invalid color {hex}")))
+}
+
+impl From<&ManifestValue> for PropertyValue {
+    fn from(value: &ManifestValue) -> Self {
+        match value {
+            ManifestValue::Bool(value) => PropertyValue::Bool(*value),
+            ManifestValue::Number(value) => PropertyValue::Number(*value),
+            ManifestValue::Color(value) => PropertyValue::Color(*value),
+            ManifestValue::String(value) => PropertyValue::String(value.clone()),
+        }
+    }
+}
+
+impl Manifest {
+    /// Parses a manifest from JSON.
+    pub fn from_json(json: &str) -> Result<Self, ManifestError> {
+        serde_json::from_str(json).map_err(|e| ManifestError::Parse {
+            message: e.to_string(),
+        })
+    }
+
+    /// Reads and parses the manifest file at the given path.
+    pub fn load(path: &Path) -> Result<Self, ManifestError> {
+        let json = fs::read_to_string(path).map_err(|e| ManifestError::Io {
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        })?;
+
+        Self::from_json(&json)
+    }
+
+    /// Serializes this manifest as pretty-printed JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("Manifest is always serializable")
+    }
+
+    /// Declares the given native widget, along with the default values of its
+    /// properties and the keywords they accept.
+    pub fn add_native_widget(&mut self, widget: &NativeWidget) -> Result<(), ManifestError> {
+        let mut properties = BTreeMap::new();
+        for (property, value) in widget.default_properties.iter() {
+            let Some(value) = ManifestValue::from_property_value(value) else {
+                return Err(ManifestError::UnsupportedValue {
+                    widget: widget.name.clone(),
+                    property: property.clone(),
+                    value: value.to_string(),
+                });
+            };
+
+            properties.insert(property.clone(), value);
+        }
+
+        self.widgets.insert(widget.name.clone(), properties);
+
+        if !widget.keywords.is_empty() {
+            let keywords = widget.keywords.iter().map(|(k, v)| (k.clone(), v.clone()));
+            self.keywords
+                .insert(widget.name.clone(), keywords.collect());
+        }
+
+        Ok(())
+    }
+
+    /// Returns the native widgets declared by this manifest.
+    pub fn native_widgets(&self) -> Vec<NativeWidget> {
+        self.widgets
+            .iter()
+            .map(|(name, properties)| NativeWidget {
+                name: name.clone(),
+                default_properties: Arc::new(
+                    properties
+                        .iter()
+                        .map(|(property, value)| (property.clone(), value.into()))
+                        .collect::<HashMap<_, _>>(),
+                ),
+                keywords: Arc::new(
+                    self.keywords
+                        .get(name)
+                        .into_iter()
+                        .flatten()
+                        .map(|(property, keywords)| (property.clone(), keywords.clone()))
+                        .collect(),
+                ),
+            })
+            .collect()
+    }
+
+    /// Registers the native widgets and global properties declared by this
+    /// manifest with the given workspace.
+    pub fn register(&self, workspace: &mut Workspace) {
+        for widget in self.native_widgets() {
+            workspace.register_native_widget(widget);
+        }
+
+        for property in &self.properties {
+            workspace.register_global_property(property.clone());
+        }
+    }
+}
+
+/// Errors that can occur while loading a [`Manifest`].
+#[derive(Debug, thiserror::Error, Clone, PartialEq)]
+pub enum ManifestError {
+    /// The manifest file could not be read.
+    #[error("Failed to read {}: {message}", path.display())]
+    Io {
+        /// The path of the file.
+        path: PathBuf,
+
+        /// The error message.
+        message: String,
+    },
+
+    /// The manifest is not valid.
+    #[error("Invalid manifest: {message}")]
+    Parse {
+        /// The error message.
+        message: String,
+    },
+
+    /// The default value of a native widget property cannot be written in a
+    /// manifest.
+    #[error("The default value {value} of {widget}.{property} cannot be written in a manifest")]
+    UnsupportedValue {
+        /// The name of the widget.
+        widget: String,
+
+        /// The name of the property.
+        property: String,
+
+        /// The default value.
+        value: String,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn registered_widgets_and_properties_load() {
+        let manifest = Manifest::from_json(
+            r#"{
+                "widgets": { "health_bar": { "value": 1, "fill-from": "left" } },
+                "properties": ["frame-width"],
+                "markers": ["test"]
+            }"#,
+        )
+        .unwrap();
+
+        let widgets = manifest.native_widgets();
+        assert_eq!(widgets.len(), 1);
+        assert_eq!(widgets[0].name, "health_bar");
+        assert_eq!(
+            widgets[0].default_properties.get("value"),
+            Some(&PropertyValue::Number(1.0))
+        );
+        assert_eq!(manifest.markers, vec![String::from("test")]);
+
+        let path = std::env::temp_dir().join("neko_maid_manifest_test.neko_ui");
+        let mut workspace = Workspace::new();
+        workspace.set_source(
+            &path,
+            String::from("layout health_bar { value: 0.5; frame-width: 4; }"),
+        );
+        assert!(workspace.load(&path).is_err());
+
+        manifest.register(&mut workspace);
+        assert!(workspace.load(&path).is_ok());
+    }
+
+    #[test]
+    fn colors_load_and_round_trip() {
+        let manifest = Manifest::from_json(
+            r##"{ "widgets": { "health_bar": { "fill": "#cc1a1a", "label": "cc1a1a" } } }"##,
+        )
+        .unwrap();
+
+        let widget = &manifest.native_widgets()[0];
+        assert_eq!(
+            widget.default_properties.get("fill"),
+            Some(&PropertyValue::Color(Rgba::rgba_u8(0xcc, 0x1a, 0x1a, 0xff)))
+        );
+        assert_eq!(
+            widget.default_properties.get("label"),
+            Some(&PropertyValue::String(String::from("cc1a1a")))
+        );
+
+        let mut dumped = Manifest::default();
+        dumped.add_native_widget(widget).unwrap();
+        assert_eq!(dumped, manifest);
+        assert_eq!(Manifest::from_json(&dumped.to_json()).unwrap(), manifest);
+    }
+
+    #[test]
+    fn keywords_load_and_round_trip() {
+        let manifest = Manifest::from_json(
+            r#"{
+                "widgets": { "health_bar": { "value": 1, "fill-from": "left" } },
+                "keywords": { "health_bar": { "fill-from": ["left", "right", "middle"] } }
+            }"#,
+        )
+        .unwrap();
+
+        let widget = &manifest.native_widgets()[0];
+        assert_eq!(
+            widget.property_keywords("fill-from"),
+            &["left", "right", "middle"]
+        );
+        assert!(widget.property_keywords("value").is_empty());
+
+        let mut dumped = Manifest::default();
+        dumped.add_native_widget(widget).unwrap();
+        assert_eq!(dumped, manifest);
+    }
+
+    #[test]
+    fn unsupported_default_values_are_rejected() {
+        let widget = NativeWidget {
+            name: String::from("minimap"),
+            default_properties: Arc::new(HashMap::from([(
+                String::from("zoom"),
+                PropertyValue::Pixels(2.0),
+            )])),
+            keywords: Arc::default(),
+        };
+
+        assert!(matches!(
+            Manifest::default().add_native_widget(&widget),
+            Err(ManifestError::UnsupportedValue { .. })
+        ));
+    }
+
+    #[test]
+    fn unknown_fields_are_rejected() {
+        assert!(Manifest::from_json(r#"{ "widget": {} }"#).is_err());
+    }
+}
@@ -0,0 +1,188 @@
+//! Inline markup for the text of `p` widgets.
+//!
+//! Markup uses square-bracket tags, such as
+//! `"Deal [b]50[/b] [color=#f00]fire[/color] damage"`. The supported tags are:
+//!
+//! - `[b]` for bold text,
+//! - `[i]` for italic text,
+//! - `[color=#rrggbb]` for colored text, and
+//! - `[size=20]` for a different font size.
+//!
+//! Tags may be nested. A closing tag closes the most recent matching open tag
+//! along with any tags opened after it. Brackets that do not form a known tag
+//! are kept as text, and `[[` can be used to write a literal `[`.
+
+use crate::color::Rgba;
+
+/// The style of a run of markup text. Unset fields are inherited from the
+/// widget the text belongs to.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct MarkupStyle {
+    /// Whether the text is bold.
+    pub bold: bool,
+
+    /// Whether the text is italic.
+    pub italic: bool,
+
+    /// The color of the text.
+    pub color: Option<Rgba>,
+
+    /// The font size of the text.
+    pub size: Option<f64>,
+}
+
+impl MarkupStyle {
+    /// Returns whether this style does not override anything.
+    pub fn is_plain(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// A run of text with a single style.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarkupSpan {
+    /// The text of the span, without any tags.
+    pub text: String,
+
+    /// The style of the span.
+    pub style: MarkupStyle,
+}
+
+/// Parses the markup of the given text into a list of styled spans.
+///
+/// Consecutive runs of text with the same style are merged, and empty runs
+/// are omitted.
+pub fn parse_markup(text: &str) -> Vec<MarkupSpan> {
+    let mut spans: Vec<MarkupSpan> = Vec::new();
+    let mut stack: Vec<(&str, MarkupStyle)> = Vec::new();
+    let mut style = MarkupStyle::default();
+    let mut rest = text;
+
+    let mut push = |text: &str, style: MarkupStyle| {
+        if text.is_empty() {
+            return;
+        }
+        match spans.last_mut() {
+            Some(last) if last.style == style => last.text.push_str(text),
+            _ => spans.push(MarkupSpan {
+                text: text.to_string(),
+                style,
+            }),
+        }
+    };
+
+    while let Some(open) = rest.find('[') {
+        push(&rest[.. open], style);
+        rest = &rest[open ..];
+
+        if let Some(after) = rest.strip_prefix("[[") {
+            push("[", style);
+            rest = after;
+            continue;
+        }
+
+        let Some(close) = rest.find(']') else {
+            break;
+        };
+        let tag = &rest[1 .. close];
+
+        if let Some(name) = tag.strip_prefix('/') {
+            if let Some(index) = stack.iter().rposition(|(open, _)| *open == name) {
+                style = stack[index].1;
+                stack.truncate(index);
+                rest = &rest[close + 1 ..];
+                continue;
+            }
+        } else if let Some(new_style) = apply_tag(tag, style) {
+            let name = tag.split('=').next().unwrap_or(tag);
+            stack.push((name, style));
+            style = new_style;
+            rest = &rest[close + 1 ..];
+            continue;
+        }
+
+        // Not a tag, so the bracket is kept as text.
+        push("[", style);
+        rest = &rest[1 ..];
+    }
+
+    push(rest, style);
+    spans
+}
+
+/// Returns the given style with an opening tag applied, or `None` if the tag
+/// is unknown or has an invalid value.
+fn apply_tag(tag: &str, mut style: MarkupStyle) -> Option<MarkupStyle> {
+    match tag.split_once('=') {
+        None if tag == "b" => style.bold = true,
+        None if tag == "i" => style.italic = true,
+        Some(("color", value)) => style.color = Some(Rgba::from_hex(value.trim())?),
+        Some(("size", value)) => style.size = Some(value.trim().parse().ok()?),
+        _ => return None,
+    }
+    Some(style)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates a span with the given text and style.
+    fn span(text: &str, style: MarkupStyle) -> MarkupSpan {
+        MarkupSpan {
+            text: text.to_string(),
+            style,
+        }
+    }
+
+    #[test]
+    fn markup_is_parsed() {
+        let bold = MarkupStyle {
+            bold: true,
+            ..Default::default()
+        };
+        let red = MarkupStyle {
+            color: Some(Rgba::new(1.0, 0.0, 0.0, 1.0)),
+            ..Default::default()
+        };
+        let plain = MarkupStyle::default();
+
+        assert_eq!(
+            parse_markup("Deal [b]50[/b] [color=#f00]fire[/color] damage"),
+            vec![
+                span("Deal ", plain),
+                span("50", bold),
+                span(" ", plain),
+                span("fire", red),
+                span(" damage", plain),
+            ]
+        );
+
+        assert_eq!(
+            parse_markup("[b]a[size=20]b[/b]c"),
+            vec![
+                span("a", bold),
+                span(
+                    "b",
+                    MarkupStyle {
+                        bold: true,
+                        size: Some(20.0),
+                        ..Default::default()
+                    }
+                ),
+                span("c", plain),
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_markup_is_kept_as_text() {
+        let plain = MarkupStyle::default();
+
+        assert_eq!(
+            parse_markup("[Press E] [[b] [/i] [color=red] [b"),
+            vec![span("[Press E] [b] [/i] [color=red] [b", plain)]
+        );
+        assert_eq!(parse_markup(""), vec![]);
+    }
+}
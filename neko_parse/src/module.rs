@@ -0,0 +1,858 @@
+//! Module parsing functionality.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::NekoMaidParseError;
+use crate::context::{NekoResult, ParseContext};
+use crate::element::{NekoElementBuilder, build_element};
+use crate::font::{FontFace, parse_font_face};
+use crate::import::parse_import;
+use crate::layout::{Layout, parse_layout, parse_layout_name};
+use crate::native::AssetKind;
+use crate::property::{UnresolvedPropertyValue, parse_variable};
+use crate::style::{Selector, Style, parse_style};
+use crate::token::{TokenPosition, TokenType};
+use crate::value::PropertyValue;
+use crate::visibility::{Visibility, parse_visibility};
+use crate::widget::{Widget, parse_widget};
+
+/// A NekoMaid UI module.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Module {
+    /// A map of defined variables and their values.
+    pub variables: HashMap<String, UnresolvedPropertyValue>,
+
+    /// A list of defined styles.
+    ///
+    /// Styles later in the list have higher precedence.
+    pub styles: Vec<Style>,
+
+    /// A map of available widgets. (Both native and user-defined)
+    pub widgets: HashMap<String, Widget>,
+
+    /// A list of elements defined in this module, ready to be instantiated.
+    pub elements: Vec<NekoElementBuilder>,
+
+    /// A map of named elements defined in this module, ready to be
+    /// instantiated on request.
+    pub named_elements: HashMap<String, NekoElementBuilder>,
+
+    /// The names of variables that are not exported to importing modules.
+    pub private_variables: HashSet<String>,
+
+    /// The names of widgets that are not exported to importing modules.
+    pub private_widgets: HashSet<String>,
+
+    /// A map of declared font faces, including those of imported modules.
+    ///
+    /// Font faces are always exported to importing modules.
+    pub fonts: HashMap<String, FontFace>,
+
+    /// A map of the asset paths written in this module and its imports, and
+    /// the kind of asset they refer to.
+    ///
+    /// Paths given through variables are included if the variable is given a
+    /// literal path, such as the default of a custom widget property.
+    pub assets: HashMap<String, AssetKind>,
+}
+
+impl Module {
+    /// Builds an element from the widget with the given name, using the given
+    /// properties in place of the widget defaults.
+    ///
+    /// The element is styled with the styles defined in this module.
+    pub fn build_widget(
+        &self,
+        widget: &str,
+        properties: &HashMap<String, PropertyValue>,
+    ) -> NekoResult<NekoElementBuilder> {
+        let Some(definition) = self.widgets.get(widget) else {
+            return Err(NekoMaidParseError::UnknownWidget {
+                widget: widget.to_string(),
+                position: TokenPosition::default(),
+            });
+        };
+
+        let mut layout = Layout::new(widget.to_string());
+
+        for (name, value) in properties {
+            if !definition.has_property(name) {
+                return Err(NekoMaidParseError::InvalidProperty {
+                    property: name.clone(),
+                    widget: widget.to_string(),
+                    position: TokenPosition::default(),
+                });
+            }
+
+            layout.properties.insert(
+                name.clone(),
+                UnresolvedPropertyValue::Constant(value.clone()),
+            );
+        }
+
+        build_element(&self.styles, &self.widgets, layout, None)
+    }
+}
+
+/// Parses a module from the given parse context.
+pub(super) fn parse_module(mut ctx: ParseContext) -> NekoResult<Module> {
+    while let Some(next) = ctx.peek() {
+        match next.token_type {
+            TokenType::ImportKeyword => parse_import(&mut ctx)?,
+            TokenType::FontFaceKeyword => {
+                let font = parse_font_face(&mut ctx)?;
+                ctx.add_font(font);
+            }
+            TokenType::LayoutKeyword => {
+                let name = parse_layout_name(&mut ctx)?;
+                let layout = parse_layout(&mut ctx)?;
+
+                match name {
+                    Some(name) => ctx.add_named_layout(name, layout),
+                    None => ctx.add_layout(layout),
+                }
+            }
+            _ => parse_declaration(&mut ctx)?,
+        }
+    }
+
+    ctx.into_module()
+}
+
+/// Parses a variable, widget, or style declaration, optionally preceded by a
+/// visibility modifier.
+fn parse_declaration(ctx: &mut ParseContext) -> NekoResult<()> {
+    let visibility = parse_visibility(ctx);
+    let next = ctx.peek().ok_or(NekoMaidParseError::EndOfStream)?;
+
+    match next.token_type {
+        TokenType::VarKeyword => {
+            let variable = parse_variable(ctx)?;
+            ctx.declare_variable(variable.name, variable.value, visibility);
+        }
+        TokenType::DefKeyword => {
+            let widget = parse_widget(ctx)?;
+            ctx.declare_widget(widget, visibility);
+        }
+        TokenType::StyleKeyword => {
+            parse_style(ctx, Selector::default(), visibility)?;
+        }
+        _ => {
+            let mut expected = vec![
+                TokenType::VarKeyword.type_name().to_string(),
+                TokenType::DefKeyword.type_name().to_string(),
+                TokenType::StyleKeyword.type_name().to_string(),
+            ];
+
+            if visibility == Visibility::Public {
+                expected.insert(0, TokenType::ImportKeyword.type_name().to_string());
+                expected.push(TokenType::LayoutKeyword.type_name().to_string());
+                expected.push(TokenType::FontFaceKeyword.type_name().to_string());
+                expected.push(TokenType::PrivateKeyword.type_name().to_string());
+            }
+
+            return Err(NekoMaidParseError::UnexpectedToken {
+                expected,
+                found: next.token_type.type_name().to_string(),
+                position: next.position,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::NekoMaidParser;
+    use crate::color::Rgba;
+    use crate::element::NekoElement;
+    use crate::native::NATIVE_WIDGETS;
+
+    fn parse(code: &str) -> NekoResult<Module> {
+        parse_with_modules(code, Vec::new())
+    }
+
+    /// Resolves the properties of an element with the variables of the module.
+    fn resolve(module: &Module, element: &NekoElementBuilder) -> NekoElement {
+        let mut variables = HashMap::new();
+        for (name, value) in &module.variables {
+            variables.insert(name.clone(), value.resolve(&variables).unwrap());
+        }
+
+        let mut element = element.element.clone();
+        element.resolve(&variables).unwrap();
+        element
+    }
+
+    fn parse_with_modules(code: &str, modules: Vec<(&str, Module)>) -> NekoResult<Module> {
+        let mut parser = NekoMaidParser::tokenize(code)?;
+        for native in NATIVE_WIDGETS.iter() {
+            parser.register_native_widget(native.clone());
+        }
+        for (name, module) in modules {
+            parser.add_module(name.to_string(), module);
+        }
+        parser.finish()
+    }
+
+    const LIBRARY: &str = r#"
+        private var accent = #ff0000;
+
+        private style p {
+            color: $accent;
+        }
+
+        private def label {
+            var text = "";
+
+            layout p {
+                text: $text;
+                color: $accent;
+                output;
+            }
+        }
+
+        def title {
+            var text = "";
+
+            layout div {
+                with label {
+                    text: $text;
+                }
+
+                output;
+            }
+        }
+    "#;
+
+    #[test]
+    fn widgets_can_be_used_before_definition() {
+        let code = r#"
+            style title +big {
+                font-size: 32;
+            }
+
+            layout title {
+                class big;
+                text: "Hello";
+            }
+
+            def title {
+                var text = "";
+
+                layout div {
+                    with label {
+                        text: $text;
+                    }
+
+                    output;
+                }
+            }
+
+            def label {
+                var text = "";
+
+                layout p {
+                    text: $text;
+                    output;
+                }
+            }
+        "#;
+
+        let module = parse(code).unwrap();
+        assert_eq!(module.elements.len(), 1);
+    }
+
+    #[test]
+    fn unknown_widget_is_reported() {
+        let code = "layout missing {}";
+
+        assert!(matches!(
+            parse(code),
+            Err(NekoMaidParseError::UnknownWidget { widget, .. }) if widget == "missing"
+        ));
+    }
+
+    #[test]
+    fn invalid_property_is_reported_after_definition() {
+        let code = r#"
+            layout label {
+                color: #fff;
+            }
+
+            def label {
+                var text = "";
+
+                layout p {
+                    output;
+                }
+            }
+        "#;
+
+        assert!(matches!(
+            parse(code),
+            Err(NekoMaidParseError::InvalidProperty { property, .. }) if property == "color"
+        ));
+    }
+
+    #[test]
+    fn recursive_widgets_are_reported() {
+        let code = r#"
+            def a {
+                layout div {
+                    with b {}
+                    output;
+                }
+            }
+
+            def b {
+                layout a {
+                    output;
+                }
+            }
+        "#;
+
+        assert!(matches!(
+            parse(code),
+            Err(NekoMaidParseError::RecursiveWidget { .. })
+        ));
+    }
+
+    #[test]
+    fn private_items_are_not_exported() {
+        let library = parse(LIBRARY).unwrap();
+        assert!(library.private_widgets.contains("label"));
+        assert!(library.private_variables.contains("accent"));
+
+        let code = r#"
+            import "lib";
+
+            layout label {}
+        "#;
+
+        assert!(matches!(
+            parse_with_modules(code, vec![("lib", library.clone())]),
+            Err(NekoMaidParseError::UnknownWidget { widget, .. }) if widget == "label"
+        ));
+
+        let code = r#"
+            import "lib";
+
+            layout title {
+                text: "Hello";
+            }
+        "#;
+
+        let module = parse_with_modules(code, vec![("lib", library)]).unwrap();
+        assert!(!module.variables.contains_key("accent"));
+        assert!(module.variables.contains_key("lib::accent"));
+        assert_eq!(module.styles.len(), 1);
+        assert_eq!(module.styles[0].scope(), Some("lib"));
+        assert!(!module.widgets.contains_key("label"));
+        assert!(module.private_widgets.contains("lib::label"));
+    }
+
+    #[test]
+    fn private_names_can_be_reused_by_importers() {
+        let library = parse(LIBRARY).unwrap();
+
+        let code = r#"
+            import "lib";
+
+            var accent = 4px;
+
+            def label {
+                layout div {
+                    output;
+                }
+            }
+
+            layout title {}
+        "#;
+
+        let module = parse_with_modules(code, vec![("lib", library)]).unwrap();
+
+        let Some(Widget::Custom(title)) = module.widgets.get("title") else {
+            panic!("title should be a custom widget");
+        };
+        assert_eq!(title.layout.children[0].widget, "lib::label");
+
+        let Some(Widget::Custom(label)) = module.widgets.get("lib::label") else {
+            panic!("lib::label should be a custom widget");
+        };
+        assert_eq!(
+            label.layout.properties.get("color"),
+            Some(&UnresolvedPropertyValue::Variable("lib::accent".into()))
+        );
+    }
+
+    #[test]
+    fn private_styles_apply_to_library_elements() {
+        let library = r#"
+            private style p {
+                color: #ff0000;
+            }
+
+            def card {
+                layout div {
+                    with p {
+                        text: "Title";
+                    }
+
+                    output;
+                }
+            }
+        "#;
+
+        let code = r#"
+            import "lib";
+
+            layout card {
+                with p {
+                    text: "Body";
+                }
+            }
+
+            layout p {}
+        "#;
+
+        let module = parse_with_modules(code, vec![("lib", parse(library).unwrap())]).unwrap();
+        let card = &module.elements[0];
+        let title = &card.children[0];
+        let body = &card.children[1];
+
+        assert_eq!(title.element.styles().len(), 1);
+        assert_eq!(title.element.styles()[0].scope(), Some("lib"));
+        assert!(body.element.styles().is_empty());
+        assert!(module.elements[1].element.styles().is_empty());
+    }
+
+    #[test]
+    fn private_widget_selectors_are_renamed() {
+        let library = r#"
+            private def label {
+                layout p {
+                    output;
+                }
+            }
+
+            private style label {
+                color: #ff0000;
+            }
+
+            def title {
+                layout div {
+                    with label {}
+                    output;
+                }
+            }
+        "#;
+
+        let code = r#"
+            import "lib";
+
+            layout title {}
+        "#;
+
+        let module = parse_with_modules(code, vec![("lib", parse(library).unwrap())]).unwrap();
+        assert_eq!(
+            module.styles[0].selector().hierarchy[0].widget,
+            "lib::label"
+        );
+    }
+
+    #[test]
+    fn named_layouts_are_kept_separate() {
+        let code = r#"
+            def title {
+                var text = "";
+
+                layout p {
+                    text: $text;
+                    output;
+                }
+            }
+
+            layout div {}
+
+            layout main_menu div {
+                with title {
+                    text: "Main Menu";
+                }
+            }
+
+            layout credits title {
+                text: "Credits";
+            }
+        "#;
+
+        let module = parse(code).unwrap();
+        assert_eq!(module.elements.len(), 1);
+        assert_eq!(module.named_elements.len(), 2);
+        assert_eq!(module.named_elements["main_menu"].children.len(), 1);
+
+        let properties = HashMap::from([("text".to_string(), "Options".into())]);
+        let element = module.build_widget("title", &properties).unwrap();
+        assert_eq!(element.native_widget.name, "p");
+
+        let properties = HashMap::from([("color".to_string(), "red".into())]);
+        assert!(matches!(
+            module.build_widget("title", &properties),
+            Err(NekoMaidParseError::InvalidProperty { .. })
+        ));
+    }
+
+    #[test]
+    fn grid_functions_are_parsed() {
+        let code = r#"
+            var gap = 8px;
+
+            layout div {
+                grid-template-columns: repeat(auto-fill, minmax($gap, 1fr)) 2fr;
+                grid-row: 2 span 3;
+            }
+        "#;
+
+        let module = parse(code).unwrap();
+        let variables = HashMap::from([("gap".to_string(), PropertyValue::Pixels(8.0))]);
+        let mut element = module.elements[0].element.clone();
+        element.resolve(&variables).unwrap();
+
+        let function = |name: &str, args| PropertyValue::Function {
+            name: name.to_string(),
+            args,
+        };
+        let minmax = function(
+            "minmax",
+            vec![PropertyValue::Pixels(8.0), PropertyValue::Fraction(1.0)],
+        );
+        assert_eq!(
+            element.get_property("grid-template-columns"),
+            Some(&PropertyValue::List(vec![
+                function("repeat", vec!["auto-fill".into(), minmax]),
+                PropertyValue::Fraction(2.0),
+            ]))
+        );
+        assert_eq!(
+            element.get_property("grid-row"),
+            Some(&PropertyValue::List(vec![
+                2.into(),
+                "span".into(),
+                3.into()
+            ]))
+        );
+        assert_eq!(
+            element.get_property("grid-column"),
+            Some(&PropertyValue::String("auto".into()))
+        );
+    }
+
+    #[test]
+    fn comma_lists_are_parsed() {
+        let code = r#"
+            var glow = 0 0 4px #fff;
+
+            layout div {
+                box-shadow: 0 2px 8px #00000080, $glow;
+            }
+        "#;
+
+        let module = parse(code).unwrap();
+        let glow = PropertyValue::List(vec![
+            0.into(),
+            0.into(),
+            PropertyValue::Pixels(4.0),
+            Rgba::WHITE.into(),
+        ]);
+        let variables = HashMap::from([("glow".to_string(), glow.clone())]);
+        let mut element = module.elements[0].element.clone();
+        element.resolve(&variables).unwrap();
+
+        let shadow = PropertyValue::List(vec![
+            0.into(),
+            PropertyValue::Pixels(2.0),
+            PropertyValue::Pixels(8.0),
+            Rgba::from_hex("#00000080").unwrap().into(),
+        ]);
+        assert_eq!(
+            element.get_property("box-shadow"),
+            Some(&PropertyValue::CommaList(vec![shadow, glow]))
+        );
+    }
+
+    #[test]
+    fn asset_paths_are_resolved() {
+        let code = r#"
+            var icon = "close.png";
+
+            font-face body {
+                src: "Inter.ttf";
+            }
+
+            def card {
+                var src = "notes";
+
+                layout p {
+                    text: $src;
+                    output;
+                }
+            }
+
+            layout img {
+                src: "icons/close.png";
+                with p {
+                    font: body, "fonts/Inter.ttf";
+                }
+                with img {
+                    src: $icon;
+                }
+                with card {
+                    src: "more-notes";
+                }
+            }
+        "#;
+
+        let mut parser = NekoMaidParser::tokenize(code).unwrap();
+        for native in NATIVE_WIDGETS.iter() {
+            parser.register_native_widget(native.clone());
+        }
+        parser.set_asset_path_resolver(Box::new(|path| format!("ui/{}", path)));
+        let module = parser.finish().unwrap();
+
+        assert_eq!(module.fonts["body"].src, "ui/Inter.ttf");
+        assert_eq!(
+            module.assets,
+            HashMap::from([
+                ("ui/Inter.ttf".to_string(), AssetKind::Font),
+                ("ui/icons/close.png".to_string(), AssetKind::Image),
+                ("ui/fonts/Inter.ttf".to_string(), AssetKind::Font),
+                ("ui/close.png".to_string(), AssetKind::Image),
+            ])
+        );
+
+        let image = &module.elements[0];
+        assert_eq!(
+            resolve(&module, image).get_property("src"),
+            Some(&"ui/icons/close.png".into())
+        );
+        assert_eq!(
+            resolve(&module, &image.children[0]).get_property("font"),
+            Some(&PropertyValue::CommaList(vec![
+                "body".into(),
+                "ui/fonts/Inter.ttf".into(),
+            ]))
+        );
+        assert_eq!(
+            resolve(&module, &image.children[1]).get_property("src"),
+            Some(&"ui/close.png".into())
+        );
+        assert_eq!(
+            resolve(&module, &image.children[2]).get_property("text"),
+            Some(&"more-notes".into())
+        );
+    }
+
+    #[test]
+    fn local_variables_shadow_outer_variables() {
+        let code = r#"
+            var accent = #ff0000;
+            var fill = #0000ff;
+
+            def swatch {
+                var accent = #00ff00;
+                var fill = $accent;
+                var border = $fill;
+
+                layout div {
+                    background-color: $fill;
+                    border-color: $border;
+                    output;
+                }
+            }
+
+            layout div {
+                with swatch {}
+            }
+        "#;
+
+        // The local variables are kept in a hash map, so check that the
+        // result does not depend on its order.
+        for _ in 0 .. 16 {
+            let module = parse(code).unwrap();
+            let swatch = resolve(&module, &module.elements[0].children[0]);
+            let green = Rgba::from_hex("#00ff00").unwrap().into();
+
+            assert_eq!(swatch.get_property("background-color"), Some(&green));
+            assert_eq!(swatch.get_property("border-color-top"), Some(&green));
+        }
+    }
+
+    #[test]
+    fn widget_asset_paths_are_resolved_in_declaring_module() {
+        let library = r#"
+            def icon_button {
+                var icon = "close.png";
+
+                layout div {
+                    with img {
+                        src: $icon;
+                    }
+                    output;
+                }
+            }
+
+            def toolbar {
+                var first = "menu.png";
+
+                layout div {
+                    with icon_button {
+                        icon: $first;
+                    }
+                    with icon_button {
+                        icon: "search.png";
+                    }
+                    output;
+                }
+            }
+        "#;
+
+        let code = r#"
+            import "lib";
+
+            layout div {
+                with icon_button {}
+                with icon_button {
+                    icon: "open.png";
+                }
+                with toolbar {}
+            }
+        "#;
+
+        let parse = |code: &str, directory: &'static str, modules: Vec<(&str, Module)>| {
+            let mut parser = NekoMaidParser::tokenize(code).unwrap();
+            for native in NATIVE_WIDGETS.iter() {
+                parser.register_native_widget(native.clone());
+            }
+            for (name, module) in modules {
+                parser.add_module(name.to_string(), module);
+            }
+            parser.set_asset_path_resolver(Box::new(move |path| format!("{}{}", directory, path)));
+            parser.finish().unwrap()
+        };
+
+        let library = parse(library, "ui/widgets/", Vec::new());
+        let module = parse(code, "ui/", vec![("lib", library)]);
+
+        assert_eq!(
+            module.assets,
+            HashMap::from([
+                ("ui/widgets/close.png".to_string(), AssetKind::Image),
+                ("ui/widgets/menu.png".to_string(), AssetKind::Image),
+                ("ui/widgets/search.png".to_string(), AssetKind::Image),
+                ("ui/open.png".to_string(), AssetKind::Image),
+            ])
+        );
+
+        let root = &module.elements[0];
+        let src = |element: &NekoElementBuilder| {
+            resolve(&module, &element.children[0])
+                .get_property("src")
+                .cloned()
+        };
+        assert_eq!(src(&root.children[0]), Some("ui/widgets/close.png".into()));
+        assert_eq!(src(&root.children[1]), Some("ui/open.png".into()));
+
+        let toolbar = &root.children[2];
+        assert_eq!(
+            src(&toolbar.children[0]),
+            Some("ui/widgets/menu.png".into())
+        );
+        assert_eq!(
+            src(&toolbar.children[1]),
+            Some("ui/widgets/search.png".into())
+        );
+    }
+
+    #[test]
+    fn global_properties_are_accepted_by_every_widget() {
+        let code = r#"
+            style div +slot {
+                slot-index: 1;
+            }
+
+            layout div {
+                class slot;
+                with div {
+                    class slot;
+                    slot-index: 3;
+                }
+            }
+        "#;
+
+        assert!(matches!(
+            parse(code),
+            Err(NekoMaidParseError::InvalidProperty { .. })
+        ));
+
+        let mut parser = NekoMaidParser::tokenize(code).unwrap();
+        for native in NATIVE_WIDGETS.iter() {
+            parser.register_native_widget(native.clone());
+        }
+        parser.register_global_property("slot-index");
+        let module = parser.finish().unwrap();
+
+        let resolve = |element: &NekoElementBuilder| {
+            let mut element = element.element.clone();
+            element.resolve(&HashMap::new()).unwrap();
+            element
+        };
+
+        let outer = &module.elements[0];
+        assert_eq!(resolve(outer).get_property("slot-index"), Some(&1.into()));
+        assert_eq!(
+            resolve(&outer.children[0]).get_property("slot-index"),
+            Some(&3.into())
+        );
+    }
+
+    #[test]
+    fn doc_comments_are_attached_to_declarations() {
+        let code = r#"
+            /// A styled paragraph.
+            private style p {
+                /// Ignored, as properties are not documented.
+                color: #fff;
+            }
+
+            /// A title.
+            ///
+            /// Spans the full width.
+            def title {
+                /// The title text.
+                var text = "";
+                var size = 32;
+
+                layout p {
+                    text: $text;
+                    output;
+                }
+            }
+        "#;
+
+        let module = parse(code).unwrap();
+        assert_eq!(module.styles[0].doc(), Some("A styled paragraph."));
+
+        let Some(Widget::Custom(title)) = module.widgets.get("title") else {
+            panic!("title should be a custom widget");
+        };
+        assert_eq!(
+            title.doc.as_deref(),
+            Some("A title.\n\nSpans the full width.")
+        );
+        assert_eq!(title.property_docs.len(), 1);
+        assert_eq!(title.property_docs["text"], "The title text.");
+    }
+}
@@ -0,0 +1,653 @@
+//! Defines the native widgets available in NekoMaid UI, along with the
+//! default values of their properties.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use lazy_static::lazy_static;
+
+use crate::color::Rgba;
+use crate::property::PropertyType;
+use crate::value::PropertyValue;
+use crate::widget::NativeWidget;
+
+lazy_static! {
+    /// The list of native widgets available in NekoMaid UI.
+    pub static ref NATIVE_WIDGETS: Vec<NativeWidget> = vec![
+        native_widget(
+            "div",
+            {
+                let mut m = HashMap::new();
+                node_properties(&mut m);
+                background_color_properties(&mut m);
+                gradient_properties(&mut m);
+                border_color_properties(&mut m);
+                border_radius_properties(&mut m);
+                box_shadow_properties(&mut m);
+                outline_properties(&mut m);
+                z_index_properties(&mut m);
+                ui_transform_properties(&mut m);
+                opacity_properties(&mut m);
+                m
+            },
+        ),
+        native_widget(
+            "img",
+            {
+                let mut m = HashMap::new();
+                node_properties(&mut m);
+                background_color_properties(&mut m);
+                gradient_properties(&mut m);
+                border_color_properties(&mut m);
+                border_radius_properties(&mut m);
+                box_shadow_properties(&mut m);
+                outline_properties(&mut m);
+                z_index_properties(&mut m);
+                ui_transform_properties(&mut m);
+                opacity_properties(&mut m);
+                image_properties(&mut m);
+                m
+            },
+        ),
+        native_widget(
+            "p",
+            {
+                let mut m = HashMap::new();
+                node_properties(&mut m);
+                background_color_properties(&mut m);
+                gradient_properties(&mut m);
+                border_color_properties(&mut m);
+                border_radius_properties(&mut m);
+                box_shadow_properties(&mut m);
+                outline_properties(&mut m);
+                z_index_properties(&mut m);
+                ui_transform_properties(&mut m);
+                opacity_properties(&mut m);
+                text_properties(&mut m);
+                m
+            },
+        ),
+        native_widget(
+            "span",
+            {
+                let mut m = HashMap::new();
+                node_properties(&mut m);
+                background_color_properties(&mut m);
+                gradient_properties(&mut m);
+                border_color_properties(&mut m);
+                border_radius_properties(&mut m);
+                box_shadow_properties(&mut m);
+                outline_properties(&mut m);
+                z_index_properties(&mut m);
+                ui_transform_properties(&mut m);
+                opacity_properties(&mut m);
+                text_span_properties(&mut m);
+                m
+            },
+        )
+    ];
+}
+
+/// Creates a native widget with the given default properties, which accept
+/// the keywords listed by [`accepted_values`].
+fn native_widget(name: &str, default_properties: HashMap<String, PropertyValue>) -> NativeWidget {
+    let keywords = default_properties
+        .keys()
+        .filter_map(|property| {
+            let (_, keywords) = accepted_values(property);
+            (!keywords.is_empty()).then(|| {
+                (
+                    property.clone(),
+                    keywords.iter().map(|k| k.to_string()).collect(),
+                )
+            })
+        })
+        .collect();
+
+    NativeWidget {
+        name: name.to_string(),
+        default_properties: Arc::new(default_properties),
+        keywords: Arc::new(keywords),
+    }
+}
+
+/// Value types accepted by properties that take a single keyword.
+const KEYWORD: &[PropertyType] = &[PropertyType::String];
+
+/// Value types accepted by length properties, such as `width`.
+const LENGTH: &[PropertyType] = &[
+    PropertyType::Number,
+    PropertyType::Pixels,
+    PropertyType::Percentage,
+    PropertyType::String,
+];
+
+/// Value types accepted by box shorthands of lengths, such as `margin`.
+const LENGTH_LIST: &[PropertyType] = &[
+    PropertyType::Number,
+    PropertyType::Pixels,
+    PropertyType::Percentage,
+    PropertyType::String,
+    PropertyType::List,
+];
+
+/// Value types accepted by the `border` shorthand.
+const BORDER: &[PropertyType] = &[
+    PropertyType::Number,
+    PropertyType::Pixels,
+    PropertyType::Percentage,
+    PropertyType::String,
+    PropertyType::Color,
+    PropertyType::List,
+];
+
+/// Value types accepted by grid track lists, such as `grid-template-rows`.
+const GRID_TRACKS: &[PropertyType] = &[
+    PropertyType::Number,
+    PropertyType::Pixels,
+    PropertyType::Percentage,
+    PropertyType::Fraction,
+    PropertyType::String,
+    PropertyType::Function,
+    PropertyType::List,
+];
+
+/// Value types accepted by grid placements, such as `grid-row`.
+const GRID_PLACEMENT: &[PropertyType] = &[
+    PropertyType::Number,
+    PropertyType::String,
+    PropertyType::List,
+];
+
+/// Value types accepted by `box-shadow`, which takes one or more shadows.
+const SHADOW: &[PropertyType] = &[
+    PropertyType::String,
+    PropertyType::List,
+    PropertyType::CommaList,
+];
+
+/// Value types accepted by gradient properties, such as `background`, which
+/// take one or more gradient functions.
+const GRADIENT: &[PropertyType] = &[
+    PropertyType::String,
+    PropertyType::Function,
+    PropertyType::CommaList,
+];
+
+/// Value types accepted by plain numeric properties.
+const NUMBER: &[PropertyType] = &[PropertyType::Number];
+
+/// Value types accepted by color properties.
+const COLOR: &[PropertyType] = &[PropertyType::Color];
+
+/// Value types accepted by boolean properties.
+const BOOLEAN: &[PropertyType] = &[PropertyType::Boolean];
+
+/// Value types accepted by free-form string properties, such as `text`.
+const STRING: &[PropertyType] = &[PropertyType::String];
+
+/// Returns the value types accepted by the native widget property with the
+/// given name, along with the keywords it accepts as string values, either on
+/// their own or within its lists and functions.
+///
+/// If the keyword list is empty, any string is accepted by properties that
+/// accept strings. Unknown properties accept nothing.
+pub fn accepted_values(property: &str) -> (&'static [PropertyType], &'static [&'static str]) {
+    match property {
+        "display" => (KEYWORD, &["flex", "grid", "block", "none"]),
+        "box-sizing" => (KEYWORD, &["border-box", "content-box"]),
+        "position-type" => (KEYWORD, &["relative", "absolute"]),
+        "overflow-x" | "overflow-y" => (KEYWORD, &["visible", "clip", "hidden", "scroll"]),
+        "overflow-clip-margin-box" => (KEYWORD, &["content-box", "padding-box", "border-box"]),
+        "align-items" => (
+            KEYWORD,
+            &[
+                "default",
+                "start",
+                "end",
+                "flex-start",
+                "flex-end",
+                "center",
+                "baseline",
+                "stretch",
+            ],
+        ),
+        "justify-items" => (
+            KEYWORD,
+            &["default", "start", "end", "center", "baseline", "stretch"],
+        ),
+        "align-self" => (
+            KEYWORD,
+            &[
+                "auto",
+                "start",
+                "end",
+                "flex-start",
+                "flex-end",
+                "center",
+                "baseline",
+                "stretch",
+            ],
+        ),
+        "justify-self" => (
+            KEYWORD,
+            &["auto", "start", "end", "center", "baseline", "stretch"],
+        ),
+        "align-content" | "justify-content" => (
+            KEYWORD,
+            &[
+                "default",
+                "start",
+                "end",
+                "flex-start",
+                "flex-end",
+                "center",
+                "stretch",
+                "space-between",
+                "space-around",
+                "space-evenly",
+            ],
+        ),
+        "flex-direction" => (KEYWORD, &["row", "column", "row-reverse", "column-reverse"]),
+        "flex-wrap" => (KEYWORD, &["nowrap", "wrap", "wrap-reverse"]),
+        "grid-auto-flow" => (KEYWORD, &["row", "column", "row-dense", "column-dense"]),
+        "grid-template-rows" | "grid-template-columns" => (
+            GRID_TRACKS,
+            &[
+                "none",
+                "auto",
+                "min-content",
+                "max-content",
+                "auto-fill",
+                "auto-fit",
+            ],
+        ),
+        "grid-auto-rows" | "grid-auto-columns" => {
+            (GRID_TRACKS, &["none", "auto", "min-content", "max-content"])
+        }
+        "grid-row" | "grid-column" => (GRID_PLACEMENT, &["auto", "span"]),
+        "mode" => (KEYWORD, &["auto", "stretch", "sliced", "tiled"]),
+        "center-scale-mode" | "sides-scale-mode" => (KEYWORD, &["stretch", "tile"]),
+        "font-smoothing" => (KEYWORD, &["none", "antialiased"]),
+        "justify" => (KEYWORD, &["left", "right", "center", "justified"]),
+        "line-break" => (KEYWORD, &["word", "char", "word-or-char", "nowrap"]),
+        "text-overflow" => (KEYWORD, &["clip", "ellipsis"]),
+        "text-transform" => (KEYWORD, &["none", "uppercase", "lowercase", "capitalize"]),
+
+        "left" | "top" | "right" | "bottom" | "width" | "height" | "min-width" | "min-height"
+        | "max-width" | "max-height" | "flex-basis" | "row-gap" | "column-gap" => {
+            (LENGTH, &["auto"])
+        }
+        "margin" | "padding" | "border-thickness" | "border-radius" => (LENGTH_LIST, &["auto"]),
+        "border" | "outline" => (BORDER, &["auto"]),
+        "outline-width" | "outline-offset" => (LENGTH, &["auto"]),
+        "box-shadow" => (SHADOW, &["none"]),
+        "text-shadow" => (&[PropertyType::String, PropertyType::List], &["none"]),
+        "background" | "border-gradient" => (
+            GRADIENT,
+            &[
+                "none",
+                "to",
+                "from",
+                "at",
+                "left",
+                "right",
+                "top",
+                "bottom",
+                "center",
+                "circle",
+                "ellipse",
+                "closest-side",
+                "farthest-side",
+                "closest-corner",
+                "farthest-corner",
+            ],
+        ),
+        p if p.starts_with("margin")
+            || p.starts_with("padding")
+            || p.starts_with("border-thickness")
+            || p.starts_with("border-radius") =>
+        {
+            (LENGTH, &["auto"])
+        }
+
+        "aspect-ratio" => (&[PropertyType::Number, PropertyType::String], &["none"]),
+        "line-height" => (
+            &[
+                PropertyType::Number,
+                PropertyType::Pixels,
+                PropertyType::Percentage,
+            ],
+            &[],
+        ),
+        "scrollbar-width"
+        | "overflow-clip-margin"
+        | "flex-grow"
+        | "flex-shrink"
+        | "max-corner-scale"
+        | "center-scale-stretch"
+        | "sides-scale-stretch"
+        | "stretch-value"
+        | "font-size"
+        | "z-index"
+        | "opacity"
+        | "atlas-index"
+        | "atlas-frames"
+        | "frame-rate" => (NUMBER, &[]),
+        "atlas-layout" => (&[PropertyType::String, PropertyType::List], &["none"]),
+        "rotate" => (&[PropertyType::Angle, PropertyType::Number], &[]),
+        "scale" => (
+            &[
+                PropertyType::Number,
+                PropertyType::Percentage,
+                PropertyType::List,
+            ],
+            &[],
+        ),
+        "translate" => (
+            &[
+                PropertyType::Number,
+                PropertyType::Pixels,
+                PropertyType::Percentage,
+                PropertyType::List,
+            ],
+            &[],
+        ),
+        "global-z-index" => (&[PropertyType::Number, PropertyType::String], &["none"]),
+        p if p.starts_with("slice-size") => (NUMBER, &[]),
+
+        "background-color" | "tint" | "color" | "outline-color" => (COLOR, &[]),
+        "border-color" => (&[PropertyType::Color, PropertyType::List], &[]),
+        p if p.starts_with("border-color") => (COLOR, &[]),
+
+        "flip-x" | "flip-y" | "tile-x" | "tile-y" => (BOOLEAN, &[]),
+        "font" | "font-bold" | "font-italic" => {
+            (&[PropertyType::String, PropertyType::CommaList], &[])
+        }
+        "src" | "text" => (STRING, &[]),
+
+        _ => (&[], &[]),
+    }
+}
+
+/// The kind of asset referenced by an asset path in a module.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum AssetKind {
+    /// An image, such as the `src` of an `img`.
+    Image,
+
+    /// A font file.
+    Font,
+}
+
+/// Returns the kind of asset loaded by the given property of a built-in
+/// native widget, if it loads one, such as the `src` of an `img`.
+pub fn asset_property(widget: &str, property: &str) -> Option<AssetKind> {
+    match (widget, property) {
+        ("img", "src") => Some(AssetKind::Image),
+        ("p", "font" | "font-bold" | "font-italic") | ("span", "font") => Some(AssetKind::Font),
+        _ => None,
+    }
+}
+
+/// Checks whether the given string value of an asset property is an asset
+/// path. Asset paths are resolved relative to the module that declares them.
+///
+/// Fonts may also be given by name, so only values that look like file paths
+/// are considered asset paths.
+pub fn is_asset_path(kind: AssetKind, value: &str) -> bool {
+    match kind {
+        AssetKind::Image => !value.is_empty(),
+        AssetKind::Font => value.contains('.') || value.contains('/'),
+    }
+}
+
+/// Inserts the default properties for a `Node` into the given map.
+fn node_properties(m: &mut HashMap<String, PropertyValue>) {
+    m.insert("display".into(), "flex".into());
+    m.insert("box-sizing".into(), "border-box".into());
+    m.insert("position-type".into(), "relative".into());
+
+    m.insert("overflow-x".into(), "visible".into());
+    m.insert("overflow-y".into(), "visible".into());
+    m.insert("scrollbar-width".into(), 0.into());
+
+    m.insert("overflow-clip-margin-box".into(), "padding-box".into());
+    m.insert("overflow-clip-margin".into(), 0.into());
+
+    m.insert("left".into(), "auto".into());
+    m.insert("top".into(), "auto".into());
+    m.insert("right".into(), "auto".into());
+    m.insert("bottom".into(), "auto".into());
+
+    m.insert("width".into(), "auto".into());
+    m.insert("height".into(), "auto".into());
+    m.insert("min-width".into(), "auto".into());
+    m.insert("min-height".into(), "auto".into());
+    m.insert("max-width".into(), "auto".into());
+    m.insert("max-height".into(), "auto".into());
+    m.insert("aspect-ratio".into(), "none".into());
+
+    m.insert("align-items".into(), "default".into());
+    m.insert("justify-items".into(), "default".into());
+    m.insert("align-self".into(), "auto".into());
+    m.insert("justify-self".into(), "auto".into());
+    m.insert("align-content".into(), "default".into());
+    m.insert("justify-content".into(), "default".into());
+
+    m.insert("margin".into(), 0.into());
+    m.insert("margin-top".into(), 0.into());
+    m.insert("margin-left".into(), 0.into());
+    m.insert("margin-right".into(), 0.into());
+    m.insert("margin-bottom".into(), 0.into());
+
+    m.insert("padding".into(), 0.into());
+    m.insert("padding-top".into(), 0.into());
+    m.insert("padding-left".into(), 0.into());
+    m.insert("padding-right".into(), 0.into());
+    m.insert("padding-bottom".into(), 0.into());
+
+    m.insert("border-thickness".into(), 0.into());
+    m.insert("border-thickness-top".into(), 0.into());
+    m.insert("border-thickness-left".into(), 0.into());
+    m.insert("border-thickness-right".into(), 0.into());
+    m.insert("border-thickness-bottom".into(), 0.into());
+
+    m.insert(
+        "border".into(),
+        PropertyValue::List(vec![0.into(), Rgba::NONE.into()]),
+    );
+
+    m.insert("flex-direction".into(), "row".into());
+    m.insert("flex-wrap".into(), "nowrap".into());
+    m.insert("flex-grow".into(), 0.into());
+    m.insert("flex-shrink".into(), 1.into());
+    m.insert("flex-basis".into(), "auto".into());
+
+    m.insert("row-gap".into(), 0.into());
+    m.insert("column-gap".into(), 0.into());
+
+    m.insert("grid-auto-flow".into(), "row".into());
+    m.insert("grid-template-rows".into(), "none".into());
+    m.insert("grid-template-columns".into(), "none".into());
+    m.insert("grid-auto-rows".into(), "auto".into());
+    m.insert("grid-auto-columns".into(), "auto".into());
+    m.insert("grid-row".into(), "auto".into());
+    m.insert("grid-column".into(), "auto".into());
+}
+
+/// Inserts the default properties for a `BackgroundColor` into the given map.
+fn background_color_properties(m: &mut HashMap<String, PropertyValue>) {
+    m.insert("background-color".into(), Rgba::NONE.into());
+}
+
+/// Inserts the default properties for a `BackgroundGradient` and a
+/// `BorderGradient` into the given map.
+fn gradient_properties(m: &mut HashMap<String, PropertyValue>) {
+    m.insert("background".into(), "none".into());
+    m.insert("border-gradient".into(), "none".into());
+}
+
+/// Inserts the default properties for a `BorderColor` into the given map.
+fn border_color_properties(m: &mut HashMap<String, PropertyValue>) {
+    m.insert("border-color".into(), Rgba::NONE.into());
+    m.insert("border-color-top".into(), Rgba::NONE.into());
+    m.insert("border-color-left".into(), Rgba::NONE.into());
+    m.insert("border-color-right".into(), Rgba::NONE.into());
+    m.insert("border-color-bottom".into(), Rgba::NONE.into());
+}
+
+/// Inserts the default properties for a `BorderRadius` into the given map.
+fn border_radius_properties(m: &mut HashMap<String, PropertyValue>) {
+    m.insert("border-radius".into(), 0.into());
+    m.insert("border-radius-top-left".into(), 0.into());
+    m.insert("border-radius-top-right".into(), 0.into());
+    m.insert("border-radius-bottom-left".into(), 0.into());
+    m.insert("border-radius-bottom-right".into(), 0.into());
+}
+
+/// Inserts the default properties for a `BoxShadow` into the given map.
+fn box_shadow_properties(m: &mut HashMap<String, PropertyValue>) {
+    m.insert("box-shadow".into(), "none".into());
+}
+
+/// Inserts the default properties for an `Outline` into the given map.
+fn outline_properties(m: &mut HashMap<String, PropertyValue>) {
+    m.insert(
+        "outline".into(),
+        PropertyValue::List(vec![0.into(), Rgba::NONE.into()]),
+    );
+    m.insert("outline-width".into(), 0.into());
+    m.insert("outline-offset".into(), 0.into());
+    m.insert("outline-color".into(), Rgba::NONE.into());
+}
+
+/// Inserts the default properties for `ZIndex` and `GlobalZIndex` into the
+/// given map.
+fn z_index_properties(m: &mut HashMap<String, PropertyValue>) {
+    m.insert("z-index".into(), 0.into());
+    m.insert("global-z-index".into(), "none".into());
+}
+
+/// Inserts the default properties for a `UiTransform` into the given map.
+fn ui_transform_properties(m: &mut HashMap<String, PropertyValue>) {
+    m.insert("rotate".into(), PropertyValue::Angle(0.0));
+    m.insert("scale".into(), 1.into());
+    m.insert("translate".into(), 0.into());
+}
+
+/// Inserts the default opacity, which is applied to the colors of an element
+/// and its descendants, into the given map.
+fn opacity_properties(m: &mut HashMap<String, PropertyValue>) {
+    m.insert("opacity".into(), 1.into());
+}
+
+/// Inserts the default properties for an `ImageNode` into the given map.
+fn image_properties(m: &mut HashMap<String, PropertyValue>) {
+    m.insert("src".into(), "".into());
+    m.insert("tint".into(), Rgba::WHITE.into());
+    m.insert("flip-x".into(), false.into());
+    m.insert("flip-y".into(), false.into());
+    m.insert("mode".into(), "auto".into());
+
+    // slice mode properties
+    m.insert("slice-size".into(), 0.into());
+    m.insert("slice-size-top".into(), 0.into());
+    m.insert("slice-size-right".into(), 0.into());
+    m.insert("slice-size-bottom".into(), 0.into());
+    m.insert("slice-size-left".into(), 0.into());
+
+    m.insert("max-corner-scale".into(), 1.into());
+    m.insert("center-scale-mode".into(), "stretch".into());
+    m.insert("center-scale-stretch".into(), 1.into());
+    m.insert("sides-scale-mode".into(), "stretch".into());
+    m.insert("sides-scale-stretch".into(), 1.into());
+
+    // tile mode properties
+    m.insert("tile-x".into(), true.into());
+    m.insert("tile-y".into(), true.into());
+    m.insert("stretch-value".into(), 1.into());
+
+    // texture atlas properties
+    m.insert("atlas-layout".into(), "none".into());
+    m.insert("atlas-index".into(), 0.into());
+    m.insert("atlas-frames".into(), 0.into());
+    m.insert("frame-rate".into(), 0.into());
+}
+
+/// Inserts the default properties for a `Text` bundle into the given map.
+fn text_properties(m: &mut HashMap<String, PropertyValue>) {
+    // Text
+    m.insert("text".into(), "".into());
+    m.insert("text-transform".into(), "none".into());
+    m.insert("text-overflow".into(), "clip".into());
+
+    // TextFont
+    m.insert("font".into(), "auto".into());
+    m.insert("font-size".into(), 16.into());
+    m.insert("line-height".into(), PropertyValue::Percent(120.0));
+    m.insert("font-smoothing".into(), "antialiased".into());
+
+    // Fonts used by `[b]` and `[i]` markup, if not the regular font.
+    m.insert("font-bold".into(), "none".into());
+    m.insert("font-italic".into(), "none".into());
+
+    // TextLayout
+    m.insert("justify".into(), "left".into());
+    m.insert("line-break".into(), "word".into());
+
+    // TextColor
+    m.insert("color".into(), Rgba::WHITE.into());
+
+    // TextShadow
+    m.insert("text-shadow".into(), "none".into());
+}
+
+/// Inserts the default properties for a `TextSpan` bundle into the given map.
+fn text_span_properties(m: &mut HashMap<String, PropertyValue>) {
+    // TextSpan
+    m.insert("text".into(), "".into());
+    m.insert("text-transform".into(), "none".into());
+
+    // TextFont
+    m.insert("font".into(), "auto".into());
+    m.insert("font-size".into(), 16.into());
+    m.insert("line-height".into(), PropertyValue::Percent(120.0));
+    m.insert("font-smoothing".into(), "antialiased".into());
+
+    // TextColor
+    m.insert("color".into(), Rgba::WHITE.into());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_are_accepted_values() {
+        for widget in NATIVE_WIDGETS.iter() {
+            for (name, value) in widget.default_properties.iter() {
+                let (types, keywords) = accepted_values(name);
+                assert!(
+                    types.contains(&value.value_type()),
+                    "{}: default {} is not accepted",
+                    name,
+                    value
+                );
+
+                if let PropertyValue::String(s) = value
+                    && !keywords.is_empty()
+                {
+                    assert!(
+                        keywords.contains(&s.as_str()),
+                        "{}: unknown keyword {}",
+                        name,
+                        s
+                    );
+                }
+            }
+        }
+    }
+}
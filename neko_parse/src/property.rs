@@ -0,0 +1,408 @@
+//! A module for parsing and handling properties in NekoMaid UI files.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::NekoMaidParseError;
+use crate::context::{NekoResult, ParseContext};
+use crate::token::TokenType;
+use crate::value::PropertyValue;
+
+/// A property within a style or element.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Property {
+    /// The name of the property.
+    pub name: String,
+
+    /// The value of the property.
+    pub value: PropertyValue,
+}
+
+/// A property within a style or element.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnresolvedProperty {
+    /// The name of the property.
+    pub name: String,
+
+    /// The value of the property.
+    pub value: UnresolvedPropertyValue,
+}
+
+/// An unresolved property value that may be a constant or a variable reference.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum UnresolvedPropertyValue {
+    /// A constant property value.
+    Constant(PropertyValue),
+
+    /// A variable reference.
+    Variable(String),
+
+    /// A sequence of values, any of which may be variable references.
+    List(Vec<UnresolvedPropertyValue>),
+
+    /// A comma-separated sequence of values, any of which may be variable
+    /// references.
+    CommaList(Vec<UnresolvedPropertyValue>),
+
+    /// A function call, whose arguments may contain variable references.
+    Function {
+        /// The name of the function.
+        name: String,
+
+        /// The arguments of the function.
+        args: Vec<UnresolvedPropertyValue>,
+    },
+}
+
+impl UnresolvedPropertyValue {
+    /// Resolves the property value using the provided variable map.
+    pub fn resolve(&self, variables: &HashMap<String, PropertyValue>) -> NekoResult<PropertyValue> {
+        match self {
+            UnresolvedPropertyValue::Constant(v) => Ok(v.clone()),
+            UnresolvedPropertyValue::Variable(var_name) => {
+                if let Some(v) = variables.get(var_name) {
+                    Ok(v.clone())
+                } else {
+                    Err(NekoMaidParseError::VariableNotFound {
+                        variable: var_name.clone(),
+                        position: Default::default(),
+                    })
+                }
+            }
+            UnresolvedPropertyValue::List(values) => {
+                let mut resolved = Vec::with_capacity(values.len());
+                for value in values {
+                    match value.resolve(variables)? {
+                        PropertyValue::List(inner) => resolved.extend(inner),
+                        value => resolved.push(value),
+                    }
+                }
+                Ok(PropertyValue::List(resolved))
+            }
+            UnresolvedPropertyValue::CommaList(values) => {
+                let mut resolved = Vec::with_capacity(values.len());
+                for value in values {
+                    match value.resolve(variables)? {
+                        PropertyValue::CommaList(inner) => resolved.extend(inner),
+                        value => resolved.push(value),
+                    }
+                }
+                Ok(PropertyValue::CommaList(resolved))
+            }
+            UnresolvedPropertyValue::Function { name, args } => {
+                let args = args
+                    .iter()
+                    .map(|arg| arg.resolve(variables))
+                    .collect::<NekoResult<_>>()?;
+                Ok(PropertyValue::Function {
+                    name: name.clone(),
+                    args,
+                })
+            }
+        }
+    }
+
+    /// Returns the names of all variables referenced by this value.
+    pub fn variables(&self) -> Vec<&str> {
+        match self {
+            UnresolvedPropertyValue::Variable(var_name) => vec![var_name.as_str()],
+            UnresolvedPropertyValue::List(values)
+            | UnresolvedPropertyValue::CommaList(values)
+            | UnresolvedPropertyValue::Function { args: values, .. } => {
+                values.iter().flat_map(|value| value.variables()).collect()
+            }
+            UnresolvedPropertyValue::Constant(_) => Vec::new(),
+        }
+    }
+
+    /// Renames the referenced variable, if it is present in the given map of
+    /// old names to new names.
+    pub fn rename_variable(&mut self, renames: &HashMap<String, String>) {
+        match self {
+            UnresolvedPropertyValue::Variable(var_name) => {
+                if let Some(new_name) = renames.get(var_name) {
+                    *var_name = new_name.clone();
+                }
+            }
+            UnresolvedPropertyValue::List(values)
+            | UnresolvedPropertyValue::CommaList(values)
+            | UnresolvedPropertyValue::Function { args: values, .. } => {
+                for value in values {
+                    value.rename_variable(renames);
+                }
+            }
+            UnresolvedPropertyValue::Constant(_) => {}
+        }
+    }
+}
+
+impl fmt::Display for UnresolvedPropertyValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UnresolvedPropertyValue::Constant(value) => write!(f, "{}", value),
+            UnresolvedPropertyValue::Variable(name) => write!(f, "${}", name),
+            UnresolvedPropertyValue::List(values) => {
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " ")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                Ok(())
+            }
+            UnresolvedPropertyValue::CommaList(values) => {
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                Ok(())
+            }
+            UnresolvedPropertyValue::Function { name, args } => {
+                write!(f, "{}(", name)?;
+                for (i, arg) in args.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", arg)?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+/// Renames all variables referenced by the given property values, using the
+/// given map of old names to new names.
+pub fn rename_variables(
+    properties: &mut HashMap<String, UnresolvedPropertyValue>,
+    renames: &HashMap<String, String>,
+) {
+    for value in properties.values_mut() {
+        value.rename_variable(renames);
+    }
+}
+
+/// The type of a widget property.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum PropertyType {
+    /// A string type.
+    String,
+
+    /// A numeric type.
+    Number,
+
+    /// A boolean type.
+    Boolean,
+
+    /// A color type.
+    Color,
+
+    /// A percentage type.
+    Percentage,
+
+    /// A pixel type.
+    Pixels,
+
+    /// A grid fraction type.
+    Fraction,
+
+    /// An angle type.
+    Angle,
+
+    /// A sequence of values.
+    List,
+
+    /// A comma-separated sequence of values.
+    #[cfg_attr(feature = "serde", serde(rename = "comma-list"))]
+    CommaList,
+
+    /// A function call.
+    Function,
+}
+
+impl fmt::Display for PropertyType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let type_name = match self {
+            PropertyType::String => "string",
+            PropertyType::Number => "number",
+            PropertyType::Boolean => "boolean",
+            PropertyType::Color => "color",
+            PropertyType::Percentage => "percentage",
+            PropertyType::Pixels => "pixels",
+            PropertyType::Fraction => "fraction",
+            PropertyType::Angle => "angle",
+            PropertyType::List => "list",
+            PropertyType::CommaList => "comma-list",
+            PropertyType::Function => "function",
+        };
+        write!(f, "{}", type_name)
+    }
+}
+
+/// Parses an unresolved property from the input and returns a
+/// [`UnresolvedProperty`].
+pub fn parse_unresolved_property(ctx: &mut ParseContext) -> NekoResult<UnresolvedProperty> {
+    let name = ctx.expect_as_string(TokenType::Identifier)?;
+    ctx.expect(TokenType::Colon)?;
+    let value = parse_unresolved_value(ctx)?;
+    ctx.expect(TokenType::Semicolon)?;
+
+    Ok(UnresolvedProperty { name, value })
+}
+
+/// Parses a variable declaration from the input and returns a [`Property`].
+pub fn parse_variable(ctx: &mut ParseContext) -> NekoResult<UnresolvedProperty> {
+    ctx.expect(TokenType::VarKeyword)?;
+    let name = ctx.expect_as_string(TokenType::Identifier)?;
+    ctx.expect(TokenType::Equals)?;
+    let value = parse_unresolved_value(ctx)?;
+    ctx.expect(TokenType::Semicolon)?;
+
+    Ok(UnresolvedProperty { name, value })
+}
+
+/// Parses an unresolved property value from the input and returns a
+/// [`UnresolvedPropertyValue`].
+///
+/// A sequence of several values separated by whitespace, such as `4px 8px`,
+/// is returned as a list. Several such sequences separated by commas are
+/// returned as a comma list.
+pub fn parse_unresolved_value(ctx: &mut ParseContext) -> NekoResult<UnresolvedPropertyValue> {
+    let first = parse_value_sequence(ctx)?;
+
+    let mut values = Vec::new();
+    while ctx.maybe_consume(TokenType::Comma).is_some() {
+        values.push(parse_value_sequence(ctx)?);
+    }
+
+    if values.is_empty() {
+        return Ok(first);
+    }
+
+    values.insert(0, first);
+    Ok(UnresolvedPropertyValue::CommaList(values))
+}
+
+/// Parses a sequence of values separated by whitespace. A single value is
+/// returned as is, and several values are returned as a list.
+fn parse_value_sequence(ctx: &mut ParseContext) -> NekoResult<UnresolvedPropertyValue> {
+    let first = parse_single_value(ctx)?;
+
+    let mut values = Vec::new();
+    while let Some(next) = ctx.peek()
+        && is_value_token(next.token_type)
+    {
+        values.push(parse_single_value(ctx)?);
+    }
+
+    if values.is_empty() {
+        return Ok(first);
+    }
+
+    values.insert(0, first);
+    Ok(UnresolvedPropertyValue::List(values))
+}
+
+/// Checks whether the given token type starts a property value.
+fn is_value_token(token_type: TokenType) -> bool {
+    matches!(
+        token_type,
+        TokenType::Identifier
+            | TokenType::StringLiteral
+            | TokenType::ColorLiteral
+            | TokenType::BooleanLiteral
+            | TokenType::NumberLiteral
+            | TokenType::PercentLiteral
+            | TokenType::PixelsLiteral
+            | TokenType::FractionLiteral
+            | TokenType::AngleLiteral
+            | TokenType::Variable
+    )
+}
+
+/// Parses a single property value, which is a literal, a variable reference,
+/// or a function call.
+fn parse_single_value(ctx: &mut ParseContext) -> NekoResult<UnresolvedPropertyValue> {
+    if let Some(next) = ctx.peek()
+        && next.token_type == TokenType::Identifier
+        && let Some(paren) = ctx.peek_nth(1)
+        && paren.token_type == TokenType::OpenParen
+    {
+        return parse_function(ctx);
+    }
+
+    let next_pos = ctx.next_position().unwrap_or_default();
+    let next = ctx.consume()?;
+
+    match next.token_type {
+        TokenType::Identifier | TokenType::StringLiteral => Ok(UnresolvedPropertyValue::Constant(
+            next.into_string_property(next_pos)?,
+        )),
+        TokenType::ColorLiteral => Ok(UnresolvedPropertyValue::Constant(
+            next.into_color_property(next_pos)?,
+        )),
+        TokenType::BooleanLiteral => Ok(UnresolvedPropertyValue::Constant(
+            next.into_boolean_property(next_pos)?,
+        )),
+        TokenType::NumberLiteral => Ok(UnresolvedPropertyValue::Constant(
+            next.into_number_property(next_pos)?,
+        )),
+        TokenType::PercentLiteral => Ok(UnresolvedPropertyValue::Constant(
+            next.into_percent_property(next_pos)?,
+        )),
+        TokenType::PixelsLiteral => Ok(UnresolvedPropertyValue::Constant(
+            next.into_pixels_property(next_pos)?,
+        )),
+        TokenType::FractionLiteral => Ok(UnresolvedPropertyValue::Constant(
+            next.into_fraction_property(next_pos)?,
+        )),
+        TokenType::AngleLiteral => Ok(UnresolvedPropertyValue::Constant(
+            next.into_angle_property(next_pos)?,
+        )),
+        TokenType::Variable => {
+            let var_name = next.into_variable_name(next_pos)?;
+            Ok(UnresolvedPropertyValue::Variable(var_name))
+        }
+        _ => Err(NekoMaidParseError::UnexpectedToken {
+            expected: vec![
+                TokenType::StringLiteral.type_name().to_string(),
+                TokenType::Identifier.type_name().to_string(),
+                TokenType::ColorLiteral.type_name().to_string(),
+                TokenType::BooleanLiteral.type_name().to_string(),
+                TokenType::NumberLiteral.type_name().to_string(),
+                TokenType::PercentLiteral.type_name().to_string(),
+                TokenType::PixelsLiteral.type_name().to_string(),
+                TokenType::FractionLiteral.type_name().to_string(),
+                TokenType::AngleLiteral.type_name().to_string(),
+                TokenType::Variable.type_name().to_string(),
+            ],
+            found: format!("{}", next.token_type),
+            position: next.position,
+        }),
+    }
+}
+
+/// Parses a function call, such as `repeat(3, 100px 1fr)`. Each argument may
+/// be a sequence of values separated by whitespace.
+fn parse_function(ctx: &mut ParseContext) -> NekoResult<UnresolvedPropertyValue> {
+    let name = ctx.expect_as_string(TokenType::Identifier)?;
+    ctx.expect(TokenType::OpenParen)?;
+
+    let mut args = Vec::new();
+    if ctx.maybe_consume(TokenType::CloseParen).is_none() {
+        loop {
+            args.push(parse_value_sequence(ctx)?);
+            if ctx.maybe_consume(TokenType::Comma).is_none() {
+                ctx.expect(TokenType::CloseParen)?;
+                break;
+            }
+        }
+    }
+
+    Ok(UnresolvedPropertyValue::Function { name, args })
+}
@@ -0,0 +1,376 @@
+//! A machine-readable description of the widgets available in NekoMaid UI.
+//!
+//! The schema lists every widget along with its properties, their accepted
+//! value types and keywords, and their default values. It is intended for
+//! editor tooling and documentation generators, and can be exported as JSON or
+//! RON with the `schema` feature.
+
+use crate::module::Module;
+use crate::native::{NATIVE_WIDGETS, accepted_values};
+use crate::property::{PropertyType, UnresolvedPropertyValue};
+use crate::value::PropertyValue;
+use crate::widget::Widget;
+
+/// A description of a set of widgets.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Schema {
+    /// The described widgets, native widgets first, each sorted by name.
+    pub widgets: Vec<WidgetSchema>,
+}
+
+impl Schema {
+    /// Creates a schema describing all native widgets.
+    pub fn native() -> Self {
+        let mut schema = Self::default();
+        for native in NATIVE_WIDGETS.iter() {
+            schema.add_widget(&Widget::Native(native.clone()), true);
+        }
+        schema
+    }
+
+    /// Adds all widgets available in the given module to this schema,
+    /// including native widgets and widgets imported from other modules.
+    pub fn add_module(&mut self, module: &Module) {
+        for (name, widget) in &module.widgets {
+            let public = !module.private_widgets.contains(name);
+            self.add_widget(widget, public);
+        }
+    }
+
+    /// Adds a widget to this schema, replacing any widget with the same name.
+    pub fn add_widget(&mut self, widget: &Widget, public: bool) {
+        let schema = WidgetSchema::new(widget, public);
+        self.widgets.retain(|w| w.name != schema.name);
+
+        let index = self
+            .widgets
+            .partition_point(|w| (w.kind, &w.name) < (schema.kind, &schema.name));
+        self.widgets.insert(index, schema);
+    }
+
+    /// Returns the widget with the given name, if it is described.
+    pub fn widget(&self, name: &str) -> Option<&WidgetSchema> {
+        self.widgets.iter().find(|w| w.name == name)
+    }
+
+    /// Serializes this schema as pretty-printed JSON.
+    #[cfg(feature = "schema")]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("Schema is always serializable")
+    }
+
+    /// Serializes this schema as pretty-printed RON.
+    #[cfg(feature = "schema")]
+    pub fn to_ron(&self) -> String {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .expect("Schema is always serializable")
+    }
+}
+
+/// The kind of a described widget.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum WidgetKind {
+    /// A widget provided by NekoMaid itself.
+    Native,
+
+    /// A widget defined with `def` in a NekoMaid UI file.
+    Custom,
+}
+
+/// A description of a single widget.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct WidgetSchema {
+    /// The name of the widget.
+    pub name: String,
+
+    /// Whether the widget is native or custom.
+    pub kind: WidgetKind,
+
+    /// Whether the widget is visible to importing modules.
+    pub public: bool,
+
+    /// The doc comment of the widget definition, if any.
+    pub doc: Option<String>,
+
+    /// The properties of the widget, sorted by name.
+    pub properties: Vec<PropertySchema>,
+}
+
+impl WidgetSchema {
+    /// Creates a description of the given widget.
+    fn new(widget: &Widget, public: bool) -> Self {
+        let (kind, doc, mut properties): (_, _, Vec<PropertySchema>) = match widget {
+            Widget::Native(native) => {
+                let properties = native
+                    .default_properties
+                    .iter()
+                    .map(|(name, value)| {
+                        let (types, _) = accepted_values(name);
+                        PropertySchema {
+                            name: name.clone(),
+                            types: types.to_vec(),
+                            keywords: native.property_keywords(name).to_vec(),
+                            default: Some(DefaultValue::from(value)),
+                            doc: None,
+                        }
+                    })
+                    .collect();
+                (WidgetKind::Native, None, properties)
+            }
+            Widget::Custom(custom) => {
+                let properties = custom
+                    .default_properties
+                    .iter()
+                    .map(|(name, value)| {
+                        let types = match value {
+                            UnresolvedPropertyValue::Constant(value) => vec![value.value_type()],
+                            UnresolvedPropertyValue::List(_) => vec![PropertyType::List],
+                            UnresolvedPropertyValue::CommaList(_) => vec![PropertyType::CommaList],
+                            UnresolvedPropertyValue::Function { .. } => {
+                                vec![PropertyType::Function]
+                            }
+                            UnresolvedPropertyValue::Variable(_) => Vec::new(),
+                        };
+
+                        PropertySchema {
+                            name: name.clone(),
+                            types,
+                            keywords: Vec::new(),
+                            default: Some(DefaultValue::from(value)),
+                            doc: custom.property_docs.get(name).cloned(),
+                        }
+                    })
+                    .collect();
+                (WidgetKind::Custom, custom.doc.clone(), properties)
+            }
+        };
+
+        properties.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Self {
+            name: widget.name().to_string(),
+            kind,
+            public,
+            doc,
+            properties,
+        }
+    }
+
+    /// Returns the property with the given name, if it is described.
+    pub fn property(&self, name: &str) -> Option<&PropertySchema> {
+        self.properties.iter().find(|p| p.name == name)
+    }
+}
+
+/// A description of a single widget property.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PropertySchema {
+    /// The name of the property.
+    pub name: String,
+
+    /// The value types accepted by the property.
+    ///
+    /// This is empty if the accepted types are unknown, such as for custom
+    /// widget properties that default to a variable.
+    pub types: Vec<PropertyType>,
+
+    /// The keywords accepted as string values, either on their own or within
+    /// lists and functions.
+    ///
+    /// If this is empty, any string is accepted by properties that accept
+    /// strings.
+    pub keywords: Vec<String>,
+
+    /// The default value of the property, if any.
+    pub default: Option<DefaultValue>,
+
+    /// The doc comment of the property, if any.
+    pub doc: Option<String>,
+}
+
+/// The default value of a property.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(tag = "type", content = "value", rename_all = "lowercase")
+)]
+pub enum DefaultValue {
+    /// A string value.
+    String(String),
+
+    /// A numeric value.
+    Number(f64),
+
+    /// A boolean value.
+    Boolean(bool),
+
+    /// A color value, in hexadecimal notation.
+    Color(String),
+
+    /// A percentage value.
+    Percentage(f64),
+
+    /// A pixel value.
+    Pixels(f64),
+
+    /// A grid fraction value.
+    Fraction(f64),
+
+    /// An angle value, in degrees.
+    Angle(f64),
+
+    /// A sequence of values.
+    List(Vec<DefaultValue>),
+
+    /// A comma-separated sequence of values.
+    #[cfg_attr(feature = "serde", serde(rename = "comma-list"))]
+    CommaList(Vec<DefaultValue>),
+
+    /// A function call.
+    Function {
+        /// The name of the function.
+        name: String,
+
+        /// The arguments of the function.
+        args: Vec<DefaultValue>,
+    },
+
+    /// A reference to the variable with the given name.
+    Variable(String),
+}
+
+impl From<&PropertyValue> for DefaultValue {
+    fn from(value: &PropertyValue) -> Self {
+        match value {
+            PropertyValue::String(s) => DefaultValue::String(s.clone()),
+            PropertyValue::Number(n) => DefaultValue::Number(*n),
+            PropertyValue::Bool(b) => DefaultValue::Boolean(*b),
+            PropertyValue::Color(c) => DefaultValue::Color(c.to_hex()),
+            PropertyValue::Percent(p) => DefaultValue::Percentage(*p),
+            PropertyValue::Pixels(px) => DefaultValue::Pixels(*px),
+            PropertyValue::Fraction(fr) => DefaultValue::Fraction(*fr),
+            PropertyValue::Angle(deg) => DefaultValue::Angle(*deg),
+            PropertyValue::List(values) => {
+                DefaultValue::List(values.iter().map(DefaultValue::from).collect())
+            }
+            PropertyValue::CommaList(values) => {
+                DefaultValue::CommaList(values.iter().map(DefaultValue::from).collect())
+            }
+            PropertyValue::Function { name, args } => DefaultValue::Function {
+                name: name.clone(),
+                args: args.iter().map(DefaultValue::from).collect(),
+            },
+        }
+    }
+}
+
+impl From<&UnresolvedPropertyValue> for DefaultValue {
+    fn from(value: &UnresolvedPropertyValue) -> Self {
+        match value {
+            UnresolvedPropertyValue::Constant(value) => DefaultValue::from(value),
+            UnresolvedPropertyValue::Variable(name) => DefaultValue::Variable(name.clone()),
+            UnresolvedPropertyValue::List(values) => {
+                DefaultValue::List(values.iter().map(DefaultValue::from).collect())
+            }
+            UnresolvedPropertyValue::CommaList(values) => {
+                DefaultValue::CommaList(values.iter().map(DefaultValue::from).collect())
+            }
+            UnresolvedPropertyValue::Function { name, args } => DefaultValue::Function {
+                name: name.clone(),
+                args: args.iter().map(DefaultValue::from).collect(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::NekoMaidParser;
+    use crate::widget::NativeWidget;
+
+    #[test]
+    fn describes_widgets() {
+        let code = r#"
+            /// A labelled button.
+            def button {
+              /// The button label.
+              var text = "Click";
+              var size = $font-size;
+
+              layout div {
+                output;
+              }
+            }
+
+            private def spacer {
+              layout div {
+                output;
+              }
+            }
+
+            var font-size = 16;
+        "#;
+
+        let mut parser = NekoMaidParser::tokenize(code).unwrap();
+        for native in NATIVE_WIDGETS.iter() {
+            parser.register_native_widget(native.clone());
+        }
+        let module = parser.finish().unwrap();
+
+        let mut schema = Schema::native();
+        schema.add_module(&module);
+
+        let names: Vec<_> = schema.widgets.iter().map(|w| w.name.as_str()).collect();
+        assert_eq!(names, ["div", "img", "p", "span", "button", "spacer"]);
+
+        let div = schema.widget("div").unwrap();
+        let display = div.property("display").unwrap();
+        assert_eq!(display.types, [PropertyType::String]);
+        assert!(display.keywords.contains(&"grid".to_string()));
+        assert_eq!(display.default, Some(DefaultValue::String("flex".into())));
+
+        let button = schema.widget("button").unwrap();
+        assert_eq!(button.kind, WidgetKind::Custom);
+        assert_eq!(button.doc.as_deref(), Some("A labelled button."));
+        let text = button.property("text").unwrap();
+        assert_eq!(text.types, [PropertyType::String]);
+        assert_eq!(text.doc.as_deref(), Some("The button label."));
+        assert_eq!(
+            button.property("size").unwrap().default,
+            Some(DefaultValue::Variable("font-size".into()))
+        );
+
+        assert!(!schema.widget("spacer").unwrap().public);
+    }
+
+    #[test]
+    fn describes_keywords_of_registered_widgets() {
+        let widget = NativeWidget {
+            name: String::from("health_bar"),
+            default_properties: Arc::new(HashMap::from([(
+                String::from("fill-from"),
+                PropertyValue::from("left"),
+            )])),
+            keywords: Arc::new(HashMap::from([(
+                String::from("fill-from"),
+                vec![String::from("left"), String::from("right")],
+            )])),
+        };
+
+        let mut schema = Schema::default();
+        schema.add_widget(&Widget::Native(widget), true);
+
+        let fill_from = schema.widget("health_bar").unwrap().property("fill-from");
+        assert_eq!(fill_from.unwrap().keywords, ["left", "right"]);
+    }
+}
@@ -0,0 +1,381 @@
+//! Expansion of shorthand properties into the per-side properties they set.
+//!
+//! Shorthands follow CSS, so `margin: 4px 8px;` sets `margin-top` and
+//! `margin-bottom` to `4px`, and `margin-left` and `margin-right` to `8px`.
+
+use std::collections::HashMap;
+
+use crate::value::PropertyValue;
+
+/// The side suffixes of box shorthands, in CSS order.
+const SIDES: [&str; 4] = ["top", "right", "bottom", "left"];
+
+/// The corner suffixes of `border-radius`, in CSS order.
+const CORNERS: [&str; 4] = ["top-left", "top-right", "bottom-right", "bottom-left"];
+
+/// Inserts a property parsed from a block into the given map.
+///
+/// Properties within a block apply in source order, as in CSS, so a shorthand
+/// removes any of its longhands set earlier in the block. In
+/// `margin-top: 8px; margin: 4px;`, the top margin is `4px`.
+pub fn insert_property<V>(properties: &mut HashMap<String, V>, name: String, value: V) {
+    let overridden = longhand_names(&name);
+    properties.retain(|property, _| !overridden.contains(property));
+    properties.insert(name, value);
+}
+
+/// Returns true if the two properties may set the same value, because they
+/// are the same property or one is a shorthand of the other. The order of
+/// such properties within a block matters.
+pub fn properties_overlap(a: &str, b: &str) -> bool {
+    a == b
+        || longhand_names(a).iter().any(|name| name == b)
+        || longhand_names(b).iter().any(|name| name == a)
+}
+
+/// Returns the names of all longhands the given shorthand may set, including
+/// the longhands of the shorthands it sets in turn.
+fn longhand_names(name: &str) -> Vec<String> {
+    let suffixed = |suffixes: &[&str]| {
+        suffixes
+            .iter()
+            .map(|suffix| format!("{}-{}", name, suffix))
+            .collect()
+    };
+
+    match name {
+        "margin" | "padding" | "border-thickness" | "border-color" => suffixed(&SIDES),
+        "border-radius" => suffixed(&CORNERS),
+        "border" => ["border-thickness", "border-color"]
+            .into_iter()
+            .flat_map(|shorthand| {
+                let mut names = longhand_names(shorthand);
+                names.push(shorthand.to_string());
+                names
+            })
+            .collect(),
+        "outline" => suffixed(&["width", "offset", "color"]),
+        _ => Vec::new(),
+    }
+}
+
+/// Expands all shorthand properties in the given map.
+///
+/// The longhands are inserted alongside the shorthand. Longhands that are
+/// already set in the map take priority over the shorthand, as a shorthand
+/// removes the longhands set before it when its block is parsed, with
+/// [`insert_property`]. Any longhand still in the map was therefore set after
+/// the shorthand. Shorthands with an invalid number of values are left as
+/// they are.
+pub fn expand_shorthands(properties: &mut HashMap<String, PropertyValue>) {
+    let mut expanded = Vec::new();
+    for (name, value) in properties.iter() {
+        expanded.extend(expand_shorthand(name, value));
+    }
+
+    let mut set_by_border = Vec::new();
+    for (name, value) in expanded {
+        if properties.contains_key(&name) {
+            continue;
+        }
+
+        // `border` expands into other shorthands, which are expanded in turn.
+        set_by_border.extend(expand_shorthand(&name, &value));
+        properties.insert(name, value);
+    }
+
+    for (name, value) in set_by_border {
+        properties.entry(name).or_insert(value);
+    }
+}
+
+/// Sets a property in the given map. If the property is a shorthand, all of
+/// its longhands are set as well, overriding their previous values.
+pub fn set_property(
+    properties: &mut HashMap<String, PropertyValue>,
+    name: String,
+    value: PropertyValue,
+) {
+    for (longhand, value) in expand_shorthand(&name, &value) {
+        set_property(properties, longhand, value);
+    }
+    properties.insert(name, value);
+}
+
+/// Returns the longhands set by the given property, or nothing if the
+/// property is not a shorthand or its value is invalid.
+pub fn expand_shorthand(name: &str, value: &PropertyValue) -> Vec<(String, PropertyValue)> {
+    let values = match value {
+        PropertyValue::List(values) => values.as_slice(),
+        value => std::slice::from_ref(value),
+    };
+
+    match name {
+        "margin" | "padding" | "border-thickness" | "border-color" => expand_box(values)
+            .map(|values| longhands(name, &SIDES, values))
+            .unwrap_or_default(),
+        "border-radius" => expand_box(values)
+            .map(|values| longhands(name, &CORNERS, values))
+            .unwrap_or_default(),
+        "border" => expand_border(values),
+        "outline" => expand_outline(values),
+        _ => Vec::new(),
+    }
+}
+
+/// Assigns 1 to 4 values to the four sides or corners of a box, using the
+/// CSS rules for omitted values.
+fn expand_box(values: &[PropertyValue]) -> Option<[&PropertyValue; 4]> {
+    match values {
+        [all] => Some([all, all, all, all]),
+        [vertical, horizontal] => Some([vertical, horizontal, vertical, horizontal]),
+        [top, horizontal, bottom] => Some([top, horizontal, bottom, horizontal]),
+        [top, right, bottom, left] => Some([top, right, bottom, left]),
+        _ => None,
+    }
+}
+
+/// Creates the longhand properties for the given shorthand name, suffixes,
+/// and values.
+fn longhands(
+    name: &str,
+    suffixes: &[&str; 4],
+    values: [&PropertyValue; 4],
+) -> Vec<(String, PropertyValue)> {
+    suffixes
+        .iter()
+        .zip(values)
+        .map(|(suffix, value)| (format!("{}-{}", name, suffix), value.clone()))
+        .collect()
+}
+
+/// Expands the `border` shorthand, which takes a thickness, a color, or both,
+/// in any order.
+fn expand_border(values: &[PropertyValue]) -> Vec<(String, PropertyValue)> {
+    let mut thickness = None;
+    let mut color = None;
+
+    for value in values {
+        let slot = match value {
+            PropertyValue::Color(_) => &mut color,
+            _ => &mut thickness,
+        };
+
+        if slot.replace(value.clone()).is_some() {
+            return Vec::new();
+        }
+    }
+
+    let mut longhands = Vec::new();
+    if let Some(thickness) = thickness {
+        longhands.push(("border-thickness".to_string(), thickness));
+    }
+    if let Some(color) = color {
+        longhands.push(("border-color".to_string(), color));
+    }
+    longhands
+}
+
+/// Expands the `outline` shorthand, which takes a width, an optional offset
+/// following the width, and a color, with the color in any position.
+fn expand_outline(values: &[PropertyValue]) -> Vec<(String, PropertyValue)> {
+    let mut lengths = Vec::new();
+    let mut color = None;
+
+    for value in values {
+        match value {
+            PropertyValue::Color(_) => {
+                if color.replace(value.clone()).is_some() {
+                    return Vec::new();
+                }
+            }
+            _ => lengths.push(value.clone()),
+        }
+    }
+
+    let mut longhands = Vec::new();
+    match lengths.as_slice() {
+        [] => {}
+        [width] => longhands.push(("outline-width".to_string(), width.clone())),
+        [width, offset] => {
+            longhands.push(("outline-width".to_string(), width.clone()));
+            longhands.push(("outline-offset".to_string(), offset.clone()));
+        }
+        _ => return Vec::new(),
+    }
+    if let Some(color) = color {
+        longhands.push(("outline-color".to_string(), color));
+    }
+    longhands
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::NekoMaidParser;
+    use crate::color::Rgba;
+    use crate::native::NATIVE_WIDGETS;
+
+    /// Expands the given properties and returns the value of each longhand.
+    fn expand(properties: &[(&str, PropertyValue)]) -> HashMap<String, PropertyValue> {
+        let mut properties = properties
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.clone()))
+            .collect();
+        expand_shorthands(&mut properties);
+        properties
+    }
+
+    #[test]
+    fn expands_box_shorthands() {
+        let padding = PropertyValue::List(vec![
+            PropertyValue::Number(0.0),
+            PropertyValue::Pixels(8.0),
+            PropertyValue::Pixels(4.0),
+        ]);
+        let properties = expand(&[("padding", padding)]);
+
+        assert_eq!(properties["padding-top"], PropertyValue::Number(0.0));
+        assert_eq!(properties["padding-right"], PropertyValue::Pixels(8.0));
+        assert_eq!(properties["padding-bottom"], PropertyValue::Pixels(4.0));
+        assert_eq!(properties["padding-left"], PropertyValue::Pixels(8.0));
+
+        let radius = PropertyValue::List(vec![
+            PropertyValue::Pixels(12.0),
+            PropertyValue::Pixels(12.0),
+            PropertyValue::Number(0.0),
+            PropertyValue::Number(0.0),
+        ]);
+        let properties = expand(&[("border-radius", radius)]);
+
+        assert_eq!(
+            properties["border-radius-top-right"],
+            PropertyValue::Pixels(12.0)
+        );
+        assert_eq!(
+            properties["border-radius-bottom-right"],
+            PropertyValue::Number(0.0)
+        );
+    }
+
+    #[test]
+    fn explicit_longhands_take_priority() {
+        let properties = expand(&[
+            ("margin", PropertyValue::Pixels(4.0)),
+            ("margin-top", PropertyValue::Pixels(8.0)),
+        ]);
+
+        assert_eq!(properties["margin-top"], PropertyValue::Pixels(8.0));
+        assert_eq!(properties["margin-left"], PropertyValue::Pixels(4.0));
+    }
+
+    #[test]
+    fn expands_border() {
+        let color = Rgba::from_hex("#5B4A31").unwrap();
+        let border = PropertyValue::List(vec![
+            PropertyValue::Pixels(2.0),
+            PropertyValue::Color(color),
+        ]);
+        let properties = expand(&[("border", border)]);
+
+        assert_eq!(properties["border-thickness"], PropertyValue::Pixels(2.0));
+        assert_eq!(
+            properties["border-thickness-left"],
+            PropertyValue::Pixels(2.0)
+        );
+        assert_eq!(
+            properties["border-color-bottom"],
+            PropertyValue::Color(color)
+        );
+    }
+
+    #[test]
+    fn expands_outline() {
+        let color = Rgba::from_hex("#FFD700").unwrap();
+        let outline = PropertyValue::List(vec![
+            PropertyValue::Color(color),
+            PropertyValue::Pixels(2.0),
+            PropertyValue::Pixels(1.0),
+        ]);
+        let properties = expand(&[("outline", outline)]);
+
+        assert_eq!(properties["outline-width"], PropertyValue::Pixels(2.0));
+        assert_eq!(properties["outline-offset"], PropertyValue::Pixels(1.0));
+        assert_eq!(properties["outline-color"], PropertyValue::Color(color));
+    }
+
+    #[test]
+    fn later_properties_take_priority() {
+        let code = r#"
+            style div {
+              margin-top: 8px;
+              margin: 4px;
+              border-color: #5B4A31;
+              border: 2px;
+            }
+
+            layout div {
+              padding: 4px;
+              padding-top: 8px;
+            }
+        "#;
+
+        let mut parser = NekoMaidParser::tokenize(code).unwrap();
+        for native in NATIVE_WIDGETS.iter() {
+            parser.register_native_widget(native.clone());
+        }
+        let module = parser.finish().unwrap();
+
+        let mut element = module.elements[0].element.clone();
+        element.resolve(&HashMap::new()).unwrap();
+
+        let get = |name| element.get_property_no_default(name).cloned();
+        assert_eq!(get("margin-top"), Some(PropertyValue::Pixels(4.0)));
+        assert_eq!(get("padding-top"), Some(PropertyValue::Pixels(8.0)));
+        assert_eq!(get("padding-left"), Some(PropertyValue::Pixels(4.0)));
+        assert_eq!(get("border-color-top"), None);
+    }
+
+    #[test]
+    fn ignores_invalid_shorthands() {
+        let margin = PropertyValue::List(vec![PropertyValue::Number(1.0); 5]);
+        let properties = expand(&[("margin", margin)]);
+        assert_eq!(properties.len(), 1);
+    }
+
+    #[test]
+    fn expands_parsed_shorthands() {
+        let code = r#"
+            var gap = 8px;
+
+            style div {
+              margin: 4px $gap;
+              border: 2px #5B4A31;
+            }
+
+            layout div {
+              margin-top: 0;
+            }
+        "#;
+
+        let mut parser = NekoMaidParser::tokenize(code).unwrap();
+        for native in NATIVE_WIDGETS.iter() {
+            parser.register_native_widget(native.clone());
+        }
+        let module = parser.finish().unwrap();
+
+        let variables = HashMap::from([("gap".to_string(), PropertyValue::Pixels(8.0))]);
+        let mut element = module.elements[0].element.clone();
+        element.resolve(&variables).unwrap();
+
+        let get = |name| element.get_property(name).unwrap().clone();
+        assert_eq!(get("margin-top"), PropertyValue::Number(0.0));
+        assert_eq!(get("margin-bottom"), PropertyValue::Pixels(4.0));
+        assert_eq!(get("margin-left"), PropertyValue::Pixels(8.0));
+        assert_eq!(get("border-thickness-right"), PropertyValue::Pixels(2.0));
+        assert_eq!(
+            get("border-color-top"),
+            PropertyValue::Color(Rgba::from_hex("#5B4A31").unwrap())
+        );
+    }
+}
@@ -1,15 +1,19 @@
 //! A parser for NekoMaid UI style definitions.
 
-use bevy::platform::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet};
 
-use crate::parse::NekoMaidParseError;
-use crate::parse::context::{NekoResult, ParseContext};
-use crate::parse::property::{UnresolvedPropertyValue, parse_unresolved_property};
-use crate::parse::token::TokenType;
-use crate::parse::value::PropertyValue;
+use crate::NekoMaidParseError;
+use crate::asset::resolve_asset_paths;
+use crate::context::{NekoResult, ParseContext};
+use crate::property::{UnresolvedPropertyValue, parse_unresolved_property, rename_variables};
+use crate::shorthand::{self, expand_shorthands};
+use crate::token::TokenType;
+use crate::value::PropertyValue;
+use crate::visibility::Visibility;
 
 /// A NekoMaid UI style definition.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Style {
     /// The selector for the style.
     selector: Selector,
@@ -19,6 +23,17 @@ pub struct Style {
 
     /// The properties defined in the style.
     properties: HashMap<String, PropertyValue>,
+
+    /// Whether the style is applied to widgets of modules importing it.
+    visibility: Visibility,
+
+    /// The name of the module declaring this style, if it is a private style
+    /// imported from another module. Such styles only apply to the elements
+    /// created by the widgets of that module.
+    scope: Option<String>,
+
+    /// The doc comment of the style, if any.
+    doc: Option<String>,
 }
 
 impl Style {
@@ -26,22 +41,37 @@ impl Style {
     pub fn new(
         selector: Selector,
         unresolved_properties: HashMap<String, UnresolvedPropertyValue>,
+        visibility: Visibility,
     ) -> Self {
         Self {
             selector,
             unresolved_properties,
             properties: HashMap::new(),
+            visibility,
+            scope: None,
+            doc: None,
         }
     }
 
+    /// Sets the doc comment of this style.
+    pub fn with_doc(mut self, doc: Option<String>) -> Self {
+        self.doc = doc;
+        self
+    }
+
+    /// Returns the doc comment of this style, if any.
+    pub fn doc(&self) -> Option<&str> {
+        self.doc.as_deref()
+    }
+
     /// Returns a reference to the selector of this style.
     pub fn get_property(&self, name: &str) -> Option<&PropertyValue> {
         self.properties.get(name)
     }
 
-    /// Sets a property in this style.
+    /// Sets a property in this style, expanding it if it is a shorthand.
     pub fn set_property(&mut self, name: String, value: PropertyValue) {
-        self.properties.insert(name, value);
+        shorthand::set_property(&mut self.properties, name, value);
     }
 
     /// Returns a reference to the selector of this style.
@@ -54,6 +84,52 @@ impl Style {
         &self.properties
     }
 
+    /// Returns a reference to the properties of this style before variable
+    /// resolution.
+    pub fn unresolved_properties(&self) -> &HashMap<String, UnresolvedPropertyValue> {
+        &self.unresolved_properties
+    }
+
+    /// Returns the visibility of this style.
+    pub fn visibility(&self) -> Visibility {
+        self.visibility
+    }
+
+    /// Returns the name of the module whose elements this style is limited to,
+    /// if it is a private style imported from another module.
+    pub fn scope(&self) -> Option<&str> {
+        self.scope.as_deref()
+    }
+
+    /// Checks whether this style applies to elements created within the
+    /// given scope, which is the module defining the widget that creates
+    /// them, or `None` for elements of the current module.
+    pub fn applies_in(&self, scope: Option<&str>) -> bool {
+        self.scope.is_none() || self.scope.as_deref() == scope
+    }
+
+    /// Limits this style to the elements created by the widgets of the given
+    /// module, unless it is already limited to another module.
+    pub(crate) fn set_scope(&mut self, scope: &str) {
+        self.scope.get_or_insert_with(|| scope.to_string());
+    }
+
+    /// Renames all variables referenced by this style, using the given map of
+    /// old names to new names.
+    pub(crate) fn rename_variables(&mut self, renames: &HashMap<String, String>) {
+        rename_variables(&mut self.unresolved_properties, renames);
+    }
+
+    /// Renames the widgets named by the selector of this style, using the given
+    /// map of old names to new names.
+    pub(crate) fn rename_widgets(&mut self, renames: &HashMap<String, String>) {
+        for part in &mut self.selector.hierarchy {
+            if let Some(new_name) = renames.get(&part.widget) {
+                part.widget = new_name.clone();
+            }
+        }
+    }
+
     /// Resolve properties of this style.
     pub fn resolve(&mut self, variables: &HashMap<String, PropertyValue>) -> NekoResult<()> {
         for (name, value) in &self.unresolved_properties {
@@ -61,12 +137,17 @@ impl Style {
             self.properties.insert(name.clone(), prop);
         }
 
+        expand_shorthands(&mut self.properties);
         Ok(())
     }
 
     /// Merges another style into this one, overriding existing properties, and
     /// adding new ones.
     pub fn merge(&mut self, other: Style) {
+        if self.doc.is_none() {
+            self.doc = other.doc;
+        }
+
         for (key, value) in other.properties {
             self.properties.insert(key, value);
         }
@@ -75,6 +156,7 @@ impl Style {
 
 /// A selector for targeting widgets in styles.
 #[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Selector {
     /// A hierarchy of selector parts, to target multi-level widget structures.
     pub hierarchy: Vec<SelectorPart>,
@@ -82,6 +164,7 @@ pub struct Selector {
 
 /// A part of a style selector, targeting a specific widget and classes.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SelectorPart {
     /// The widget the selector part applies to.
     pub widget: String,
@@ -94,11 +177,17 @@ pub struct SelectorPart {
 }
 
 /// Parses a style from the given parse context.
-pub fn parse_style(ctx: &mut ParseContext, mut selector: Selector) -> NekoResult<()> {
+pub fn parse_style(
+    ctx: &mut ParseContext,
+    mut selector: Selector,
+    visibility: Visibility,
+) -> NekoResult<()> {
+    let doc = ctx.peek_doc();
     ctx.maybe_consume(TokenType::StyleKeyword);
     ctx.maybe_consume(TokenType::WithKeyword);
 
     let selector_part = parse_style_selector(ctx)?;
+    let widget = selector_part.widget.clone();
     selector.hierarchy.push(selector_part);
 
     ctx.expect(TokenType::OpenBrace)?;
@@ -108,11 +197,12 @@ pub fn parse_style(ctx: &mut ParseContext, mut selector: Selector) -> NekoResult
     while let Some(next) = ctx.peek() {
         match next.token_type {
             TokenType::Identifier => {
-                let property = parse_unresolved_property(ctx)?;
-                properties.insert(property.name, property.value);
+                let mut property = parse_unresolved_property(ctx)?;
+                resolve_asset_paths(ctx, &widget, &property.name, &mut property.value);
+                shorthand::insert_property(&mut properties, property.name, property.value);
             }
             TokenType::WithKeyword => {
-                parse_style(ctx, selector.clone())?;
+                parse_style(ctx, selector.clone(), visibility)?;
             }
             TokenType::CloseBrace => break,
             _ => {
@@ -131,7 +221,7 @@ pub fn parse_style(ctx: &mut ParseContext, mut selector: Selector) -> NekoResult
 
     ctx.expect(TokenType::CloseBrace)?;
 
-    ctx.add_style(Style::new(selector, properties));
+    ctx.add_style(Style::new(selector, properties, visibility).with_doc(doc));
 
     Ok(())
 }
@@ -141,12 +231,7 @@ pub fn parse_style_selector(ctx: &mut ParseContext) -> NekoResult<SelectorPart>
     let widget_position = ctx.next_position().unwrap_or_default();
     let widget = ctx.expect_as_string(TokenType::Identifier)?;
 
-    if ctx.get_widget(&widget).is_none() {
-        return Err(NekoMaidParseError::UnknownWidget {
-            widget,
-            position: widget_position,
-        });
-    }
+    ctx.reference_widget(widget.clone(), widget_position);
 
     let mut whitelist = HashSet::new();
     let mut blacklist = HashSet::new();
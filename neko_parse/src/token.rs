@@ -2,10 +2,9 @@
 
 use std::fmt;
 
-use bevy::color::Color;
-
-use crate::parse::NekoMaidParseError;
-use crate::parse::value::PropertyValue;
+use crate::NekoMaidParseError;
+use crate::color::Rgba;
+use crate::value::PropertyValue;
 
 /// A token with its type and position.
 #[derive(Debug, Clone, PartialEq)]
@@ -117,6 +116,38 @@ impl Token {
         }
     }
 
+    /// Converts the token value to a grid fraction number, if possible.
+    /// Otherwise, returns an error.
+    pub fn into_fraction_property(
+        self,
+        position: TokenPosition,
+    ) -> Result<PropertyValue, NekoMaidParseError> {
+        match self.value {
+            TokenValue::Number(n) => Ok(PropertyValue::Fraction(n)),
+            v => Err(NekoMaidParseError::InvalidTokenValue {
+                expected: "number".to_string(),
+                found: format!("{:?}", v),
+                position,
+            }),
+        }
+    }
+
+    /// Converts the token value to an angle in degrees, if possible.
+    /// Otherwise, returns an error.
+    pub fn into_angle_property(
+        self,
+        position: TokenPosition,
+    ) -> Result<PropertyValue, NekoMaidParseError> {
+        match self.value {
+            TokenValue::Number(n) => Ok(PropertyValue::Angle(n)),
+            v => Err(NekoMaidParseError::InvalidTokenValue {
+                expected: "number".to_string(),
+                found: format!("{:?}", v),
+                position,
+            }),
+        }
+    }
+
     /// Converts the token value to a variable name string, if possible.
     /// Otherwise, returns an error.
     pub fn into_variable_name(self, position: TokenPosition) -> Result<String, NekoMaidParseError> {
@@ -145,7 +176,7 @@ pub enum TokenValue {
     Number(f64),
 
     /// A color literal.
-    Color(Color),
+    Color(Rgba),
 
     /// A boolean literal.
     Boolean(bool),
@@ -188,7 +219,7 @@ impl TokenValue {
     }
 
     /// Attempts to extract the color value from the token.
-    pub fn as_color(self) -> Option<Color> {
+    pub fn as_color(self) -> Option<Rgba> {
         match self {
             TokenValue::Color(c) => Some(c),
             _ => None,
@@ -226,8 +257,8 @@ impl From<bool> for TokenValue {
     }
 }
 
-impl From<Color> for TokenValue {
-    fn from(c: Color) -> Self {
+impl From<Rgba> for TokenValue {
+    fn from(c: Rgba) -> Self {
         TokenValue::Color(c)
     }
 }
@@ -257,6 +288,15 @@ pub enum TokenType {
     /// The equals symbol.
     Equals,
 
+    /// The open parenthesis symbol.
+    OpenParen,
+
+    /// The close parenthesis symbol.
+    CloseParen,
+
+    /// The comma symbol.
+    Comma,
+
     // === Keywords ===
     /// The `import` keyword.
     ImportKeyword,
@@ -282,6 +322,13 @@ pub enum TokenType {
     /// The `children` keyword.
     OutputKeyword,
 
+    /// The `private` keyword. It is only a keyword directly before `var`,
+    /// `def`, or `style`, and is an identifier anywhere else.
+    PrivateKeyword,
+
+    /// The `font-face` keyword.
+    FontFaceKeyword,
+
     // === Literals ===
     /// A boolean literal.
     BooleanLiteral,
@@ -298,6 +345,12 @@ pub enum TokenType {
     /// A pixels literal.
     PixelsLiteral,
 
+    /// A grid fraction literal, such as `1fr`.
+    FractionLiteral,
+
+    /// An angle literal in degrees, such as `90deg`.
+    AngleLiteral,
+
     /// A string literal.
     StringLiteral,
 
@@ -308,10 +361,17 @@ pub enum TokenType {
     /// An identifier token.
     Identifier,
 
+    // === Documentation ===
+    /// A doc comment token, starting with `///`.
+    DocComment,
+
     // === Ignore ===
-    /// A comment token.
+    /// A `//` line comment token.
     Comment,
 
+    /// A `/* */` block comment token.
+    BlockComment,
+
     /// No more tokens (end of stream)
     EndOfStream,
 }
@@ -327,6 +387,9 @@ impl TokenType {
             TokenType::OpenBrace => "{",
             TokenType::CloseBrace => "}",
             TokenType::Equals => "=",
+            TokenType::OpenParen => "(",
+            TokenType::CloseParen => ")",
+            TokenType::Comma => ",",
             TokenType::ImportKeyword => "import",
             TokenType::StyleKeyword => "style",
             TokenType::VarKeyword => "var",
@@ -335,15 +398,21 @@ impl TokenType {
             TokenType::DefKeyword => "def",
             TokenType::ClassKeyword => "class",
             TokenType::OutputKeyword => "children",
+            TokenType::PrivateKeyword => "private",
+            TokenType::FontFaceKeyword => "font-face",
             TokenType::BooleanLiteral => "boolean",
             TokenType::ColorLiteral => "color",
             TokenType::NumberLiteral => "number",
             TokenType::PercentLiteral => "percent",
             TokenType::PixelsLiteral => "pixels",
+            TokenType::FractionLiteral => "fraction",
+            TokenType::AngleLiteral => "angle",
             TokenType::StringLiteral => "string",
             TokenType::Variable => "variable",
             TokenType::Identifier => "identifier",
+            TokenType::DocComment => "doc comment",
             TokenType::Comment => "comment",
+            TokenType::BlockComment => "block comment",
             TokenType::EndOfStream => "EOS",
         }
     }
@@ -352,7 +421,10 @@ impl TokenType {
     pub fn has_string(&self) -> bool {
         matches!(
             self,
-            TokenType::Identifier | TokenType::StringLiteral | TokenType::Variable
+            TokenType::Identifier
+                | TokenType::StringLiteral
+                | TokenType::Variable
+                | TokenType::DocComment
         )
     }
 
@@ -360,7 +432,11 @@ impl TokenType {
     pub fn has_number(&self) -> bool {
         matches!(
             self,
-            TokenType::NumberLiteral | TokenType::PercentLiteral | TokenType::PixelsLiteral
+            TokenType::NumberLiteral
+                | TokenType::PercentLiteral
+                | TokenType::PixelsLiteral
+                | TokenType::FractionLiteral
+                | TokenType::AngleLiteral
         )
     }
 
@@ -376,7 +452,10 @@ impl TokenType {
 
     /// Returns true if the token type should be ignored by the tokenizer.
     pub fn is_ignore(&self) -> bool {
-        matches!(self, TokenType::Comment | TokenType::EndOfStream)
+        matches!(
+            self,
+            TokenType::Comment | TokenType::BlockComment | TokenType::EndOfStream
+        )
     }
 }
 
@@ -388,6 +467,7 @@ impl fmt::Display for TokenType {
 
 /// Represents the position of a token within the input string.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TokenPosition {
     /// The line number of the token.
     pub line: usize,
@@ -1,10 +1,10 @@
 //! A tokenizer for parsing source code into tokens.
 
-use bevy::color::Srgba;
 use lazy_static::lazy_static;
 use regex::Regex;
 
-use crate::parse::token::{Token, TokenPosition, TokenType, TokenValue};
+use crate::color::Rgba;
+use crate::token::{Token, TokenPosition, TokenType, TokenValue};
 
 #[rustfmt::skip]
 lazy_static! {
@@ -17,6 +17,9 @@ lazy_static! {
         (TokenType::OpenBrace,       Regex::new(r"^\s*(\{)").unwrap()),
         (TokenType::CloseBrace,      Regex::new(r"^\s*(\})").unwrap()),
         (TokenType::Equals,          Regex::new(r"^\s*(=)").unwrap()),
+        (TokenType::OpenParen,       Regex::new(r"^\s*(\()").unwrap()),
+        (TokenType::CloseParen,      Regex::new(r"^\s*(\))").unwrap()),
+        (TokenType::Comma,           Regex::new(r"^\s*(,)").unwrap()),
 
         // keywords
         (TokenType::ImportKeyword,   Regex::new(r"^\s*(import)\b").unwrap()),
@@ -27,12 +30,16 @@ lazy_static! {
         (TokenType::DefKeyword,      Regex::new(r"^\s*(def)\b").unwrap()),
         (TokenType::ClassKeyword,    Regex::new(r"^\s*(class)\b").unwrap()),
         (TokenType::OutputKeyword,   Regex::new(r"^\s*(output)\b").unwrap()),
+        (TokenType::PrivateKeyword,  Regex::new(r"^\s*(private)\s+(?P<next>var|def|style)\b").unwrap()),
+        (TokenType::FontFaceKeyword, Regex::new(r"^\s*(font-face)\b").unwrap()),
 
         // literals
         (TokenType::BooleanLiteral,  Regex::new(r"^\s*([Tt]rue|[Ff]alse)\b").unwrap()),
         (TokenType::ColorLiteral,    Regex::new(r"^\s*#([a-fA-F0-9]{8}|[a-fA-F0-9]{6}|[a-fA-F0-9]{4}|[a-fA-F0-9]{3})\b").unwrap()),
         (TokenType::PercentLiteral,  Regex::new(r"^\s*(-?\d+\.?\d*|-?\d*\.\d+)%").unwrap()),
         (TokenType::PixelsLiteral,   Regex::new(r"^\s*(-?\d+\.?\d*|-?\d*\.\d+)px\b").unwrap()),
+        (TokenType::AngleLiteral,    Regex::new(r"^\s*(-?\d+\.?\d*|-?\d*\.\d+)deg\b").unwrap()),
+        (TokenType::FractionLiteral, Regex::new(r"^\s*(-?\d+\.?\d*|-?\d*\.\d+)fr\b").unwrap()),
         (TokenType::NumberLiteral,   Regex::new(r"^\s*(-?\d+\.?\d*|-?\d*\.\d+)\b").unwrap()),
         (TokenType::StringLiteral,   Regex::new(r#"^\s*"(.*)""#).unwrap()),
         (TokenType::StringLiteral,   Regex::new(r#"^\s*'(.*)'"#).unwrap()),
@@ -42,8 +49,12 @@ lazy_static! {
         (TokenType::Variable,        Regex::new(r"^\s*\$([a-zA-Z_][a-zA-Z0-9_-]*)").unwrap()),
         (TokenType::Identifier,      Regex::new(r"^\s*([a-zA-Z_][a-zA-Z0-9_-]*)").unwrap()),
 
+        // documentation
+        (TokenType::DocComment,      Regex::new(r"^\s*///[ \t]?(.*)(?:\n|$)").unwrap()),
+
         // ignore
         (TokenType::Comment,         Regex::new(r"^\s*//(.*)(?:\n|$)").unwrap()),
+        (TokenType::BlockComment,    Regex::new(r"^\s*/\*((?s:.*?))\*/").unwrap()),
         (TokenType::EndOfStream,     Regex::new(r"^(\s*)$").unwrap()),
     ];
 }
@@ -77,35 +88,90 @@ impl Tokenizer {
     /// Tokenizes the given source code into a vector of tokens.
     ///
     /// Tokens marked as "ignore" (e.g., whitespace, comments) are omitted from
-    /// the resulting vector.
+    /// the resulting vector. Doc comments are kept, so that they can be
+    /// attached to the declarations that follow them.
     pub fn tokenize(code: &str) -> Result<Vec<Token>, TokenizeError> {
-        let mut position = CodePos::default();
         let mut tokens = Vec::new();
 
-        'outer: while position.index < code.len() {
-            for (token_type, regex) in TOKENS.iter() {
-                if let Some(t) = try_token(code, &mut position, regex, *token_type) {
-                    if !t.token_type.is_ignore() {
-                        tokens.push(t);
-                    }
-                    continue 'outer;
-                }
+        scan(code, |token, _| {
+            if !token.token_type.is_ignore() {
+                tokens.push(token);
             }
+        })?;
 
-            return Err(TokenizeError::UnexpectedCharacter {
-                character: code.chars().nth(position.index).unwrap(),
-                position: TokenPosition {
-                    line: position.line,
-                    column: position.column,
-                    length: 1,
-                },
-            });
-        }
+        Ok(tokens)
+    }
+
+    /// Tokenizes the given source code into a vector of tokens, keeping the
+    /// source text of each token.
+    ///
+    /// Unlike [`Tokenizer::tokenize`], comments are kept in the resulting
+    /// vector, so that the source code can be reconstructed from the tokens.
+    pub fn tokenize_source(code: &str) -> Result<Vec<SourceToken>, TokenizeError> {
+        let mut tokens = Vec::new();
+
+        scan(code, |token, text| {
+            if token.token_type != TokenType::EndOfStream {
+                tokens.push(SourceToken {
+                    token,
+                    text: text.to_string(),
+                });
+            }
+        })?;
 
         Ok(tokens)
     }
 }
 
+/// A token along with the source text it was parsed from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceToken {
+    /// The parsed token.
+    pub token: Token,
+
+    /// The source text of the token, without surrounding whitespace.
+    pub text: String,
+}
+
+impl SourceToken {
+    /// Returns the line on which this token ends.
+    pub fn end_line(&self) -> usize {
+        self.token.position.line + self.text.matches('\n').count()
+    }
+}
+
+/// Scans the given source code, calling `emit` for every token found along with
+/// its source text.
+fn scan(code: &str, mut emit: impl FnMut(Token, &str)) -> Result<(), TokenizeError> {
+    let mut position = CodePos::default();
+
+    'outer: while position.index < code.len() {
+        let start = position.index;
+
+        for (token_type, regex) in TOKENS.iter() {
+            if let Some(mut t) = try_token(code, &mut position, regex, *token_type) {
+                let source = &code[start .. position.index];
+                let text = source.trim_start();
+                skip_whitespace(&source[.. source.len() - text.len()], &mut t.position);
+
+                emit(t, text.trim_end());
+                continue 'outer;
+            }
+        }
+
+        return Err(TokenizeError::UnexpectedCharacter {
+            character: code.chars().nth(position.index).unwrap(),
+            position: TokenPosition {
+                line: position.line,
+                column: position.column,
+                length: 1,
+            },
+        });
+    }
+
+    Ok(())
+}
+
 /// Errors that can occur during tokenization.
 #[derive(Debug, thiserror::Error, Clone, PartialEq)]
 pub enum TokenizeError {
@@ -120,6 +186,8 @@ pub enum TokenizeError {
     },
 }
 
+/// Attempts to match a token of the given type at the current position in the
+/// code, advancing the position past the match if successful.
 fn try_token(
     code: &str,
     position: &mut CodePos,
@@ -159,9 +227,7 @@ fn try_token(
 
         if token_type.has_color() {
             let matched_str = &code[start .. end];
-            let color = Srgba::hex(matched_str)
-                .expect("Hex code Validated by regex")
-                .into();
+            let color = Rgba::from_hex(matched_str).expect("Hex code Validated by regex");
             token.value = TokenValue::Color(color);
         }
 
@@ -171,6 +237,19 @@ fn try_token(
     }
 }
 
+/// Moves the given token position past the given leading whitespace, so that
+/// it points to the start of the token text.
+fn skip_whitespace(whitespace: &str, position: &mut TokenPosition) {
+    for c in whitespace.chars() {
+        if c == '\n' {
+            position.line += 1;
+            position.column = 1;
+        } else {
+            position.column += 1;
+        }
+    }
+}
+
 /// Updates the current token position based on the new start index.
 fn update_position(code: &str, position: &mut CodePos, new_start: usize) {
     for c in code[position.index .. new_start].chars() {
@@ -188,7 +267,8 @@ fn update_position(code: &str, position: &mut CodePos, new_start: usize) {
 ///
 /// Returns the start and end indices of the match if successful. Also returns
 /// the end index of the full match (including any trailing characters that are
-/// not part of the captured group).
+/// not part of the captured group). A group named `next` is matched but not
+/// consumed, so that it is scanned again as the following token.
 fn try_regex(re: &Regex, code: &str, offset: usize) -> Option<(usize, usize, usize)> {
     if let Some(captures) = re.captures(&code[offset ..])
         && let Some(matched) = captures.get(1)
@@ -196,7 +276,10 @@ fn try_regex(re: &Regex, code: &str, offset: usize) -> Option<(usize, usize, usi
     {
         let start = matched.start() + offset;
         let end = matched.end() + offset;
-        let full_end = full_match.end() + offset;
+        let full_end = match captures.name("next") {
+            Some(next) => next.start() + offset,
+            None => full_match.end() + offset,
+        };
         return Some((start, end, full_end));
     }
     None
@@ -301,6 +384,26 @@ but not here";
         assert_eq!(tokens[4].value, "red-blue".into());
     }
 
+    #[test]
+    fn private_is_only_a_keyword_before_declarations() {
+        let code = "private\n  def private-x private";
+        let tokens = Tokenizer::tokenize(code).unwrap();
+
+        assert_eq!(tokens.len(), 4);
+
+        assert_eq!(tokens[0].token_type, TokenType::PrivateKeyword);
+        assert_eq!(tokens[0].position.length, 7);
+
+        assert_eq!(tokens[1].token_type, TokenType::DefKeyword);
+        assert_eq!(tokens[1].position.line, 2);
+
+        assert_eq!(tokens[2].token_type, TokenType::Identifier);
+        assert_eq!(tokens[2].value, "private-x".into());
+
+        assert_eq!(tokens[3].token_type, TokenType::Identifier);
+        assert_eq!(tokens[3].value, "private".into());
+    }
+
     #[test]
     fn tokenizer_numbers() {
         let code = "123 45.67 0.001 1000 .5 1. -3 -.2";
@@ -330,6 +433,30 @@ but not here";
         assert_eq!(tokens[6].value, (-3.0).into());
     }
 
+    #[test]
+    fn tokenize_functions() {
+        let code = "repeat(2, 1fr .5fr) 45deg";
+        let tokens = Tokenizer::tokenize(code).unwrap();
+
+        let types: Vec<_> = tokens.iter().map(|t| t.token_type).collect();
+        assert_eq!(
+            types,
+            [
+                TokenType::Identifier,
+                TokenType::OpenParen,
+                TokenType::NumberLiteral,
+                TokenType::Comma,
+                TokenType::FractionLiteral,
+                TokenType::FractionLiteral,
+                TokenType::CloseParen,
+                TokenType::AngleLiteral,
+            ]
+        );
+        assert_eq!(tokens[4].value, 1.0.into());
+        assert_eq!(tokens[5].value, 0.5.into());
+        assert_eq!(tokens[7].value, 45.0.into());
+    }
+
     #[test]
     fn tokenize_strings() {
         let code = r#""hello" 'world' `backtick`"#;
@@ -346,4 +473,29 @@ but not here";
         assert_eq!(tokens[2].token_type, TokenType::StringLiteral);
         assert_eq!(tokens[2].value, "backtick".into());
     }
+
+    #[test]
+    fn tokenize_comments() {
+        let code = r"
+// line comment
+/* block
+   comment */ style
+/// first line
+///second line
+def";
+        let tokens = Tokenizer::tokenize(code).unwrap();
+
+        assert_eq!(tokens.len(), 4);
+
+        assert_eq!(tokens[0].token_type, TokenType::StyleKeyword);
+
+        assert_eq!(tokens[1].token_type, TokenType::DocComment);
+        assert_eq!(tokens[1].value, "first line".into());
+
+        assert_eq!(tokens[2].token_type, TokenType::DocComment);
+        assert_eq!(tokens[2].value, "second line".into());
+
+        assert_eq!(tokens[3].token_type, TokenType::DefKeyword);
+        assert_eq!(tokens[3].position.line, 7);
+    }
 }
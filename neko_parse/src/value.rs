@@ -0,0 +1,154 @@
+//! A module for working with NekoMaid UI element property values.
+
+use std::fmt;
+
+use crate::color::Rgba;
+use crate::property::PropertyType;
+
+/// A value of a NekoMaid UI element property.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PropertyValue {
+    /// A string value.
+    String(String),
+
+    /// A numeric value.
+    Number(f64),
+
+    /// A boolean value.
+    Bool(bool),
+
+    /// A color value.
+    Color(Rgba),
+
+    /// A percentage number value.
+    Percent(f64),
+
+    /// A pixel number value.
+    Pixels(f64),
+
+    /// A grid fraction value, such as `1fr`.
+    Fraction(f64),
+
+    /// An angle value in degrees, such as `90deg`.
+    Angle(f64),
+
+    /// A sequence of values, such as `4px 8px`.
+    List(Vec<PropertyValue>),
+
+    /// A comma-separated sequence of values, such as `0 2px #000, 0 4px #000`.
+    CommaList(Vec<PropertyValue>),
+
+    /// A function call, such as `minmax(100px, 1fr)`.
+    Function {
+        /// The name of the function.
+        name: String,
+
+        /// The arguments of the function.
+        args: Vec<PropertyValue>,
+    },
+}
+
+impl PropertyValue {
+    /// Returns the type of this property value.
+    pub fn value_type(&self) -> PropertyType {
+        match self {
+            PropertyValue::String(_) => PropertyType::String,
+            PropertyValue::Number(_) => PropertyType::Number,
+            PropertyValue::Bool(_) => PropertyType::Boolean,
+            PropertyValue::Color(_) => PropertyType::Color,
+            PropertyValue::Percent(_) => PropertyType::Percentage,
+            PropertyValue::Pixels(_) => PropertyType::Pixels,
+            PropertyValue::Fraction(_) => PropertyType::Fraction,
+            PropertyValue::Angle(_) => PropertyType::Angle,
+            PropertyValue::List(_) => PropertyType::List,
+            PropertyValue::CommaList(_) => PropertyType::CommaList,
+            PropertyValue::Function { .. } => PropertyType::Function,
+        }
+    }
+}
+
+impl From<String> for PropertyValue {
+    fn from(value: String) -> Self {
+        PropertyValue::String(value)
+    }
+}
+
+impl From<&String> for PropertyValue {
+    fn from(value: &String) -> Self {
+        PropertyValue::String(value.clone())
+    }
+}
+
+impl From<&str> for PropertyValue {
+    fn from(value: &str) -> Self {
+        PropertyValue::String(value.to_string())
+    }
+}
+
+impl From<f64> for PropertyValue {
+    fn from(value: f64) -> Self {
+        PropertyValue::Number(value)
+    }
+}
+
+impl From<i32> for PropertyValue {
+    fn from(value: i32) -> Self {
+        PropertyValue::Number(value as f64)
+    }
+}
+
+impl From<bool> for PropertyValue {
+    fn from(value: bool) -> Self {
+        PropertyValue::Bool(value)
+    }
+}
+
+impl From<Rgba> for PropertyValue {
+    fn from(value: Rgba) -> Self {
+        PropertyValue::Color(value)
+    }
+}
+
+impl fmt::Display for PropertyValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PropertyValue::String(s) => write!(f, "\"{}\"", s),
+            PropertyValue::Number(n) => write!(f, "{}", n),
+            PropertyValue::Bool(b) => write!(f, "{}", b),
+            PropertyValue::Percent(p) => write!(f, "{}%", p),
+            PropertyValue::Pixels(px) => write!(f, "{}px", px),
+            PropertyValue::Color(c) => write!(f, "{}", c.to_hex()),
+            PropertyValue::Fraction(fr) => write!(f, "{}fr", fr),
+            PropertyValue::Angle(deg) => write!(f, "{}deg", deg),
+            PropertyValue::List(values) => {
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " ")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                Ok(())
+            }
+            PropertyValue::CommaList(values) => {
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                Ok(())
+            }
+            PropertyValue::Function { name, args } => {
+                write!(f, "{}(", name)?;
+                for (i, arg) in args.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", arg)?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
@@ -0,0 +1,99 @@
+//! Handles the visibility of module-level declarations, and hides private
+//! declarations from modules that import them.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::context::ParseContext;
+use crate::module::Module;
+use crate::token::TokenType;
+use crate::widget::Widget;
+
+/// The visibility of a module-level declaration.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Visibility {
+    /// The declaration is exported to modules that import it.
+    #[default]
+    Public,
+
+    /// The declaration is only visible within the module that declares it.
+    Private,
+}
+
+/// Parses an optional visibility modifier from the input.
+///
+/// Declarations without a modifier are public. Doc comments preceding the
+/// modifier are attached to the declaration.
+pub fn parse_visibility(ctx: &mut ParseContext) -> Visibility {
+    let doc = ctx.peek_doc();
+
+    match ctx.maybe_consume(TokenType::PrivateKeyword) {
+        Some(_) => {
+            ctx.attach_doc(doc);
+            Visibility::Private
+        }
+        None => Visibility::Public,
+    }
+}
+
+/// Renames all private variables and widgets of the given module so that they
+/// cannot be referenced by name from an importing module, and limits all
+/// private styles to the elements created by the widgets of the module.
+///
+/// Private widgets and variables are still required in order to build the
+/// public widgets that use them, so they are kept under a name prefixed by
+/// the module name, which is not a valid identifier in NekoMaid UI.
+pub(crate) fn hide_private_items(module: &mut Module, prefix: &str) {
+    let variables = mangle_names(&module.private_variables, prefix);
+    let widgets = mangle_names(&module.private_widgets, prefix);
+
+    module.variables = module
+        .variables
+        .drain()
+        .map(|(name, mut value)| {
+            value.rename_variable(&variables);
+            (variables.get(&name).cloned().unwrap_or(name), value)
+        })
+        .collect();
+
+    module.widgets = module
+        .widgets
+        .drain()
+        .map(|(name, mut widget)| {
+            let name = widgets.get(&name).cloned().unwrap_or(name);
+            if let Widget::Custom(custom) = &mut widget {
+                custom.name = name.clone();
+                custom.scope.get_or_insert_with(|| prefix.to_string());
+                custom.rename(&widgets, &variables);
+            }
+            (name, widget)
+        })
+        .collect();
+
+    for style in &mut module.styles {
+        if style.visibility() == Visibility::Private {
+            style.set_scope(prefix);
+        }
+        style.rename_variables(&variables);
+        style.rename_widgets(&widgets);
+    }
+
+    for element in module
+        .elements
+        .iter_mut()
+        .chain(module.named_elements.values_mut())
+    {
+        element.rename_variables(&variables);
+    }
+
+    module.private_variables = variables.into_values().collect();
+    module.private_widgets = widgets.into_values().collect();
+}
+
+/// Creates a map from each name to its name prefixed by the module name.
+fn mangle_names(names: &HashSet<String>, prefix: &str) -> HashMap<String, String> {
+    names
+        .iter()
+        .map(|name| (name.clone(), format!("{}::{}", prefix, name)))
+        .collect()
+}
@@ -1,22 +1,25 @@
 //! A module for parsing NekoMaid UI widget definitions.
 
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
-use bevy::asset::AssetServer;
-use bevy::ecs::entity::Entity;
-use bevy::ecs::system::{Commands, Res};
-use bevy::platform::collections::{HashMap, HashSet};
-
-use crate::parse::NekoMaidParseError;
-use crate::parse::class::parse_class;
-use crate::parse::context::{NekoResult, ParseContext};
-use crate::parse::element::NekoElement;
-use crate::parse::property::{UnresolvedPropertyValue, parse_unresolved_property, parse_variable};
-use crate::parse::token::TokenType;
-use crate::parse::value::PropertyValue;
+use crate::asset::resolve_asset_paths;
+use crate::class::parse_class;
+use crate::context::{NekoResult, ParseContext};
+use crate::property::{
+    UnresolvedPropertyValue,
+    parse_unresolved_property,
+    parse_variable,
+    rename_variables,
+};
+use crate::token::{TokenPosition, TokenType};
+use crate::value::PropertyValue;
+use crate::{NekoMaidParseError, shorthand};
 
 /// A NekoMaid UI widget definition.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[allow(clippy::large_enum_variant)]
 pub enum Widget {
     /// A custom widget defined in NekoMaid UI.
     Custom(CustomWidget),
@@ -45,6 +48,7 @@ impl Widget {
 
 /// A custom widget definition.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CustomWidget {
     /// The name of the widget.
     pub name: String,
@@ -54,17 +58,51 @@ pub struct CustomWidget {
 
     /// The layout of the widget.
     pub layout: WidgetLayout,
+
+    /// The position of the widget definition in the source code.
+    pub position: TokenPosition,
+
+    /// The doc comment of the widget definition, if any.
+    pub doc: Option<String>,
+
+    /// The doc comments of the widget properties, by property name.
+    pub property_docs: HashMap<String, String>,
+
+    /// The name of the module defining this widget, if it was imported from
+    /// another module. The private styles of that module apply to the
+    /// elements created by this widget.
+    pub scope: Option<String>,
 }
 
 impl CustomWidget {
     /// Checks if the widget has a property with the given name.
     pub fn has_property(&self, property_name: &str) -> bool {
-        self.default_properties.get(property_name).is_some()
+        self.default_properties.contains_key(property_name)
+    }
+
+    /// Renames the widgets and variables referenced by this widget, using the
+    /// given maps of old names to new names.
+    ///
+    /// Variables shadowed by the properties of this widget are not renamed.
+    pub(crate) fn rename(
+        &mut self,
+        widgets: &HashMap<String, String>,
+        variables: &HashMap<String, String>,
+    ) {
+        let variables = variables
+            .iter()
+            .filter(|(name, _)| !self.default_properties.contains_key(*name))
+            .map(|(name, new_name)| (name.clone(), new_name.clone()))
+            .collect();
+
+        rename_variables(&mut self.default_properties, &variables);
+        self.layout.rename(widgets, &variables);
     }
 }
 
 /// A native widget definition.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NativeWidget {
     /// The name of the widget.
     pub name: String,
@@ -72,11 +110,9 @@ pub struct NativeWidget {
     /// The default properties of the widget.
     pub default_properties: Arc<HashMap<String, PropertyValue>>,
 
-    /// The function used to spawn the widget.
-    ///
-    /// This function takes a mutable reference to `Commands` and the parent
-    /// entity, and returns the spawned widget entity.
-    pub spawn_func: fn(&Res<AssetServer>, &mut Commands, &NekoElement, Entity) -> Entity,
+    /// The keywords accepted as string values by the properties of the
+    /// widget, by property name.
+    pub keywords: Arc<HashMap<String, Vec<String>>>,
 }
 
 impl NativeWidget {
@@ -84,6 +120,15 @@ impl NativeWidget {
     pub fn has_property(&self, property_name: &str) -> bool {
         self.default_properties.get(property_name).is_some()
     }
+
+    /// Returns the keywords accepted by the property with the given name. If
+    /// the list is empty, any string is accepted.
+    pub fn property_keywords(&self, property_name: &str) -> &[String] {
+        self.keywords
+            .get(property_name)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
 }
 
 impl PartialEq<NativeWidget> for NativeWidget {
@@ -94,6 +139,7 @@ impl PartialEq<NativeWidget> for NativeWidget {
 
 /// Represents a layout for a widget definition in the UI.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct WidgetLayout {
     /// The widget type.
     pub widget: String,
@@ -111,8 +157,29 @@ pub struct WidgetLayout {
     pub is_output: bool,
 }
 
+impl WidgetLayout {
+    /// Renames the widgets and variables referenced by this layout and its
+    /// children, using the given maps of old names to new names.
+    pub(crate) fn rename(
+        &mut self,
+        widgets: &HashMap<String, String>,
+        variables: &HashMap<String, String>,
+    ) {
+        if let Some(new_name) = widgets.get(&self.widget) {
+            self.widget = new_name.clone();
+        }
+
+        rename_variables(&mut self.properties, variables);
+
+        for child in &mut self.children {
+            child.rename(widgets, variables);
+        }
+    }
+}
+
 /// Parses a widget from the input and returns a [`Widget`].
 pub fn parse_widget(ctx: &mut ParseContext) -> NekoResult<Widget> {
+    let doc = ctx.peek_doc();
     ctx.expect(TokenType::DefKeyword)?;
 
     let widget_position = ctx.next_position().unwrap_or_default();
@@ -121,12 +188,19 @@ pub fn parse_widget(ctx: &mut ParseContext) -> NekoResult<Widget> {
     ctx.expect(TokenType::OpenBrace)?;
 
     let mut properties = HashMap::new();
+    let mut property_docs = HashMap::new();
     let mut layout = None;
 
     while let Some(next) = ctx.peek() {
         match next.token_type {
             TokenType::VarKeyword => {
+                let property_doc = ctx.peek_doc();
                 let property = parse_variable(ctx)?;
+
+                if let Some(property_doc) = property_doc {
+                    property_docs.insert(property.name.clone(), property_doc);
+                }
+
                 properties.insert(property.name, property.value);
             }
             TokenType::LayoutKeyword => {
@@ -181,6 +255,10 @@ pub fn parse_widget(ctx: &mut ParseContext) -> NekoResult<Widget> {
         name,
         default_properties: properties,
         layout,
+        position: widget_position,
+        doc,
+        property_docs,
+        scope: None,
     }))
 }
 
@@ -192,12 +270,7 @@ pub fn parse_widget_layout(ctx: &mut ParseContext) -> NekoResult<WidgetLayout> {
     let widget_position = ctx.next_position().unwrap_or_default();
     let widget = ctx.expect_as_string(TokenType::Identifier)?;
 
-    if ctx.get_widget(&widget).is_none() {
-        return Err(NekoMaidParseError::UnknownWidget {
-            widget,
-            position: widget_position,
-        });
-    };
+    ctx.reference_widget(widget.clone(), widget_position);
 
     let mut layout = WidgetLayout {
         widget: widget.clone(),
@@ -213,21 +286,11 @@ pub fn parse_widget_layout(ctx: &mut ParseContext) -> NekoResult<WidgetLayout> {
         match next.token_type {
             TokenType::Identifier => {
                 let name_position = ctx.next_position().unwrap_or_default();
-                let property = parse_unresolved_property(ctx)?;
-
-                if !ctx
-                    .get_widget(&widget)
-                    .expect("Widget should exist as checked earlier")
-                    .has_property(&property.name)
-                {
-                    return Err(NekoMaidParseError::InvalidProperty {
-                        property: property.name,
-                        widget: widget.clone(),
-                        position: name_position,
-                    });
-                }
+                let mut property = parse_unresolved_property(ctx)?;
+                ctx.reference_property(widget.clone(), property.name.clone(), name_position);
+                resolve_asset_paths(ctx, &widget, &property.name, &mut property.value);
 
-                layout.properties.insert(property.name, property.value);
+                shorthand::insert_property(&mut layout.properties, property.name, property.value);
             }
             TokenType::ClassKeyword => {
                 let class = parse_class(ctx)?;
@@ -279,3 +342,64 @@ fn count_layout_outputs(layout: &WidgetLayout) -> u32 {
 
     count
 }
+
+/// Checks that no custom widget (directly or indirectly) contains itself within
+/// its own layout, which would otherwise expand forever when built.
+pub fn check_widget_recursion(widgets: &HashMap<String, Widget>) -> NekoResult<()> {
+    let mut finished = HashSet::new();
+
+    for widget in widgets.values() {
+        let Widget::Custom(custom) = widget else {
+            continue;
+        };
+
+        let mut stack = Vec::new();
+        visit_widget(widgets, custom, &mut stack, &mut finished)?;
+    }
+
+    Ok(())
+}
+
+/// Visits a custom widget and all custom widgets used within its layout,
+/// returning an error if a widget is reached that is already on the stack.
+fn visit_widget<'a>(
+    widgets: &'a HashMap<String, Widget>,
+    widget: &'a CustomWidget,
+    stack: &mut Vec<&'a str>,
+    finished: &mut HashSet<&'a str>,
+) -> NekoResult<()> {
+    if finished.contains(widget.name.as_str()) {
+        return Ok(());
+    }
+
+    if stack.contains(&widget.name.as_str()) {
+        let mut cycle: Vec<&str> = stack
+            .iter()
+            .skip_while(|name| **name != widget.name)
+            .copied()
+            .collect();
+        cycle.push(&widget.name);
+
+        return Err(NekoMaidParseError::RecursiveWidget {
+            widget: widget.name.clone(),
+            cycle: cycle.join(" -> "),
+            position: widget.position,
+        });
+    }
+
+    stack.push(&widget.name);
+
+    let mut layouts = vec![&widget.layout];
+    while let Some(layout) = layouts.pop() {
+        if let Some(Widget::Custom(child)) = widgets.get(&layout.widget) {
+            visit_widget(widgets, child, stack, finished)?;
+        }
+
+        layouts.extend(layout.children.iter());
+    }
+
+    stack.pop();
+    finished.insert(&widget.name);
+
+    Ok(())
+}
@@ -0,0 +1,231 @@
+//! Loads NekoMaid UI files directly from the file system, for use by tooling.
+//!
+//! Imports are resolved the same way as in the
+//! [`NekoMaidAssetLoader`](crate::asset::NekoMaidAssetLoader), relative to the
+//! directory of the importing file, so `import "widgets/button";` in
+//! `ui/menu.neko_ui` loads `ui/widgets/button.neko_ui`.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use crate::module::Module;
+use crate::native::NATIVE_WIDGETS;
+use crate::widget::NativeWidget;
+use crate::{NekoMaidParseError, NekoMaidParser};
+
+/// A set of NekoMaid UI files loaded from the file system.
+///
+/// Loaded modules are cached until the source code of any file changes.
+#[derive(Debug, Default)]
+pub struct Workspace {
+    /// Source code overriding the contents of files on disk, such as the
+    /// contents of unsaved documents in an editor.
+    sources: HashMap<PathBuf, String>,
+
+    /// The modules loaded so far, by normalized file path.
+    modules: HashMap<PathBuf, Result<Module, WorkspaceError>>,
+
+    /// Native widgets registered by the application, in addition to the
+    /// built-in native widgets.
+    native_widgets: Vec<NativeWidget>,
+
+    /// Properties accepted by every widget, such as the properties of class
+    /// markers registered by the application.
+    global_properties: Vec<String>,
+}
+
+impl Workspace {
+    /// Creates a new, empty workspace.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a native widget provided by the application, so that files
+    /// using it can be loaded.
+    pub fn register_native_widget(&mut self, widget: NativeWidget) {
+        self.native_widgets.retain(|w| w.name != widget.name);
+        self.native_widgets.push(widget);
+        self.modules.clear();
+    }
+
+    /// Registers a property accepted by every widget, such as a property of a
+    /// class marker registered by the application.
+    pub fn register_global_property(&mut self, property: impl Into<String>) {
+        self.global_properties.push(property.into());
+        self.modules.clear();
+    }
+
+    /// Returns the native widgets registered with
+    /// [`register_native_widget`](Self::register_native_widget).
+    pub fn native_widgets(&self) -> &[NativeWidget] {
+        &self.native_widgets
+    }
+
+    /// Overrides the source code of the file at the given path.
+    pub fn set_source(&mut self, path: &Path, code: String) {
+        self.sources.insert(normalize_path(path), code);
+        self.modules.clear();
+    }
+
+    /// Removes the source code override of the file at the given path, so
+    /// that the file is read from disk again.
+    pub fn remove_source(&mut self, path: &Path) {
+        self.sources.remove(&normalize_path(path));
+        self.modules.clear();
+    }
+
+    /// Clears all cached modules, so that files are read from disk again.
+    pub fn invalidate(&mut self) {
+        self.modules.clear();
+    }
+
+    /// Returns the source code of the file at the given path.
+    pub fn source(&self, path: &Path) -> Result<String, WorkspaceError> {
+        let path = normalize_path(path);
+
+        if let Some(code) = self.sources.get(&path) {
+            return Ok(code.clone());
+        }
+
+        fs::read_to_string(&path).map_err(|e| WorkspaceError::Io {
+            path,
+            message: e.to_string(),
+        })
+    }
+
+    /// Loads the module at the given path, along with all of its imports.
+    pub fn load(&mut self, path: &Path) -> Result<Module, WorkspaceError> {
+        let mut stack = Vec::new();
+        self.load_module(&normalize_path(path), &mut stack)
+    }
+
+    /// Loads the module at the given normalized path, using `stack` to detect
+    /// import cycles.
+    fn load_module(
+        &mut self,
+        path: &Path,
+        stack: &mut Vec<PathBuf>,
+    ) -> Result<Module, WorkspaceError> {
+        if let Some(result) = self.modules.get(path) {
+            return result.clone();
+        }
+
+        if stack.iter().any(|p| p == path) {
+            return Err(WorkspaceError::ImportCycle {
+                path: path.to_path_buf(),
+            });
+        }
+
+        stack.push(path.to_path_buf());
+        let result = self.parse_module(path, stack);
+        stack.pop();
+
+        self.modules.insert(path.to_path_buf(), result.clone());
+        result
+    }
+
+    /// Parses the module at the given normalized path, loading its imports.
+    fn parse_module(
+        &mut self,
+        path: &Path,
+        stack: &mut Vec<PathBuf>,
+    ) -> Result<Module, WorkspaceError> {
+        let parse_error = |error| WorkspaceError::Parse {
+            path: path.to_path_buf(),
+            error,
+        };
+
+        let code = self.source(path)?;
+        let mut parser = NekoMaidParser::tokenize(&code).map_err(parse_error)?;
+
+        for native in NATIVE_WIDGETS.iter().chain(&self.native_widgets) {
+            parser.register_native_widget(native.clone());
+        }
+
+        for property in &self.global_properties {
+            parser.register_global_property(property.clone());
+        }
+
+        for import in parser.predict_imports().clone() {
+            let import_path = resolve_import(path, &import);
+            let module =
+                self.load_module(&import_path, stack)
+                    .map_err(|error| WorkspaceError::Import {
+                        import: import.clone(),
+                        error: Box::new(error),
+                    })?;
+
+            parser.add_module(import, module);
+        }
+
+        parser.finish().map_err(parse_error)
+    }
+}
+
+/// Resolves the path of an import statement within the file at the given path.
+pub fn resolve_import(path: &Path, import: &str) -> PathBuf {
+    let directory = path.parent().unwrap_or(Path::new(""));
+    normalize_path(&directory.join(format!("{}.neko_ui", import)))
+}
+
+/// Normalizes the given path by resolving all `.` and `..` components, without
+/// accessing the file system.
+pub fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    normalized.push("..");
+                }
+            }
+            component => normalized.push(component),
+        }
+    }
+
+    normalized
+}
+
+/// Errors that can occur while loading a workspace module.
+#[derive(Debug, thiserror::Error, Clone, PartialEq)]
+pub enum WorkspaceError {
+    /// A file could not be read.
+    #[error("Failed to read {}: {message}", path.display())]
+    Io {
+        /// The path of the file.
+        path: PathBuf,
+
+        /// The error message.
+        message: String,
+    },
+
+    /// A file could not be parsed.
+    #[error("{}: {error}", path.display())]
+    Parse {
+        /// The path of the file.
+        path: PathBuf,
+
+        /// The parse error.
+        error: NekoMaidParseError,
+    },
+
+    /// A module imported by a file could not be loaded.
+    #[error("Failed to import '{import}': {error}")]
+    Import {
+        /// The name of the import, as written in the import statement.
+        import: String,
+
+        /// The error that occurred while loading the imported module.
+        error: Box<WorkspaceError>,
+    },
+
+    /// A file imports itself, either directly or through other files.
+    #[error("Import cycle detected at {}", path.display())]
+    ImportCycle {
+        /// The path of the file that imports itself.
+        path: PathBuf,
+    },
+}
@@ -1,22 +1,85 @@
 //! The NekoMaid style asset, and asset loader for NekoMaid ui files.
 
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock};
 use std::time::Instant;
 
-use bevy::asset::io::Reader;
-use bevy::asset::{AssetLoader, LoadContext, LoadDirectError};
+use bevy::asset::io::{Reader, Writer};
+use bevy::asset::saver::{AssetSaver, SavedAsset};
+use bevy::asset::{AssetLoader, AsyncWriteExt, LoadContext, LoadDirectError, UntypedAssetId};
 use bevy::prelude::*;
 
-use crate::native::NATIVE_WIDGETS;
+use crate::marker::MarkerRegistry;
+use crate::parse::compiled::CompiledModuleError;
 use crate::parse::module::Module;
+use crate::parse::native::{AssetKind, NATIVE_WIDGETS};
+use crate::parse::widget::NativeWidget;
 use crate::parse::{NekoMaidParseError, NekoMaidParser};
+use crate::widget::NativeWidgetRegistry;
 
 /// A NekoMaid UI asset.
 #[derive(Debug, Asset, TypePath, Deref)]
-pub struct NekoMaidUI(Module);
+pub struct NekoMaidUI {
+    /// The parsed module.
+    #[deref]
+    module: Module,
+
+    /// Handles to the images and fonts referenced by the module, which are
+    /// loaded as dependencies of this asset.
+    #[dependency]
+    assets: Vec<UntypedHandle>,
+}
+
+impl NekoMaidUI {
+    /// Creates a new NekoMaidUI asset from a module, and starts loading the
+    /// assets it references as dependencies.
+    fn new(module: Module, load_context: &mut LoadContext<'_>) -> Self {
+        let assets = module
+            .assets
+            .iter()
+            .map(|(path, kind)| match kind {
+                AssetKind::Image => load_context.load::<Image>(path).untyped(),
+                AssetKind::Font => load_context.load::<Font>(path).untyped(),
+            })
+            .collect();
+
+        Self { module, assets }
+    }
+
+    /// Returns whether the given asset is one of the images or fonts loaded as
+    /// dependencies of this asset.
+    pub(crate) fn depends_on(&self, id: UntypedAssetId) -> bool {
+        self.assets.iter().any(|asset| asset.id() == id)
+    }
+}
 
 /// The asset loader for NekoMaid ui files.
-#[derive(Debug, Default)]
-pub struct NekoMaidAssetLoader;
+///
+/// Files are parsed with the built-in native widgets, along with those in the
+/// [`NativeWidgetRegistry`] at the time the file is loaded. The properties of
+/// the markers in the [`MarkerRegistry`] are accepted by every widget.
+#[derive(Debug)]
+pub struct NekoMaidAssetLoader {
+    /// The native widgets registered from Rust code.
+    widgets: Arc<RwLock<HashMap<String, NativeWidget>>>,
+
+    /// The properties read by registered markers.
+    marker_properties: Arc<RwLock<HashSet<String>>>,
+}
+
+impl FromWorld for NekoMaidAssetLoader {
+    fn from_world(world: &mut World) -> Self {
+        let widgets = world
+            .get_resource_or_init::<NativeWidgetRegistry>()
+            .widgets();
+        let marker_properties = world.get_resource_or_init::<MarkerRegistry>().properties();
+        Self {
+            widgets,
+            marker_properties,
+        }
+    }
+}
+
 impl AssetLoader for NekoMaidAssetLoader {
     type Asset = NekoMaidUI;
     type Settings = ();
@@ -39,6 +102,30 @@ impl AssetLoader for NekoMaidAssetLoader {
         for native in NATIVE_WIDGETS.iter() {
             parser.register_native_widget(native.clone());
         }
+        for native in self
+            .widgets
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+        {
+            parser.register_native_widget(native.clone());
+        }
+        for property in self
+            .marker_properties
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+        {
+            parser.register_global_property(property.clone());
+        }
+
+        // Asset paths are relative to the directory of this file, or to the
+        // asset root if they start with `/`.
+        let base = load_context.asset_path().clone_owned();
+        parser.set_asset_path_resolver(Box::new(move |path| match base.resolve_embed(path) {
+            Ok(resolved) => resolved.to_string(),
+            Err(_) => path.to_string(),
+        }));
 
         for import in parser.predict_imports().clone() {
             let path = load_context.asset_path();
@@ -52,7 +139,7 @@ impl AssetLoader for NekoMaidAssetLoader {
                 .load::<NekoMaidUI>(&module_path)
                 .await?;
 
-            let module = asset.get().0.clone();
+            let module = asset.get().module.clone();
             parser.add_module(import.clone(), module);
         }
 
@@ -65,7 +152,7 @@ impl AssetLoader for NekoMaidAssetLoader {
             elapsed,
         );
 
-        Ok(NekoMaidUI(module))
+        Ok(NekoMaidUI::new(module, load_context))
     }
 
     fn extensions(&self) -> &[&str] {
@@ -73,6 +160,72 @@ impl AssetLoader for NekoMaidAssetLoader {
     }
 }
 
+/// The asset loader for precompiled NekoMaid ui files.
+///
+/// Compiled files are produced by [`NekoMaidUICompiler`], either through the
+/// Bevy asset processor or ahead of time, and load without tokenizing or
+/// parsing.
+#[derive(Debug, Default)]
+pub struct NekoMaidCompiledAssetLoader;
+impl AssetLoader for NekoMaidCompiledAssetLoader {
+    type Asset = NekoMaidUI;
+    type Settings = ();
+    type Error = NekoMaidAssetLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _: &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let now = Instant::now();
+
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        let module = Module::from_bytes(&bytes)?;
+
+        let elapsed = now.elapsed().as_millis();
+        debug!(
+            "Loaded compiled NekoMaid UI asset {} in {} ms.",
+            load_context.path().display(),
+            elapsed,
+        );
+
+        Ok(NekoMaidUI::new(module, load_context))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["neko_uic"]
+    }
+}
+
+/// An asset saver that writes NekoMaid ui assets in the precompiled binary
+/// format, to be loaded by [`NekoMaidCompiledAssetLoader`].
+///
+/// The NekoMaid plugin registers this as the default asset processor for
+/// `.neko_ui` files, so when Bevy's asset processor is enabled, UI files are
+/// compiled at build time.
+#[derive(Debug, Default)]
+pub struct NekoMaidUICompiler;
+impl AssetSaver for NekoMaidUICompiler {
+    type Asset = NekoMaidUI;
+    type Settings = ();
+    type OutputLoader = NekoMaidCompiledAssetLoader;
+    type Error = NekoMaidAssetLoaderError;
+
+    async fn save(
+        &self,
+        writer: &mut Writer,
+        asset: SavedAsset<'_, Self::Asset>,
+        _: &Self::Settings,
+    ) -> Result<(), Self::Error> {
+        let bytes = asset.to_bytes()?;
+        writer.write_all(&bytes).await?;
+        Ok(())
+    }
+}
+
 /// Errors that can occur while loading a NekoMaid asset.
 #[derive(Debug, thiserror::Error)]
 pub enum NekoMaidAssetLoaderError {
@@ -88,6 +241,10 @@ pub enum NekoMaidAssetLoaderError {
     #[error("Syntax error: {0}")]
     FailedToParse(#[from] NekoMaidParseError),
 
+    /// The compiled asset could not be encoded or decoded.
+    #[error("Invalid compiled module: {0}")]
+    InvalidCompiledModule(#[from] CompiledModuleError),
+
     /// An error occurred while loading a dependency.
     #[error("{0}")]
     FailedToLoadDependency(#[from] LoadDirectError),
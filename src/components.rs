@@ -1,6 +1,8 @@
 //! Components used for the NekoMaid plugin.
 
-use bevy::platform::collections::HashMap;
+use std::collections::HashMap;
+use std::time::Duration;
+
 use bevy::prelude::*;
 
 use crate::asset::NekoMaidUI;
@@ -18,6 +20,13 @@ pub struct NekoUITree {
 
     /// Variables that should be inserted into the global context.
     variables: HashMap<String, PropertyValue>,
+
+    /// The content of the asset to spawn as the root of this tree.
+    root: TreeRoot,
+
+    /// Whether to wait for the images and fonts referenced by the asset to
+    /// load before spawning the tree.
+    preload: bool,
 }
 
 impl NekoUITree {
@@ -27,6 +36,60 @@ impl NekoUITree {
             asset,
             variables: HashMap::new(),
             dirty: true,
+            root: TreeRoot::default(),
+            preload: true,
+        }
+    }
+
+    /// Spawns the layout with the given name instead of the unnamed layouts of
+    /// the asset.
+    pub fn with_layout(mut self, name: impl Into<String>) -> Self {
+        self.root = TreeRoot::Layout(name.into());
+        self
+    }
+
+    /// Spawns the widget with the given name and properties instead of the
+    /// unnamed layouts of the asset.
+    pub fn with_widget(
+        mut self,
+        name: impl Into<String>,
+        properties: HashMap<String, PropertyValue>,
+    ) -> Self {
+        self.root = TreeRoot::Widget {
+            name: name.into(),
+            properties,
+        };
+        self
+    }
+
+    /// Sets whether to wait for the images and fonts referenced by the asset to
+    /// load before spawning the tree. This is enabled by default; when
+    /// disabled, the tree is spawned as soon as the asset itself is loaded.
+    ///
+    /// This only controls when the tree is spawned. The images and fonts are
+    /// dependencies of the [`NekoMaidUI`] asset, so they are always loaded
+    /// along with it, and appear in a tree spawned early once they finish.
+    pub fn with_preload(mut self, preload: bool) -> Self {
+        self.preload = preload;
+        self
+    }
+
+    /// Returns whether the tree waits for the images and fonts referenced by
+    /// the asset to load before spawning.
+    pub fn preload(&self) -> bool {
+        self.preload
+    }
+
+    /// Returns a reference to the root content of this tree.
+    pub fn root(&self) -> &TreeRoot {
+        &self.root
+    }
+
+    /// Sets the root content of this tree, re-spawning it if it changed.
+    pub fn set_root(&mut self, root: TreeRoot) {
+        if self.root != root {
+            self.root = root;
+            self.mark_dirty();
         }
     }
 
@@ -67,3 +130,149 @@ impl NekoUITree {
         self.dirty
     }
 }
+
+/// The content of a NekoMaid UI asset that is spawned by a [`NekoUITree`].
+#[derive(Debug, Default, Clone, PartialEq)]
+pub enum TreeRoot {
+    /// Spawns all unnamed layouts of the asset.
+    #[default]
+    Layouts,
+
+    /// Spawns the named layout of the asset, i.e. `layout <name> <widget>`.
+    Layout(String),
+
+    /// Spawns a widget defined in the asset as the root of the tree.
+    Widget {
+        /// The name of the widget.
+        name: String,
+
+        /// The properties to pass to the widget.
+        properties: HashMap<String, PropertyValue>,
+    },
+}
+
+/// A component that truncates the text of a `p` widget with an ellipsis when
+/// it does not fit the width of its node.
+///
+/// This is inserted by `text-overflow: ellipsis`.
+#[derive(Debug, Clone, Component)]
+pub struct TextEllipsis {
+    /// The full text, before truncation.
+    text: String,
+
+    /// The content width of the node, in physical pixels, that the text was
+    /// last truncated for.
+    width: f32,
+}
+
+impl TextEllipsis {
+    /// Creates a new TextEllipsis for the given full text.
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            width: 0.0,
+        }
+    }
+
+    /// Returns the full text, before truncation.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns the content width that the text was last truncated for.
+    pub fn width(&self) -> f32 {
+        self.width
+    }
+
+    /// Sets the content width that the text is truncated for.
+    pub fn set_width(&mut self, width: f32) {
+        self.width = width;
+    }
+}
+
+/// A component that animates the [`TextureAtlas`] of an `img` widget by
+/// stepping through its frames at a fixed rate.
+///
+/// This is inserted by a `frame-rate` greater than zero.
+#[derive(Debug, Clone, Component)]
+pub struct AtlasAnimation {
+    /// The index of the first frame.
+    first: usize,
+
+    /// The number of frames, or zero to animate until the end of the atlas.
+    frames: usize,
+
+    /// The timer for advancing to the next frame.
+    timer: Timer,
+}
+
+impl AtlasAnimation {
+    /// Creates a new AtlasAnimation that starts at the given atlas index and
+    /// plays the given number of frames per second.
+    pub fn new(first: usize, frames: usize, frame_rate: f32) -> Self {
+        Self {
+            first,
+            frames,
+            timer: Timer::from_seconds(1.0 / frame_rate, TimerMode::Repeating),
+        }
+    }
+
+    /// Advances the animation timer, and returns the number of frames that
+    /// have elapsed.
+    pub fn tick(&mut self, delta: Duration) -> usize {
+        self.timer.tick(delta);
+        self.timer.times_finished_this_tick() as usize
+    }
+
+    /// Returns the atlas index that follows the given one, for an atlas with
+    /// the given number of frames.
+    pub fn next_index(&self, index: usize, len: usize) -> usize {
+        let last = match self.frames {
+            0 => len,
+            frames => (self.first + frames).min(len),
+        };
+
+        if index + 1 >= last || index < self.first {
+            self.first
+        } else {
+            index + 1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn atlas_animation_loops_over_its_frames() {
+        let animation = AtlasAnimation::new(2, 3, 10.0);
+        assert_eq!(animation.next_index(2, 8), 3);
+        assert_eq!(animation.next_index(3, 8), 4);
+        assert_eq!(animation.next_index(4, 8), 2);
+
+        // Indices outside the frames restart the animation.
+        assert_eq!(animation.next_index(0, 8), 2);
+        assert_eq!(animation.next_index(7, 8), 2);
+
+        // The frames are cut short by the end of the atlas.
+        assert_eq!(animation.next_index(3, 4), 2);
+    }
+
+    #[test]
+    fn atlas_animation_without_frames_plays_until_the_end() {
+        let animation = AtlasAnimation::new(1, 0, 10.0);
+        assert_eq!(animation.next_index(1, 4), 2);
+        assert_eq!(animation.next_index(2, 4), 3);
+        assert_eq!(animation.next_index(3, 4), 1);
+    }
+
+    #[test]
+    fn atlas_animation_counts_elapsed_frames() {
+        let mut animation = AtlasAnimation::new(0, 0, 4.0);
+        assert_eq!(animation.tick(Duration::from_millis(125)), 0);
+        assert_eq!(animation.tick(Duration::from_millis(125)), 1);
+        assert_eq!(animation.tick(Duration::from_millis(600)), 2);
+        assert_eq!(animation.tick(Duration::from_millis(150)), 1);
+    }
+}
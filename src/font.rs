@@ -0,0 +1,112 @@
+//! This module implements named fonts.
+//!
+//! Fonts can be given names either in NekoMaid UI files, using `font-face`
+//! declarations, or from Rust code using the [`FontRegistry`] resource.
+//!
+//! ```ignore
+//! app.register_font("body", "fonts/OpenDyslexic.ttf");
+//! ```
+//!
+//! Styles can then refer to the font by name, optionally followed by a list
+//! of fallbacks which are used when a name is unknown.
+//!
+//! ```text
+//! style p {
+//!     font: body, "fonts/Inter.ttf";
+//! }
+//! ```
+//!
+//! Fonts registered from Rust code take priority over `font-face`
+//! declarations, so changing the [`FontRegistry`] swaps the font family of
+//! all UI trees at once.
+
+use bevy::app::App;
+use bevy::ecs::resource::Resource;
+use bevy::log::warn;
+use bevy::platform::collections::HashMap;
+
+use crate::parse::module::Module;
+use crate::parse::value::PropertyValue;
+
+/// A resource for managing named fonts registered from Rust code.
+#[derive(Debug, Default, Clone, Resource)]
+pub struct FontRegistry {
+    /// Maps font names to font asset paths.
+    fonts: HashMap<String, String>,
+}
+
+impl FontRegistry {
+    /// Registers a named font. If a font with the same name already exists,
+    /// it is replaced.
+    pub fn register(&mut self, name: impl Into<String>, path: impl Into<String>) {
+        self.fonts.insert(name.into(), path.into());
+    }
+
+    /// Removes a named font, returning its asset path if it was registered.
+    pub fn unregister(&mut self, name: &str) -> Option<String> {
+        self.fonts.remove(name)
+    }
+
+    /// Returns the asset path of a named font, if it is registered.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.fonts.get(name).map(String::as_str)
+    }
+
+    /// Resolves the value of a `font` property into a font asset path, or
+    /// `"auto"` for the default font.
+    ///
+    /// Each font in a comma-separated list is tried in order. A font is
+    /// looked up in this registry first, then in the `font-face` declarations
+    /// of the module. Values that look like file paths are used as-is.
+    pub fn resolve(&self, font: &PropertyValue, module: &Module) -> String {
+        let fonts = match font {
+            PropertyValue::CommaList(fonts) => fonts.as_slice(),
+            font => std::slice::from_ref(font),
+        };
+
+        for font in fonts {
+            let PropertyValue::String(name) = font else {
+                continue;
+            };
+
+            if name == "auto" {
+                return name.clone();
+            }
+
+            if let Some(path) = self.get(name) {
+                return path.to_string();
+            }
+
+            if let Some(face) = module.fonts.get(name) {
+                return face.src.clone();
+            }
+
+            if name.contains('.') || name.contains('/') {
+                return name.clone();
+            }
+        }
+
+        warn!("Unknown font {}, using the default font", font);
+        "auto".to_string()
+    }
+}
+
+/// A trait to easily register named fonts.
+///
+/// ```ignore
+/// app.register_font("body", "fonts/Inter.ttf");
+/// ```
+pub trait FontAppExt {
+    /// Registers a named font with the [`FontRegistry`].
+    fn register_font(&mut self, name: impl Into<String>, path: impl Into<String>) -> &mut Self;
+}
+
+impl FontAppExt for App {
+    fn register_font(&mut self, name: impl Into<String>, path: impl Into<String>) -> &mut Self {
+        self.init_resource::<FontRegistry>()
+            .world_mut()
+            .resource_mut::<FontRegistry>()
+            .register(name, path);
+        self
+    }
+}
@@ -2,16 +2,44 @@
 #![warn(missing_docs)]
 #![warn(clippy::missing_docs_in_private_items)]
 
+use bevy::asset::processor::LoadTransformAndSave;
+use bevy::asset::transformer::IdentityAssetTransformer;
 use bevy::prelude::*;
 
-use crate::{asset::{NekoMaidAssetLoader, NekoMaidUI}, marker::MarkerRegistry};
+use crate::asset::{
+    NekoMaidAssetLoader,
+    NekoMaidCompiledAssetLoader,
+    NekoMaidUI,
+    NekoMaidUICompiler,
+};
+use crate::font::FontRegistry;
+use crate::marker::MarkerRegistry;
+use crate::native::AtlasLayouts;
+use crate::widget::NativeWidgetRegistry;
+
+/// The asset processor that compiles NekoMaid UI files into the precompiled
+/// binary format.
+type NekoMaidUIProcessor = LoadTransformAndSave<
+    NekoMaidAssetLoader,
+    IdentityAssetTransformer<NekoMaidUI>,
+    NekoMaidUICompiler,
+>;
 
 pub mod asset;
 pub mod components;
-pub mod native;
-pub mod parse;
+pub mod font;
+pub mod manifest;
 pub mod marker;
+pub mod native;
 mod systems;
+pub mod value;
+pub mod widget;
+
+pub use neko_parse as parse;
+
+// Lets the derive macros, which refer to `::neko_maid`, be used within this
+// crate.
+extern crate self as neko_maid;
 
 /// A Bevy UI plugin: NekoMaid
 ///
@@ -21,16 +49,31 @@ pub struct NekoMaidPlugin;
 impl Plugin for NekoMaidPlugin {
     fn build(&self, app_: &mut App) {
         app_.init_asset::<NekoMaidUI>()
+            .init_resource::<NativeWidgetRegistry>()
             .init_asset_loader::<NekoMaidAssetLoader>()
+            .init_asset_loader::<NekoMaidCompiledAssetLoader>()
+            .register_asset_processor::<NekoMaidUIProcessor>(NekoMaidUICompiler.into())
+            .set_default_asset_processor::<NekoMaidUIProcessor>("neko_ui")
             .init_resource::<MarkerRegistry>()
+            .init_resource::<FontRegistry>()
+            .init_resource::<AtlasLayouts>()
             .add_systems(
                 Update,
                 (
                     systems::spawn_tree.in_set(NekoMaidSystems::UpdateTree),
                     systems::update_tree.in_set(NekoMaidSystems::AssetListener),
                     systems::asset_failure.in_set(NekoMaidSystems::AssetListener),
+                    systems::dependency_failure.in_set(NekoMaidSystems::AssetListener),
+                    systems::animate_atlas,
+                    systems::update_fonts
+                        .run_if(resource_changed::<FontRegistry>)
+                        .in_set(NekoMaidSystems::AssetListener),
                 ),
             )
+            .add_systems(
+                PostUpdate,
+                systems::truncate_text.after(bevy::ui::UiSystems::PostLayout),
+            )
             .configure_sets(
                 Update,
                 NekoMaidSystems::AssetListener.before(NekoMaidSystems::UpdateTree),
@@ -0,0 +1,56 @@
+//! This module describes the native widgets and class markers registered with
+//! an app in a [`Manifest`].
+//!
+//! The command-line tools and the language server cannot discover the widgets
+//! and markers registered from Rust code, and read them from a manifest
+//! instead. Rather than writing the manifest by hand, it can be written from
+//! the app that registers them:
+//!
+//! ```ignore
+//! let mut app = App::new();
+//! app.add_native_widget::<HealthBar>().add_marker::<Slot>();
+//! std::fs::write(MANIFEST_FILE, app.manifest()?.to_json())?;
+//! ```
+
+use bevy::app::App;
+
+use crate::marker::MarkerRegistry;
+use crate::parse::manifest::{Manifest, ManifestError};
+use crate::widget::NativeWidgetRegistry;
+
+/// A trait to describe the widgets and markers registered with an app.
+pub trait ManifestAppExt {
+    /// Describes the native widgets registered from Rust code, along with the
+    /// registered class markers and the properties they read.
+    ///
+    /// Fails if a widget has a default value that cannot be written in a
+    /// manifest.
+    fn manifest(&self) -> Result<Manifest, ManifestError>;
+}
+
+impl ManifestAppExt for App {
+    fn manifest(&self) -> Result<Manifest, ManifestError> {
+        let world = self.world();
+        let mut manifest = Manifest::default();
+
+        if let Some(registry) = world.get_resource::<NativeWidgetRegistry>() {
+            let widgets = registry.widgets();
+            let widgets = widgets.read().unwrap_or_else(|e| e.into_inner());
+            for widget in widgets.values() {
+                manifest.add_native_widget(widget)?;
+            }
+        }
+
+        if let Some(registry) = world.get_resource::<MarkerRegistry>() {
+            let properties = registry.properties();
+            let properties = properties.read().unwrap_or_else(|e| e.into_inner());
+            manifest.properties = properties.iter().cloned().collect();
+            manifest.properties.sort();
+
+            manifest.markers = registry.ids().map(String::from).collect();
+            manifest.markers.sort();
+        }
+
+        Ok(manifest)
+    }
+}
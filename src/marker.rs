@@ -1,87 +1,184 @@
 //! This module implements the class marker functionality.
-//! 
+//!
 //! Class markers are components that are automatically attached to
-//! UI nodes that have the associated class. Given the `MyMarker` component defined like above,
-//! 
+//! UI nodes that have the associated class. Given the `MyMarker` component
+//! defined like above,
+//!
 //! ```
+//! # use bevy::prelude::*;
+//! # use neko_derive::NekoMarker;
+//! # use neko_maid::marker::MarkerAppExt;
 //! // define the marker component
-//! 
+//!
 //! #[derive(Component, NekoMarker)]
 //! #[neko_marker("my_marker")]
 //! pub struct MyMarker;
-//! 
+//!
 //! // register the marker type.
-//! 
+//!
+//! # let mut app = App::new();
 //! app.add_marker::<MyMarker>();
 //! ```
-//! 
-//! All layout nodes with the `my_marker` class will have the `MyMarker` component.
-//! 
-//! ```
+//!
+//! All layout nodes with the `my_marker` class will have the `MyMarker`
+//! component.
+//!
+//! ```text
 //! layout div {
 //!     class my_marker;
 //! }
 //! ```
 //!
+//! Markers can also carry data. Each field is filled from an element property,
+//! which every widget accepts once the marker is registered.
+//!
+//! ```
+//! # use bevy::prelude::*;
+//! # use neko_derive::NekoMarker;
+//! #[derive(Component, NekoMarker)]
+//! #[neko_marker("inventory_slot")]
+//! pub struct Slot {
+//!     #[neko(rename = "slot-index")]
+//!     index: u32,
+//! }
+//! ```
+//!
+//! ```text
+//! layout div {
+//!     class inventory_slot;
+//!     slot-index: 3;
+//! }
+//! ```
+
+use std::collections::HashSet;
+use std::sync::{Arc, RwLock};
 
-use bevy::{app::App, ecs::{bundle::Bundle, resource::Resource, system::EntityCommands}, platform::collections::HashMap};
+use bevy::app::App;
+use bevy::ecs::bundle::Bundle;
+use bevy::ecs::resource::Resource;
+use bevy::ecs::system::EntityCommands;
+use bevy::platform::collections::HashMap;
 
 use crate::parse::element::NekoElement;
 
-/// The marker trait. It can easily be implemented with derive.
-/// 
+/// The marker trait. It can easily be implemented with derive, where each
+/// field of the marker is filled from an element property.
+///
 /// ```
+/// # use bevy::prelude::*;
+/// # use neko_derive::NekoMarker;
 /// #[derive(Component, NekoMarker)]
 /// #[neko_marker("my_marker")]
-/// pub struct MyMarker;
+/// pub struct MyMarker {
+///     #[neko(rename = "my-value", default = 1)]
+///     value: u32,
+/// }
 /// ```
+///
+/// Markers that do not read any properties only need to implement [`new`]
+/// and [`id`].
+///
+/// [`new`]: NekoMarker::new
+/// [`id`]: NekoMarker::id
 pub trait NekoMarker: 'static {
-    ///
-    fn new() -> Self where Self: Sized;
+    /// Creates the marker without reading any element properties.
+    fn new() -> Self
+    where
+        Self: Sized;
+    /// Creates the marker from the properties of an element. Defaults to
+    /// [`new`](NekoMarker::new).
+    fn from_element(element: &NekoElement) -> Self
+    where
+        Self: Sized,
+    {
+        let _ = element;
+        Self::new()
+    }
     /// Return the marker id
-    fn id() -> &'static str where Self: Sized;
+    fn id() -> &'static str
+    where
+        Self: Sized;
+    /// Returns the names of the element properties the marker is created from.
+    fn properties() -> &'static [&'static str]
+    where
+        Self: Sized,
+    {
+        &[]
+    }
 }
 
 /// The marker factory.
-pub type MarkerFactory = Box<dyn Fn(&mut EntityCommands) + Send + Sync>;
+pub type MarkerFactory = Box<dyn Fn(&mut EntityCommands, &NekoElement) + Send + Sync>;
 
 /// A resource for managing registered marker types.
 #[derive(Default, Resource)]
 pub struct MarkerRegistry {
     /// Maps marker names to marker factories.
     factories: HashMap<String, MarkerFactory>,
+
+    /// The properties read by the registered markers, shared with the asset
+    /// loader so that every widget accepts them.
+    properties: Arc<RwLock<HashSet<String>>>,
 }
 impl MarkerRegistry {
     /// Inserts the marker component to an entity given its element.
     pub fn insert(&self, mut entity: EntityCommands, element: &NekoElement) {
         for class in element.classes() {
-            let Some(f) = self.factories.get(class) else { continue };
-            f(&mut entity);
+            let Some(f) = self.factories.get(class) else {
+                continue;
+            };
+            f(&mut entity, element);
         }
     }
+
+    /// Returns the names of the registered markers.
+    pub(crate) fn ids(&self) -> impl Iterator<Item = &str> {
+        self.factories.keys().map(String::as_str)
+    }
+
+    /// Returns a handle to the properties read by the registered markers,
+    /// which stays up to date as more markers are registered.
+    pub(crate) fn properties(&self) -> Arc<RwLock<HashSet<String>>> {
+        self.properties.clone()
+    }
 }
 
 /// A trait to easily register types that implement the [NekoMarker] trait.
-/// 
+///
 /// ```
+/// # use bevy::prelude::*;
+/// # use neko_derive::NekoMarker;
+/// # use neko_maid::marker::MarkerAppExt;
+/// # #[derive(Component, NekoMarker)]
+/// # #[neko_marker("my_marker")]
+/// # pub struct MyMarker;
+/// # let mut app = App::new();
 /// app.add_marker::<MyMarker>();
 /// ```
 pub trait MarkerAppExt {
-    ///
+    /// Registers a marker type with the [MarkerRegistry].
     fn add_marker<T: NekoMarker + Bundle>(&mut self) -> &mut Self;
 }
 
 impl MarkerAppExt for App {
     fn add_marker<T: NekoMarker + Bundle>(&mut self) -> &mut Self {
-        self.init_resource::<MarkerRegistry>()
+        let mut registry = self
+            .init_resource::<MarkerRegistry>()
             .world_mut()
-            .resource_mut::<MarkerRegistry>()
-            .factories
-            .insert(
-                T::id().to_owned(),
-                Box::new(|entity| { entity.insert(T::new()); } ),
-            );
+            .resource_mut::<MarkerRegistry>();
+
+        registry
+            .properties
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .extend(T::properties().iter().map(|p| p.to_string()));
+        registry.factories.insert(
+            T::id().to_owned(),
+            Box::new(|entity, element| {
+                entity.insert(T::from_element(element));
+            }),
+        );
 
         self
     }
-}
\ No newline at end of file
+}
@@ -1,223 +1,34 @@
-//! Defines the native widgets available in NekoMaid UI.
+//! Spawns the native widgets available in NekoMaid UI as Bevy entities.
+//!
+//! The widget definitions and their default properties live in
+//! [`crate::parse::native`].
 
-use std::sync::Arc;
+use std::collections::HashMap;
 
-use bevy::platform::collections::HashMap;
 use bevy::prelude::*;
-use lazy_static::lazy_static;
 
+use crate::components::{AtlasAnimation, TextEllipsis};
 use crate::parse::element::NekoElement;
+use crate::parse::markup::{MarkupSpan, parse_markup};
 use crate::parse::value::PropertyValue;
-use crate::parse::widget::NativeWidget;
-
-lazy_static! {
-    /// The list of native widgets available in NekoMaid UI.
-    pub static ref NATIVE_WIDGETS: Vec<NativeWidget> = vec![
-        NativeWidget {
-            name: String::from("div"),
-            default_properties: {
-                let mut m = HashMap::new();
-                node_properties(&mut m);
-                background_color_properties(&mut m);
-                border_color_properties(&mut m);
-                border_radius_properties(&mut m);
-                Arc::new(m)
-            },
-            spawn_func: spawn_div,
-        },
-        NativeWidget {
-            name: String::from("img"),
-            default_properties: {
-                let mut m = HashMap::new();
-                node_properties(&mut m);
-                background_color_properties(&mut m);
-                border_color_properties(&mut m);
-                border_radius_properties(&mut m);
-                image_properties(&mut m);
-                Arc::new(m)
-            },
-            spawn_func: spawn_img,
-        },
-        NativeWidget {
-            name: String::from("p"),
-            default_properties: {
-                let mut m = HashMap::new();
-                node_properties(&mut m);
-                background_color_properties(&mut m);
-                border_color_properties(&mut m);
-                border_radius_properties(&mut m);
-                text_properties(&mut m);
-                Arc::new(m)
-            },
-            spawn_func: spawn_p,
-        },
-        NativeWidget {
-            name: String::from("span"),
-            default_properties: {
-                let mut m = HashMap::new();
-                node_properties(&mut m);
-                background_color_properties(&mut m);
-                border_color_properties(&mut m);
-                border_radius_properties(&mut m);
-                text_span_properties(&mut m);
-                Arc::new(m)
-            },
-            spawn_func: spawn_span,
-        }
-    ];
-}
-
-/// Inserts the default properties for a [`Node`] into the given map.
-fn node_properties(m: &mut HashMap<String, PropertyValue>) {
-    m.insert("display".into(), "flex".into());
-    m.insert("box-sizing".into(), "border-box".into());
-    m.insert("position-type".into(), "relative".into());
-
-    m.insert("overflow-x".into(), "visible".into());
-    m.insert("overflow-y".into(), "visible".into());
-    m.insert("scrollbar-width".into(), 0.into());
-
-    m.insert("overflow-clip-margin-box".into(), "padding-box".into());
-    m.insert("overflow-clip-margin".into(), 0.into());
-
-    m.insert("left".into(), "auto".into());
-    m.insert("top".into(), "auto".into());
-    m.insert("right".into(), "auto".into());
-    m.insert("bottom".into(), "auto".into());
-
-    m.insert("width".into(), "auto".into());
-    m.insert("height".into(), "auto".into());
-    m.insert("min-width".into(), "auto".into());
-    m.insert("min-height".into(), "auto".into());
-    m.insert("max-width".into(), "auto".into());
-    m.insert("max-height".into(), "auto".into());
-    m.insert("aspect-ratio".into(), "none".into());
-
-    m.insert("align-items".into(), "default".into());
-    m.insert("justify-items".into(), "default".into());
-    m.insert("align-self".into(), "auto".into());
-    m.insert("justify-self".into(), "auto".into());
-    m.insert("align-content".into(), "default".into());
-    m.insert("justify-content".into(), "default".into());
-
-    m.insert("margin".into(), 0.into());
-    m.insert("margin-top".into(), 0.into());
-    m.insert("margin-left".into(), 0.into());
-    m.insert("margin-right".into(), 0.into());
-    m.insert("margin-bottom".into(), 0.into());
-
-    m.insert("padding".into(), 0.into());
-    m.insert("padding-top".into(), 0.into());
-    m.insert("padding-left".into(), 0.into());
-    m.insert("padding-right".into(), 0.into());
-    m.insert("padding-bottom".into(), 0.into());
-
-    m.insert("border-thickness".into(), 0.into());
-    m.insert("border-thickness-top".into(), 0.into());
-    m.insert("border-thickness-left".into(), 0.into());
-    m.insert("border-thickness-right".into(), 0.into());
-    m.insert("border-thickness-bottom".into(), 0.into());
-
-    m.insert("flex-direction".into(), "row".into());
-    m.insert("flex-wrap".into(), "nowrap".into());
-    m.insert("flex-grow".into(), 0.into());
-    m.insert("flex-shrink".into(), 1.into());
-    m.insert("flex-basis".into(), "auto".into());
-
-    m.insert("row-gap".into(), 0.into());
-    m.insert("column-gap".into(), 0.into());
-
-    m.insert("grid-auto-flow".into(), "row".into());
-    // m.insert("grid-template-rows".into(), "none".into());
-    // m.insert("grid-template-columns".into(), "none".into());
-    // m.insert("grid-auto-rows".into(), "auto".into());
-    // m.insert("grid-auto-columns".into(), "auto".into());
-    // m.insert("grid-row".into(), "auto".into());
-    // m.insert("grid-column".into(), "auto".into());
-}
-
-/// Inserts the default properties for a [`BackgroundColor`] into the given map.
-fn background_color_properties(m: &mut HashMap<String, PropertyValue>) {
-    m.insert("background-color".into(), Color::NONE.into());
-}
-
-/// Inserts the default properties for a [`BorderColor`] into the given map.
-fn border_color_properties(m: &mut HashMap<String, PropertyValue>) {
-    m.insert("border-color".into(), Color::NONE.into());
-    m.insert("border-color-top".into(), Color::NONE.into());
-    m.insert("border-color-left".into(), Color::NONE.into());
-    m.insert("border-color-right".into(), Color::NONE.into());
-    m.insert("border-color-bottom".into(), Color::NONE.into());
-}
-
-/// Inserts the default properties for a [`BorderRadius`] into the given map.
-fn border_radius_properties(m: &mut HashMap<String, PropertyValue>) {
-    m.insert("border-radius".into(), 0.into());
-    m.insert("border-radius-top-left".into(), 0.into());
-    m.insert("border-radius-top-right".into(), 0.into());
-    m.insert("border-radius-bottom-left".into(), 0.into());
-    m.insert("border-radius-bottom-right".into(), 0.into());
-}
-
-/// Inserts the default properties for an [`ImageNode`] into the given map.
-fn image_properties(m: &mut HashMap<String, PropertyValue>) {
-    m.insert("src".into(), "".into());
-    m.insert("tint".into(), Color::WHITE.into());
-    m.insert("flip-x".into(), false.into());
-    m.insert("flip-y".into(), false.into());
-    m.insert("mode".into(), "auto".into());
-
-    // slice mode properties
-    m.insert("slice-size".into(), 0.into());
-    m.insert("slice-size-top".into(), 0.into());
-    m.insert("slice-size-right".into(), 0.into());
-    m.insert("slice-size-bottom".into(), 0.into());
-    m.insert("slice-size-left".into(), 0.into());
-
-    m.insert("max-corner-scale".into(), 1.into());
-    m.insert("center-scale-mode".into(), "stretch".into());
-    m.insert("center-scale-stretch".into(), 1.into());
-    m.insert("sides-scale-mode".into(), "stretch".into());
-    m.insert("sides-scale-stretch".into(), 1.into());
-
-    // tile mode properties
-    m.insert("tile-x".into(), true.into());
-    m.insert("tile-y".into(), true.into());
-    m.insert("stretch-value".into(), 1.into());
-}
-
-/// Inserts the default properties for a [`Text`] bundle into the given map.
-fn text_properties(m: &mut HashMap<String, PropertyValue>) {
-    // Text
-    m.insert("text".into(), "".into());
-
-    // TextFont
-    m.insert("font".into(), "auto".into());
-    m.insert("font-size".into(), 16.into());
-    m.insert("line-height".into(), PropertyValue::Percent(120.0));
-    m.insert("font-smoothing".into(), "antialiased".into());
-
-    // TextLayout
-    m.insert("justify".into(), "left".into());
-    m.insert("line-break".into(), "word".into());
-
-    // TextColor
-    m.insert("color".into(), Color::WHITE.into());
-}
-
-/// Inserts the default properties for a [`TextSpan`] bundle into the given map.
-fn text_span_properties(m: &mut HashMap<String, PropertyValue>) {
-    // TextSpan
-    m.insert("text".into(), "".into());
-
-    // TextFont
-    m.insert("font".into(), "auto".into());
-    m.insert("font-size".into(), 16.into());
-    m.insert("line-height".into(), PropertyValue::Percent(120.0));
-    m.insert("font-smoothing".into(), "antialiased".into());
-
-    // TextColor
-    m.insert("color".into(), Color::WHITE.into());
+use crate::value::{AtlasGrid, FromPropertyValue, NekoElementExt};
+
+/// The function used to spawn a native widget.
+///
+/// This function takes a mutable reference to `Commands` and the parent
+/// entity, and returns the spawned widget entity.
+pub type SpawnFunc = fn(&Res<AssetServer>, &mut Commands, &NekoElement, Entity) -> Entity;
+
+/// Returns the function used to spawn the native widget with the given name,
+/// if there is one.
+pub fn spawn_func(name: &str) -> Option<SpawnFunc> {
+    match name {
+        "div" => Some(spawn_div),
+        "img" => Some(spawn_img),
+        "p" => Some(spawn_p),
+        "span" => Some(spawn_span),
+        _ => None,
+    }
 }
 
 /// Spawns a `div` native widget.
@@ -227,15 +38,21 @@ fn spawn_div(
     element: &NekoElement,
     parent: Entity,
 ) -> Entity {
-    commands
-        .spawn((
-            ChildOf(parent),
-            node_bundle(element),
-            background_color_bundle(element),
-            border_color_bundle(element),
-            border_radius_bundle(element),
-        ))
-        .id()
+    let mut entity = commands.spawn((
+        ChildOf(parent),
+        node_bundle(element),
+        background_color_bundle(element),
+        background_gradient_bundle(element),
+        border_color_bundle(element),
+        border_gradient_bundle(element),
+        border_radius_bundle(element),
+        box_shadow_bundle(element),
+        outline_bundle(element),
+        z_index_bundle(element),
+        ui_transform_bundle(element),
+    ));
+    insert_global_z_index(&mut entity, element);
+    entity.id()
 }
 
 /// Spawns an `img` native widget.
@@ -245,16 +62,24 @@ fn spawn_img(
     element: &NekoElement,
     parent: Entity,
 ) -> Entity {
-    commands
-        .spawn((
-            ChildOf(parent),
-            node_bundle(element),
-            background_color_bundle(element),
-            border_color_bundle(element),
-            border_radius_bundle(element),
-            image_node_bundle(asset_server, element),
-        ))
-        .id()
+    let mut entity = commands.spawn((
+        ChildOf(parent),
+        node_bundle(element),
+        background_color_bundle(element),
+        background_gradient_bundle(element),
+        border_color_bundle(element),
+        border_gradient_bundle(element),
+        border_radius_bundle(element),
+        box_shadow_bundle(element),
+        outline_bundle(element),
+        z_index_bundle(element),
+        ui_transform_bundle(element),
+        image_node_bundle(asset_server, element),
+    ));
+    insert_global_z_index(&mut entity, element);
+    insert_texture_atlas(&mut entity, element);
+    insert_atlas_animation(&mut entity, element);
+    entity.id()
 }
 
 /// Spawns an `p` native widget.
@@ -264,16 +89,37 @@ fn spawn_p(
     element: &NekoElement,
     parent: Entity,
 ) -> Entity {
-    commands
-        .spawn((
-            ChildOf(parent),
-            node_bundle(element),
-            background_color_bundle(element),
-            border_color_bundle(element),
-            border_radius_bundle(element),
-            text_node_bundle(asset_server, element),
-        ))
-        .id()
+    // Text with markup is spawned as spans, leaving the text itself empty.
+    let markup = parse_markup(&element.get_as::<String>("text"));
+    let plain = markup.iter().all(|span| span.style.is_plain());
+    let text = match markup.first() {
+        Some(span) if plain => transform_text(element, &span.text),
+        _ => String::new(),
+    };
+
+    let mut entity = commands.spawn((
+        ChildOf(parent),
+        node_bundle(element),
+        background_color_bundle(element),
+        background_gradient_bundle(element),
+        border_color_bundle(element),
+        border_gradient_bundle(element),
+        border_radius_bundle(element),
+        box_shadow_bundle(element),
+        outline_bundle(element),
+        z_index_bundle(element),
+        ui_transform_bundle(element),
+        text_node_bundle(asset_server, element, text.clone()),
+    ));
+    insert_global_z_index(&mut entity, element);
+    insert_text_shadow(&mut entity, element);
+    insert_text_ellipsis(&mut entity, element, plain.then_some(text));
+
+    let entity = entity.id();
+    if !plain {
+        spawn_markup(asset_server, commands, element, entity, &markup);
+    }
+    entity
 }
 
 /// Spawns an `span` native widget.
@@ -283,16 +129,22 @@ fn spawn_span(
     element: &NekoElement,
     parent: Entity,
 ) -> Entity {
-    commands
-        .spawn((
-            ChildOf(parent),
-            node_bundle(element),
-            background_color_bundle(element),
-            border_color_bundle(element),
-            border_radius_bundle(element),
-            span_node_bundle(asset_server, element),
-        ))
-        .id()
+    let mut entity = commands.spawn((
+        ChildOf(parent),
+        node_bundle(element),
+        background_color_bundle(element),
+        background_gradient_bundle(element),
+        border_color_bundle(element),
+        border_gradient_bundle(element),
+        border_radius_bundle(element),
+        box_shadow_bundle(element),
+        outline_bundle(element),
+        z_index_bundle(element),
+        ui_transform_bundle(element),
+        span_node_bundle(asset_server, element),
+    ));
+    insert_global_z_index(&mut entity, element);
+    entity.id()
 }
 
 /// Build [`Node`] bundle
@@ -367,13 +219,12 @@ fn node_bundle(element: &NekoElement) -> Node {
         column_gap: element.get_as("column-gap"),
 
         grid_auto_flow: element.get_as("grid-auto-flow"),
-        // grid_template_rows: element.get_as("grid-template-rows"),
-        // grid_template_columns: element.get_as("grid-template-columns"),
-        // grid_auto_rows: element.get_as("grid-auto-rows"),
-        // grid_auto_columns: element.get_as("grid-auto-columns"),
-        // grid_row: element.get_as("grid-row"),
-        // grid_column: element.get_as("grid-column"),
-        ..default()
+        grid_template_rows: element.get_as("grid-template-rows"),
+        grid_template_columns: element.get_as("grid-template-columns"),
+        grid_auto_rows: element.get_as("grid-auto-rows"),
+        grid_auto_columns: element.get_as("grid-auto-columns"),
+        grid_row: element.get_as("grid-row"),
+        grid_column: element.get_as("grid-column"),
     }
 }
 
@@ -394,6 +245,16 @@ fn background_color_bundle(element: &NekoElement) -> BackgroundColor {
     BackgroundColor(element.get_as("background-color"))
 }
 
+/// Build [`BackgroundGradient`] bundle
+fn background_gradient_bundle(element: &NekoElement) -> BackgroundGradient {
+    element.get_as("background")
+}
+
+/// Build [`BorderGradient`] bundle
+fn border_gradient_bundle(element: &NekoElement) -> BorderGradient {
+    element.get_as("border-gradient")
+}
+
 /// Build [`BorderRadius`] bundle
 fn border_radius_bundle(element: &NekoElement) -> BorderRadius {
     let border_radius = element.get_as("border-radius");
@@ -406,6 +267,295 @@ fn border_radius_bundle(element: &NekoElement) -> BorderRadius {
     }
 }
 
+/// Build [`BoxShadow`] bundle
+fn box_shadow_bundle(element: &NekoElement) -> BoxShadow {
+    element.get_as("box-shadow")
+}
+
+/// Build [`Outline`] bundle
+fn outline_bundle(element: &NekoElement) -> Outline {
+    Outline {
+        width: element.get_as("outline-width"),
+        offset: element.get_as("outline-offset"),
+        color: element.get_as("outline-color"),
+    }
+}
+
+/// Build [`ZIndex`] bundle
+fn z_index_bundle(element: &NekoElement) -> ZIndex {
+    ZIndex(element.get_as("z-index"))
+}
+
+/// Build [`UiTransform`] bundle
+fn ui_transform_bundle(element: &NekoElement) -> UiTransform {
+    UiTransform {
+        translation: element.get_as("translate"),
+        scale: element.get_as_or("scale", Vec2::ONE),
+        rotation: element.get_as("rotate"),
+    }
+}
+
+/// Inserts a [`GlobalZIndex`] if the element sets one. This is not part of the
+/// spawned bundle, since the presence of the component alone changes how the
+/// node is stacked.
+fn insert_global_z_index(entity: &mut EntityCommands, element: &NekoElement) {
+    match element.get_property("global-z-index") {
+        Some(PropertyValue::String(s)) if s == "none" => {}
+        Some(property) => {
+            entity.insert(GlobalZIndex(i32::from_property_value(property)));
+        }
+        None => {}
+    }
+}
+
+/// The texture atlas layouts used by `img` widgets, by grid.
+///
+/// Widgets with the same grid share a layout, so that respawning a tree, such
+/// as after a hot reload, does not add a new layout for every image.
+#[derive(Default, Resource)]
+pub(crate) struct AtlasLayouts(HashMap<AtlasGrid, Handle<TextureAtlasLayout>>);
+
+impl AtlasLayouts {
+    /// Returns the layout with the given grid, adding it to the given assets
+    /// if it has not been used before.
+    fn get_or_add(
+        &mut self,
+        grid: AtlasGrid,
+        layouts: &mut Assets<TextureAtlasLayout>,
+    ) -> Handle<TextureAtlasLayout> {
+        self.0
+            .entry(grid)
+            .or_insert_with(|| layouts.add(grid.layout()))
+            .clone()
+    }
+}
+
+/// Sets the texture atlas of an `img` widget if the element sets an
+/// `atlas-layout`, using the layout shared by all widgets with its grid.
+fn insert_texture_atlas(entity: &mut EntityCommands, element: &NekoElement) {
+    let grid = match element.get_property("atlas-layout") {
+        Some(PropertyValue::String(s)) if s == "none" => return,
+        Some(layout) => AtlasGrid::from_property_value(layout),
+        None => return,
+    };
+    let index = element.get_as("atlas-index");
+
+    entity.queue(move |mut entity: EntityWorldMut| {
+        let layout = entity.world_scope(|world| {
+            world.resource_scope(|world, mut cache: Mut<AtlasLayouts>| {
+                cache.get_or_add(grid, &mut world.resource_mut())
+            })
+        });
+
+        if let Some(mut image) = entity.get_mut::<ImageNode>() {
+            image.texture_atlas = Some(TextureAtlas { layout, index });
+        }
+    });
+}
+
+/// Inserts an [`AtlasAnimation`] if the element sets a `frame-rate`.
+fn insert_atlas_animation(entity: &mut EntityCommands, element: &NekoElement) {
+    let frame_rate: f32 = element.get_as("frame-rate");
+    if frame_rate > 0.0 {
+        entity.insert(AtlasAnimation::new(
+            element.get_as("atlas-index"),
+            element.get_as("atlas-frames"),
+            frame_rate,
+        ));
+    }
+}
+
+/// Inserts a [`TextShadow`] if the element sets one.
+fn insert_text_shadow(entity: &mut EntityCommands, element: &NekoElement) {
+    match element.get_property("text-shadow") {
+        Some(PropertyValue::String(s)) if s == "none" => {}
+        Some(property) => {
+            entity.insert(TextShadow::from_property_value(property));
+        }
+        None => {}
+    }
+}
+
+/// Inserts a [`TextEllipsis`] if the element sets `text-overflow: ellipsis`.
+///
+/// The text is kept on a single line and its node is clipped horizontally, so
+/// that the node is sized by its parent rather than by the text. Text with
+/// markup, given as `None`, can't be truncated and is only clipped.
+fn insert_text_ellipsis(entity: &mut EntityCommands, element: &NekoElement, text: Option<String>) {
+    match element.get_as::<String>("text-overflow").as_str() {
+        "clip" => {}
+        "ellipsis" => {
+            match text {
+                Some(text) => {
+                    entity.insert(TextEllipsis::new(text));
+                }
+                None => warn!("text-overflow: ellipsis is not supported on text with markup"),
+            }
+            entity
+                .entry::<TextLayout>()
+                .and_modify(|mut layout| layout.linebreak = LineBreak::NoWrap);
+            entity
+                .entry::<Node>()
+                .and_modify(|mut node| node.overflow.x = OverflowAxis::Clip);
+        }
+        overflow => warn!("Unknown text-overflow {}", overflow),
+    }
+}
+
+/// Returns the given text with the `text-transform` of an element applied.
+fn transform_text(element: &NekoElement, text: &str) -> String {
+    match element.get_as::<String>("text-transform").as_str() {
+        "none" => text.to_string(),
+        "uppercase" => text.to_uppercase(),
+        "lowercase" => text.to_lowercase(),
+        "capitalize" => {
+            let mut capitalized = String::with_capacity(text.len());
+            let mut word_start = true;
+            for c in text.chars() {
+                if word_start {
+                    capitalized.extend(c.to_uppercase());
+                } else {
+                    capitalized.push(c);
+                }
+                word_start = c.is_whitespace();
+            }
+            capitalized
+        }
+        transform => {
+            warn!("Unknown text-transform {}", transform);
+            text.to_string()
+        }
+    }
+}
+
+/// Spawns the spans of text with markup as [`TextSpan`] children of the given
+/// `p` entity. Anything not set by the markup is taken from the `p` element.
+fn spawn_markup(
+    asset_server: &Res<AssetServer>,
+    commands: &mut Commands,
+    element: &NekoElement,
+    parent: Entity,
+    markup: &[MarkupSpan],
+) {
+    for span in markup {
+        let mut span_element = element.clone();
+        span_element.set_property("text".into(), span.text.clone().into());
+
+        if let Some(color) = span.style.color {
+            span_element.set_property("color".into(), color.into());
+        }
+        if let Some(size) = span.style.size {
+            span_element.set_property("font-size".into(), size.into());
+        }
+
+        let font = match (span.style.bold, span.style.italic) {
+            (true, _) => element.get_property("font-bold"),
+            (false, true) => element.get_property("font-italic"),
+            (false, false) => None,
+        };
+        if let Some(PropertyValue::String(font)) = font
+            && font != "none"
+        {
+            span_element.set_property("font".into(), font.into());
+        }
+
+        commands.spawn((
+            ChildOf(parent),
+            span_node_bundle(asset_server, &span_element),
+        ));
+    }
+}
+
+/// Returns the opacity of an element, clamped to `0.0..=1.0` and multiplied by
+/// the opacity of its parent.
+pub(crate) fn element_opacity(element: &NekoElement, parent: f32) -> f32 {
+    parent * element.get_as_or("opacity", 1.0f32).clamp(0.0, 1.0)
+}
+
+/// Multiplies the alpha of all colors of a spawned native widget by the given
+/// opacity.
+///
+/// This is applied by the spawning system rather than the spawn functions,
+/// since the opacity of an element also applies to its descendants.
+pub(crate) fn apply_opacity(mut entity: EntityWorldMut, opacity: f32) {
+    if let Some(mut background) = entity.get_mut::<BackgroundColor>() {
+        fade(&mut background.0, opacity);
+    }
+    if let Some(mut border) = entity.get_mut::<BorderColor>() {
+        fade(&mut border.top, opacity);
+        fade(&mut border.left, opacity);
+        fade(&mut border.right, opacity);
+        fade(&mut border.bottom, opacity);
+    }
+    if let Some(mut gradient) = entity.get_mut::<BackgroundGradient>() {
+        fade_gradients(&mut gradient.0, opacity);
+    }
+    if let Some(mut gradient) = entity.get_mut::<BorderGradient>() {
+        fade_gradients(&mut gradient.0, opacity);
+    }
+    if let Some(mut shadow) = entity.get_mut::<BoxShadow>() {
+        for style in &mut shadow.0 {
+            fade(&mut style.color, opacity);
+        }
+    }
+    if let Some(mut outline) = entity.get_mut::<Outline>() {
+        fade(&mut outline.color, opacity);
+    }
+    if let Some(mut image) = entity.get_mut::<ImageNode>() {
+        fade(&mut image.color, opacity);
+    }
+    if let Some(mut text) = entity.get_mut::<TextColor>() {
+        fade(&mut text.0, opacity);
+    }
+    if let Some(mut shadow) = entity.get_mut::<TextShadow>() {
+        fade(&mut shadow.color, opacity);
+    }
+
+    // The spans spawned from markup are the only children of a text entity
+    // at this point, since the children of the element are spawned later.
+    if entity.contains::<Text>()
+        && let Some(children) = entity.get::<Children>()
+    {
+        let spans: Vec<Entity> = children.iter().collect();
+        entity.world_scope(|world| {
+            for span in spans {
+                if let Some(mut text) = world.get_mut::<TextColor>(span) {
+                    fade(&mut text.0, opacity);
+                }
+            }
+        });
+    }
+}
+
+/// Multiplies the alpha of the given color by the given opacity.
+fn fade(color: &mut Color, opacity: f32) {
+    color.set_alpha(color.alpha() * opacity);
+}
+
+/// Multiplies the alpha of all color stops of the given gradients by the given
+/// opacity.
+fn fade_gradients(gradients: &mut [Gradient], opacity: f32) {
+    for gradient in gradients {
+        match gradient {
+            Gradient::Linear(linear) => {
+                for stop in &mut linear.stops {
+                    fade(&mut stop.color, opacity);
+                }
+            }
+            Gradient::Radial(radial) => {
+                for stop in &mut radial.stops {
+                    fade(&mut stop.color, opacity);
+                }
+            }
+            Gradient::Conic(conic) => {
+                for stop in &mut conic.stops {
+                    fade(&mut stop.color, opacity);
+                }
+            }
+        }
+    }
+}
+
 /// Build [`ImageNode`] bundle
 fn image_node_bundle(asset_server: &Res<AssetServer>, element: &NekoElement) -> ImageNode {
     let src: String = element.get_as("src");
@@ -477,11 +627,15 @@ fn image_node_bundle(asset_server: &Res<AssetServer>, element: &NekoElement) ->
 }
 
 /// Build [`Text`] bundle
-fn text_node_bundle(asset_server: &Res<AssetServer>, element: &NekoElement) -> impl Bundle {
+fn text_node_bundle(
+    asset_server: &Res<AssetServer>,
+    element: &NekoElement,
+    text: String,
+) -> impl Bundle {
     let font: String = element.get_as("font");
 
     (
-        Text(element.get_as("text")),
+        Text(text),
         TextFont {
             font: match font {
                 s if s == "auto" => Handle::<Font>::default(),
@@ -504,7 +658,7 @@ fn span_node_bundle(asset_server: &Res<AssetServer>, element: &NekoElement) -> i
     let font: String = element.get_as("font");
 
     (
-        TextSpan(element.get_as("text")),
+        TextSpan(transform_text(element, &element.get_as::<String>("text"))),
         TextFont {
             font: match font {
                 s if s == "auto" => Handle::<Font>::default(),
@@ -517,3 +671,191 @@ fn span_node_bundle(asset_server: &Res<AssetServer>, element: &NekoElement) -> i
         TextColor(element.get_as("color")),
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::NekoMaidParser;
+    use crate::parse::native::NATIVE_WIDGETS;
+
+    /// Parses the given code and returns its elements with their properties
+    /// resolved.
+    fn elements(code: &str) -> Vec<NekoElement> {
+        let mut parser = NekoMaidParser::tokenize(code).unwrap();
+        for native in NATIVE_WIDGETS.iter() {
+            parser.register_native_widget(native.clone());
+        }
+
+        let module = parser.finish().unwrap();
+        module
+            .elements
+            .into_iter()
+            .map(|builder| {
+                let mut element = builder.element;
+                element.resolve(&HashMap::new()).unwrap();
+                element
+            })
+            .collect()
+    }
+
+    #[test]
+    fn atlas_layouts_are_shared_by_grid() {
+        let mut world = World::new();
+        world.init_resource::<Assets<TextureAtlasLayout>>();
+        world.init_resource::<AtlasLayouts>();
+
+        let code = "
+            layout img { atlas-layout: 16px 4 2; atlas-index: 3; }
+            layout img { atlas-layout: 16px 4 2; }
+            layout img { atlas-layout: 16px 8px 4 2; }
+            layout img {}
+        ";
+
+        let mut entities = Vec::new();
+        for element in elements(code) {
+            let mut commands = world.commands();
+            let mut entity = commands.spawn(ImageNode::default());
+            insert_texture_atlas(&mut entity, &element);
+            entities.push(entity.id());
+        }
+        world.flush();
+
+        let atlas = |entity: Entity| {
+            world
+                .get::<ImageNode>(entity)
+                .unwrap()
+                .texture_atlas
+                .clone()
+        };
+        let [first, second, third] = [0, 1, 2].map(|i| atlas(entities[i]).unwrap());
+        assert_eq!(first.index, 3);
+        assert_eq!(first.layout, second.layout);
+        assert_ne!(first.layout, third.layout);
+        assert!(atlas(entities[3]).is_none());
+
+        let layouts = world.resource::<Assets<TextureAtlasLayout>>();
+        assert_eq!(layouts.len(), 2);
+        assert_eq!(layouts.get(&first.layout).unwrap().len(), 8);
+        assert_eq!(layouts.get(&third.layout).unwrap().size, UVec2::new(64, 16));
+    }
+
+    #[test]
+    fn text_is_transformed() {
+        let code = "
+            layout p { text-transform: none; }
+            layout p { text-transform: uppercase; }
+            layout p { text-transform: lowercase; }
+            layout p { text-transform: capitalize; }
+        ";
+        let elements = elements(code);
+        let text = "the quICK\tbrown  fox";
+
+        assert_eq!(transform_text(&elements[0], text), "the quICK\tbrown  fox");
+        assert_eq!(transform_text(&elements[1], text), "THE QUICK\tBROWN  FOX");
+        assert_eq!(transform_text(&elements[2], text), "the quick\tbrown  fox");
+        assert_eq!(transform_text(&elements[3], text), "The QuICK\tBrown  Fox");
+    }
+
+    #[test]
+    fn capitalize_expands_letters() {
+        let elements = elements("layout p { text-transform: capitalize; }");
+        assert_eq!(transform_text(&elements[0], "ßig épée"), "SSig Épée");
+    }
+
+    #[test]
+    fn ellipsis_is_skipped_for_markup() {
+        let mut world = World::new();
+        let elements = elements("layout p { text-overflow: ellipsis; }");
+
+        let mut entities = Vec::new();
+        for text in [Some("plain".to_string()), None] {
+            let mut commands = world.commands();
+            let mut entity = commands.spawn((Node::default(), TextLayout::default()));
+            insert_text_ellipsis(&mut entity, &elements[0], text);
+            entities.push(entity.id());
+        }
+        world.flush();
+
+        for (entity, truncated) in entities.into_iter().zip([true, false]) {
+            let entity = world.entity(entity);
+            assert_eq!(entity.contains::<TextEllipsis>(), truncated);
+            assert_eq!(
+                entity.get::<TextLayout>().unwrap().linebreak,
+                LineBreak::NoWrap
+            );
+            assert_eq!(entity.get::<Node>().unwrap().overflow.x, OverflowAxis::Clip);
+        }
+    }
+
+    #[test]
+    fn opacity_is_clamped_and_multiplied() {
+        let code = "
+            layout div { opacity: 0.5; }
+            layout div { opacity: 2; }
+            layout div { opacity: -1; }
+            layout div {}
+        ";
+        let elements = elements(code);
+
+        assert_eq!(element_opacity(&elements[0], 1.0), 0.5);
+        assert_eq!(element_opacity(&elements[0], 0.5), 0.25);
+        assert_eq!(element_opacity(&elements[1], 0.5), 0.5);
+        assert_eq!(element_opacity(&elements[2], 0.5), 0.0);
+        assert_eq!(element_opacity(&elements[3], 0.8), 0.8);
+    }
+
+    #[test]
+    fn opacity_fades_colors() {
+        let mut world = World::new();
+        let color = Color::srgba(1.0, 0.5, 0.0, 0.8);
+
+        let entity = world
+            .spawn((
+                Text::default(),
+                BackgroundColor(color),
+                BorderColor::all(color),
+                BoxShadow::new(color, Val::ZERO, Val::ZERO, Val::ZERO, Val::ZERO),
+                Outline::new(Val::ZERO, Val::ZERO, color),
+                TextColor(color),
+                TextShadow { color, ..default() },
+            ))
+            .id();
+        let span = world
+            .spawn((
+                ChildOf(entity),
+                TextSpan::default(),
+                TextColor(Color::WHITE),
+            ))
+            .id();
+
+        world
+            .commands()
+            .entity(entity)
+            .queue(|entity: EntityWorldMut| apply_opacity(entity, 0.5));
+        world.flush();
+
+        let entity = world.entity(entity);
+        let border = entity.get::<BorderColor>().unwrap();
+        let alphas = [
+            entity.get::<BackgroundColor>().unwrap().0.alpha(),
+            border.top.alpha(),
+            border.left.alpha(),
+            border.right.alpha(),
+            border.bottom.alpha(),
+            entity.get::<BoxShadow>().unwrap().0[0].color.alpha(),
+            entity.get::<Outline>().unwrap().color.alpha(),
+            entity.get::<TextColor>().unwrap().0.alpha(),
+            entity.get::<TextShadow>().unwrap().color.alpha(),
+        ];
+        assert!(
+            alphas.iter().all(|alpha| (alpha - 0.4).abs() < 1e-6),
+            "{alphas:?}"
+        );
+        assert_eq!(
+            entity.get::<BackgroundColor>().unwrap().0.to_srgba().red,
+            1.0
+        );
+
+        assert_eq!(world.get::<TextColor>(span).unwrap().0.alpha(), 0.5);
+    }
+}
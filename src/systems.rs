@@ -1,15 +1,26 @@
 //! Systems used by the NekoMaid plugin.
 
-use bevy::asset::{AssetLoadFailedEvent, LoadState};
-use bevy::platform::collections::HashMap;
+use std::collections::HashMap;
+
+use bevy::asset::{
+    AssetLoadFailedEvent,
+    LoadState,
+    RecursiveDependencyLoadState,
+    UntypedAssetLoadFailedEvent,
+};
 use bevy::prelude::*;
+use bevy::text::TextLayoutInfo;
 
 use crate::asset::NekoMaidUI;
-use crate::components::NekoUITree;
+use crate::components::{AtlasAnimation, NekoUITree, TextEllipsis, TreeRoot};
+use crate::font::FontRegistry;
 use crate::marker::MarkerRegistry;
+use crate::native;
 use crate::parse::context::NekoResult;
 use crate::parse::element::NekoElementBuilder;
+use crate::parse::module::Module;
 use crate::parse::value::PropertyValue;
+use crate::widget::NativeWidgetRegistry;
 
 /// Listens for changes to the [`NekoUITree`] component and spawns the UI tree
 /// accordingly.
@@ -18,6 +29,8 @@ pub(super) fn spawn_tree(
     asset_server: Res<AssetServer>,
     assets: Res<Assets<NekoMaidUI>>,
     markers: Res<MarkerRegistry>,
+    fonts: Res<FontRegistry>,
+    widgets: Res<NativeWidgetRegistry>,
     mut roots: Query<
         (Entity, &mut NekoUITree, &mut Node),
         Or<(Added<NekoUITree>, Changed<NekoUITree>)>,
@@ -29,6 +42,18 @@ pub(super) fn spawn_tree(
             continue;
         }
 
+        // Wait for the images and fonts of the tree to load, so that it does
+        // not pop in piece by piece. The tree stays dirty, and is marked again
+        // once its dependencies have loaded or failed to load.
+        if root.preload()
+            && matches!(
+                asset_server.get_recursive_dependency_load_state(root.asset()),
+                Some(RecursiveDependencyLoadState::Loading)
+            )
+        {
+            continue;
+        }
+
         root.clear_dirty();
         commands.entity(entity).despawn_children();
 
@@ -57,12 +82,38 @@ pub(super) fn spawn_tree(
             }
         }
 
-        for element in &asset.elements {
-            let mut element = element.clone();
+        let elements = match root.root() {
+            TreeRoot::Layouts => asset.elements.clone(),
+            TreeRoot::Layout(name) => match asset.named_elements.get(name) {
+                Some(element) => vec![element.clone()],
+                None => {
+                    error!("Unknown layout '{}' for NekoUITree", name);
+                    continue;
+                }
+            },
+            TreeRoot::Widget { name, properties } => match asset.build_widget(name, properties) {
+                Ok(element) => vec![element],
+                Err(e) => {
+                    error!("{}", e);
+                    continue;
+                }
+            },
+        };
+
+        for mut element in elements {
             if let Err(e) = resolve_scope(&mut element, &variables) {
                 error!("{}", e);
             }
-            spawn_element(&asset_server, &markers, &mut commands, &element, entity);
+            resolve_fonts(&mut element, &fonts, asset);
+            spawn_element(
+                &asset_server,
+                &markers,
+                &widgets,
+                &mut commands,
+                &element,
+                entity,
+                1.0,
+            );
         }
     }
 }
@@ -86,21 +137,76 @@ pub fn resolve_scope(
     Ok(())
 }
 
+/// Replaces the font properties of an element and its children with the asset
+/// paths of the fonts they name.
+fn resolve_fonts(element: &mut NekoElementBuilder, fonts: &FontRegistry, module: &Module) {
+    for name in ["font", "font-bold", "font-italic"] {
+        match element.element.get_property_no_default(name) {
+            Some(PropertyValue::String(font)) if font == "none" => {}
+            Some(font) => {
+                let path = fonts.resolve(font, module);
+                element.element.set_property(name.to_string(), path.into());
+            }
+            None => {}
+        }
+    }
+
+    for child in &mut element.children {
+        resolve_fonts(child, fonts, module);
+    }
+}
+
 /// Recursively spawns a [`NekoElementBuilder`] and its children.
+///
+/// Native widgets registered in the [`NativeWidgetRegistry`] take priority
+/// over the built-in ones.
+///
+/// The opacity of the parent is multiplied with the opacity of each element
+/// and applied to the colors of the spawned entities.
 fn spawn_element(
     asset_server: &Res<AssetServer>,
     markers: &MarkerRegistry,
+    widgets: &NativeWidgetRegistry,
     commands: &mut Commands,
     element: &NekoElementBuilder,
     parent: Entity,
+    opacity: f32,
 ) {
-    let entity =
-        (element.native_widget.spawn_func)(asset_server, commands, &element.element, parent);
-    
+    let name = &element.native_widget.name;
+
+    let entity = if let Some(spawn_func) = widgets.spawn_func(name) {
+        let spawn_func = spawn_func.clone();
+        let native = element.element.clone();
+        commands
+            .spawn(ChildOf(parent))
+            .queue(move |entity: EntityWorldMut| spawn_func(entity, &native))
+            .id()
+    } else if let Some(spawn_func) = native::spawn_func(name) {
+        spawn_func(asset_server, commands, &element.element, parent)
+    } else {
+        error!("No spawn function for native widget `{}`", name);
+        return;
+    };
+
+    let opacity = native::element_opacity(&element.element, opacity);
+    if opacity < 1.0 {
+        commands
+            .entity(entity)
+            .queue(move |entity: EntityWorldMut| native::apply_opacity(entity, opacity));
+    }
+
     markers.insert(commands.entity(entity), &element.element);
 
     for child in &element.children {
-        spawn_element(asset_server, markers, commands, child, entity);
+        spawn_element(
+            asset_server,
+            markers,
+            widgets,
+            commands,
+            child,
+            entity,
+            opacity,
+        );
     }
 }
 
@@ -112,18 +218,54 @@ pub(super) fn update_tree(
 ) {
     for event in asset_updates.read() {
         match event {
-            AssetEvent::Modified { id } | AssetEvent::LoadedWithDependencies { id } => {
+            AssetEvent::Added { id } | AssetEvent::Modified { id } => {
                 for mut root in roots.iter_mut() {
                     if root.asset().id() == *id {
                         root.mark_dirty();
                     }
                 }
             }
+            AssetEvent::LoadedWithDependencies { id } => {
+                for mut root in roots.iter_mut() {
+                    if root.asset().id() == *id && root.is_dirty() {
+                        root.mark_dirty();
+                    }
+                }
+            }
             _ => {}
         }
     }
 }
 
+/// Listens for assets that fail to load and checks the UI trees still waiting
+/// for them again, so that a missing image or font does not keep a tree from
+/// spawning.
+pub(super) fn dependency_failure(
+    mut failures: MessageReader<UntypedAssetLoadFailedEvent>,
+    assets: Res<Assets<NekoMaidUI>>,
+    mut roots: Query<&mut NekoUITree>,
+) {
+    for event in failures.read() {
+        for mut root in roots.iter_mut() {
+            if root.is_dirty()
+                && assets
+                    .get(root.asset())
+                    .is_some_and(|asset| asset.depends_on(event.id))
+            {
+                root.mark_dirty();
+            }
+        }
+    }
+}
+
+/// Listens for changes to the [`FontRegistry`] and respawns all UI trees, so
+/// that they use the newly registered fonts.
+pub(super) fn update_fonts(mut roots: Query<&mut NekoUITree>) {
+    for mut root in roots.iter_mut() {
+        root.mark_dirty();
+    }
+}
+
 /// Listens for asset load failures and clears any existing UI trees that
 /// reference the failed asset.
 ///
@@ -141,3 +283,255 @@ pub(super) fn asset_failure(
         }
     }
 }
+
+/// The character appended to text truncated by a [`TextEllipsis`].
+const ELLIPSIS: char = '\u{2026}';
+
+/// Truncates the text of nodes with a [`TextEllipsis`] so that it fits the
+/// content width of the node.
+///
+/// The text is cut using the glyph positions of the previous text layout, so
+/// it may take a few frames to settle after the node is resized. Text that
+/// has child spans is not truncated; `p` elements with markup are only
+/// clipped, with a warning when they are spawned.
+#[allow(clippy::type_complexity)]
+pub(super) fn truncate_text(
+    mut texts: Query<
+        (&mut TextEllipsis, &mut Text, &TextLayoutInfo, &ComputedNode),
+        Or<(Changed<TextLayoutInfo>, Changed<ComputedNode>)>,
+    >,
+) {
+    for (mut ellipsis, mut text, layout, node) in texts.iter_mut() {
+        let inset = node.content_inset();
+        let width = node.size.x - inset.left - inset.right;
+
+        // Start over from the full text whenever the node is resized, since
+        // more of it may fit now.
+        if width != ellipsis.width() {
+            ellipsis.set_width(width);
+            if text.0 != ellipsis.text() {
+                text.0 = ellipsis.text().to_string();
+                continue;
+            }
+        }
+
+        if layout.size.x <= width || layout.glyphs.iter().any(|glyph| glyph.span_index != 0) {
+            continue;
+        }
+
+        // Once truncated, the last glyph is the ellipsis itself. Before that,
+        // the widest glyph is a safe estimate of its width.
+        let truncated = text.0 != ellipsis.text();
+        let ellipsis_width = match layout.glyphs.last() {
+            Some(glyph) if truncated => glyph.size.x,
+            _ => layout
+                .glyphs
+                .iter()
+                .map(|glyph| glyph.size.x)
+                .fold(0.0, f32::max),
+        };
+
+        let line_starts: Vec<usize> = std::iter::once(0)
+            .chain(text.0.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+
+        let Some(kept) = layout
+            .glyphs
+            .iter()
+            .find(|glyph| glyph.position.x + glyph.size.x / 2.0 > width - ellipsis_width)
+            .and_then(|glyph| {
+                let cut = line_starts[glyph.line_index] + glyph.byte_index;
+                ellipsis.text().get(.. cut)
+            })
+        else {
+            continue;
+        };
+
+        let mut truncated = kept.trim_end().to_string();
+        truncated.push(ELLIPSIS);
+        if text.0 != truncated {
+            text.0 = truncated;
+        }
+    }
+}
+
+/// Advances the texture atlas frames of nodes with an [`AtlasAnimation`].
+pub(super) fn animate_atlas(
+    time: Res<Time>,
+    layouts: Res<Assets<TextureAtlasLayout>>,
+    mut images: Query<(&mut AtlasAnimation, &mut ImageNode)>,
+) {
+    for (mut animation, mut image) in images.iter_mut() {
+        let frames = animation.tick(time.delta());
+        if frames == 0 {
+            continue;
+        }
+
+        let Some(atlas) = &mut image.texture_atlas else {
+            continue;
+        };
+        let Some(layout) = layouts.get(&atlas.layout) else {
+            continue;
+        };
+
+        for _ in 0 .. frames {
+            atlas.index = animation.next_index(atlas.index, layout.len());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::asset::io::Reader;
+    use bevy::asset::{AssetLoadError, AssetLoader, LoadContext};
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+    use crate::NekoMaidPlugin;
+
+    /// Loads any file as a default image, in place of the image loader that
+    /// needs a renderer.
+    #[derive(TypePath)]
+    struct TestImageLoader;
+
+    impl AssetLoader for TestImageLoader {
+        type Asset = Image;
+        type Settings = ();
+        type Error = std::io::Error;
+
+        async fn load(
+            &self,
+            _: &mut dyn Reader,
+            _: &Self::Settings,
+            _: &mut LoadContext<'_>,
+        ) -> Result<Self::Asset, Self::Error> {
+            Ok(Image::default())
+        }
+
+        fn extensions(&self) -> &[&str] {
+            &["png"]
+        }
+    }
+
+    /// Writes the given files to a new directory, and returns a headless app
+    /// that loads its assets from that directory and spawns a preloaded
+    /// [`NekoUITree`] for each of the given UI files.
+    fn test_app(name: &str, files: &[(&str, &str)], trees: &[&'static str]) -> (App, Vec<Entity>) {
+        let dir = std::env::temp_dir().join(format!("neko_maid_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        for (path, content) in files {
+            std::fs::write(dir.join(path), content).unwrap();
+        }
+
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin {
+                file_path: dir.display().to_string(),
+                watch_for_changes_override: Some(false),
+                ..default()
+            },
+            NekoMaidPlugin,
+        ))
+        .init_asset::<Image>()
+        .init_asset::<TextureAtlasLayout>()
+        .register_asset_loader(TestImageLoader);
+
+        let asset_server = app.world().resource::<AssetServer>().clone();
+        let trees = trees
+            .iter()
+            .map(|path| {
+                let tree = NekoUITree::new(asset_server.load(*path));
+                app.world_mut().spawn(tree).id()
+            })
+            .collect();
+
+        (app, trees)
+    }
+
+    /// Returns whether the given tree has spawned its elements.
+    fn is_spawned(app: &App, tree: Entity) -> bool {
+        app.world().get::<Children>(tree).is_some()
+    }
+
+    /// The files of a UI that loads an image, and of one whose image is
+    /// missing.
+    const FILES: &[(&str, &str)] = &[
+        ("image.png", ""),
+        ("ui.neko_ui", r#"layout img { src: "image.png"; }"#),
+        ("broken.neko_ui", r#"layout img { src: "missing.png"; }"#),
+    ];
+
+    #[test]
+    fn preloaded_trees_wait_for_dependencies() {
+        let (mut app, trees) = test_app("preload", FILES, &["ui.neko_ui", "broken.neko_ui"]);
+        let [ui, broken] = [trees[0], trees[1]]
+            .map(|tree| app.world().get::<NekoUITree>(tree).unwrap().asset().clone());
+
+        for _ in 0 .. 500 {
+            app.update();
+
+            let asset_server = app.world().resource::<AssetServer>();
+            if is_spawned(&app, trees[0]) {
+                assert!(asset_server.is_loaded_with_dependencies(&ui));
+            }
+            if is_spawned(&app, trees[1]) {
+                assert!(matches!(
+                    asset_server.get_recursive_dependency_load_state(&broken),
+                    Some(RecursiveDependencyLoadState::Failed(_))
+                ));
+            }
+            if trees.iter().all(|tree| is_spawned(&app, *tree)) {
+                return;
+            }
+
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        panic!("UI trees were not spawned");
+    }
+
+    #[test]
+    fn dependency_failure_marks_dependent_trees() {
+        let (mut app, trees) = test_app("failure", FILES, &["ui.neko_ui", "broken.neko_ui"]);
+        for _ in 0 .. 500 {
+            app.update();
+            if trees.iter().all(|tree| is_spawned(&app, *tree)) {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        let asset_server = app.world().resource::<AssetServer>().clone();
+        let world = app.world_mut();
+        for tree in &trees {
+            world.get_mut::<NekoUITree>(*tree).unwrap().mark_dirty();
+        }
+        let before = world.increment_change_tick();
+
+        world.write_message(UntypedAssetLoadFailedEvent {
+            id: asset_server.get_path_id("missing.png").unwrap(),
+            path: "missing.png".into(),
+            error: AssetLoadError::MissingAssetLoader {
+                loader_name: None,
+                asset_type_id: None,
+                extension: None,
+                asset_path: None,
+            },
+        });
+        world.run_system_once(dependency_failure).unwrap();
+
+        let marked: Vec<bool> = trees
+            .iter()
+            .map(|tree| {
+                let root = world.entity(*tree).get_ref::<NekoUITree>().unwrap();
+                root.last_changed()
+                    .is_newer_than(before, world.read_change_tick())
+            })
+            .collect();
+        assert_eq!(marked, [false, true]);
+    }
+}
@@ -0,0 +1,1878 @@
+//! Conversions between NekoMaid UI property values and Bevy types.
+
+use bevy::prelude::*;
+use bevy::text::{FontSmoothing, LineHeight};
+
+use crate::parse::color::Rgba;
+use crate::parse::element::NekoElement;
+use crate::parse::property::PropertyType;
+use crate::parse::value::PropertyValue;
+
+/// A type that can be created from a [`PropertyValue`].
+///
+/// Invalid values are logged as warnings and fall back to a sensible default,
+/// so that a typo in a UI file never prevents the rest of the tree from
+/// spawning.
+///
+/// The keywords accepted by these conversions are also listed in
+/// [`accepted_values`](crate::parse::native::accepted_values), which is used to
+/// generate the widget schema. The tests check that every keyword listed there
+/// converts, and that the keywords of every [`NekoEnum`] property are listed.
+pub trait FromPropertyValue {
+    /// Converts the given property value into this type.
+    fn from_property_value(property: &PropertyValue) -> Self;
+
+    /// Returns the value types this type is converted from, along with the
+    /// keywords it accepts as string values. If the keyword list is empty,
+    /// any string is accepted.
+    ///
+    /// This is used to check the default values of derived widgets. An empty
+    /// type list means the accepted types are unknown, and is not checked.
+    fn accepted_values() -> (&'static [PropertyType], &'static [&'static str]) {
+        (&[], &[])
+    }
+}
+
+/// A type that can be converted into a [`PropertyValue`].
+pub trait IntoPropertyValue {
+    /// Converts this value into a property value.
+    fn into_property_value(self) -> PropertyValue;
+}
+
+impl IntoPropertyValue for Color {
+    fn into_property_value(self) -> PropertyValue {
+        let color = self.to_srgba();
+        PropertyValue::Color(Rgba::new(color.red, color.green, color.blue, color.alpha))
+    }
+}
+
+impl IntoPropertyValue for f32 {
+    fn into_property_value(self) -> PropertyValue {
+        PropertyValue::Number(self as f64)
+    }
+}
+
+impl IntoPropertyValue for f64 {
+    fn into_property_value(self) -> PropertyValue {
+        PropertyValue::Number(self)
+    }
+}
+
+impl IntoPropertyValue for bool {
+    fn into_property_value(self) -> PropertyValue {
+        PropertyValue::Bool(self)
+    }
+}
+
+impl IntoPropertyValue for String {
+    fn into_property_value(self) -> PropertyValue {
+        PropertyValue::String(self)
+    }
+}
+
+/// Units other than pixels and percentages are not supported by NekoMaid UI,
+/// and are converted to `auto`.
+impl IntoPropertyValue for Val {
+    fn into_property_value(self) -> PropertyValue {
+        match self {
+            Val::Px(n) => PropertyValue::Pixels(n as f64),
+            Val::Percent(n) => PropertyValue::Percent(n as f64),
+            _ => PropertyValue::String("auto".to_string()),
+        }
+    }
+}
+
+/// An enum whose variants are written as keywords in property values, such
+/// as `space-between`. It can easily be implemented with derive.
+///
+/// ```
+/// # use neko_derive::NekoEnum;
+/// # use neko_maid::value::NekoEnum;
+/// #[derive(Default, NekoEnum)]
+/// pub enum Shape {
+///     #[default]
+///     Circle,
+///     RoundedSquare,
+///     #[neko(rename = "star")]
+///     Pentagram,
+/// }
+///
+/// assert_eq!(Shape::KEYWORDS, ["circle", "rounded-square", "star"]);
+/// ```
+///
+/// Variants are written in kebab-case unless renamed. Invalid keywords are
+/// logged as warnings and fall back to the default variant.
+///
+/// Native widgets derived with [`NekoWidget`](crate::widget::NekoWidget)
+/// register the keywords of their enum fields, which are then listed in the
+/// schema and the manifest, and suggested by the language server.
+pub trait NekoEnum: Default {
+    /// The keywords of all variants, in declaration order.
+    const KEYWORDS: &'static [&'static str];
+
+    /// Returns the variant with the given keyword, if there is one.
+    fn from_keyword(keyword: &str) -> Option<Self>;
+
+    /// Returns the keyword of this variant.
+    fn keyword(&self) -> &'static str;
+}
+
+impl<T: NekoEnum> FromPropertyValue for T {
+    fn from_property_value(property: &PropertyValue) -> Self {
+        match property {
+            PropertyValue::String(s) if let Some(value) = T::from_keyword(s) => value,
+            _ => {
+                warn!(
+                    "Failed to convert PropertyValue {} to {}",
+                    property,
+                    std::any::type_name::<T>()
+                );
+                Self::default()
+            }
+        }
+    }
+
+    fn accepted_values() -> (&'static [PropertyType], &'static [&'static str]) {
+        (&[PropertyType::String], T::KEYWORDS)
+    }
+}
+
+impl<T: NekoEnum> IntoPropertyValue for T {
+    fn into_property_value(self) -> PropertyValue {
+        PropertyValue::String(self.keyword().to_string())
+    }
+}
+
+/// Implements [`NekoEnum`] for a Bevy enum, given the keyword of each
+/// variant.
+macro_rules! impl_neko_enum {
+    ($($ty:ty { $($variant:ident => $keyword:literal),* $(,)? })*) => {$(
+        impl NekoEnum for $ty {
+            const KEYWORDS: &'static [&'static str] = &[$($keyword),*];
+
+            fn from_keyword(keyword: &str) -> Option<Self> {
+                match keyword {
+                    $($keyword => Some(Self::$variant),)*
+                    _ => None,
+                }
+            }
+
+            fn keyword(&self) -> &'static str {
+                match self {
+                    $(Self::$variant => $keyword,)*
+                }
+            }
+        }
+    )*};
+}
+
+impl_neko_enum! {
+    OverflowAxis {
+        Visible => "visible",
+        Clip => "clip",
+        Hidden => "hidden",
+        Scroll => "scroll",
+    }
+
+    Display {
+        Flex => "flex",
+        Grid => "grid",
+        Block => "block",
+        None => "none",
+    }
+
+    BoxSizing {
+        BorderBox => "border-box",
+        ContentBox => "content-box",
+    }
+
+    PositionType {
+        Relative => "relative",
+        Absolute => "absolute",
+    }
+
+    AlignItems {
+        Default => "default",
+        Start => "start",
+        End => "end",
+        FlexStart => "flex-start",
+        FlexEnd => "flex-end",
+        Center => "center",
+        Baseline => "baseline",
+        Stretch => "stretch",
+    }
+
+    JustifyItems {
+        Default => "default",
+        Start => "start",
+        End => "end",
+        Center => "center",
+        Baseline => "baseline",
+        Stretch => "stretch",
+    }
+
+    AlignSelf {
+        Auto => "auto",
+        Start => "start",
+        End => "end",
+        FlexStart => "flex-start",
+        FlexEnd => "flex-end",
+        Center => "center",
+        Baseline => "baseline",
+        Stretch => "stretch",
+    }
+
+    JustifySelf {
+        Auto => "auto",
+        Start => "start",
+        End => "end",
+        Center => "center",
+        Baseline => "baseline",
+        Stretch => "stretch",
+    }
+
+    AlignContent {
+        Default => "default",
+        Start => "start",
+        End => "end",
+        FlexStart => "flex-start",
+        FlexEnd => "flex-end",
+        Center => "center",
+        Stretch => "stretch",
+        SpaceBetween => "space-between",
+        SpaceAround => "space-around",
+        SpaceEvenly => "space-evenly",
+    }
+
+    JustifyContent {
+        Default => "default",
+        Start => "start",
+        End => "end",
+        FlexStart => "flex-start",
+        FlexEnd => "flex-end",
+        Center => "center",
+        Stretch => "stretch",
+        SpaceBetween => "space-between",
+        SpaceAround => "space-around",
+        SpaceEvenly => "space-evenly",
+    }
+
+    OverflowClipBox {
+        ContentBox => "content-box",
+        PaddingBox => "padding-box",
+        BorderBox => "border-box",
+    }
+
+    FlexDirection {
+        Row => "row",
+        Column => "column",
+        RowReverse => "row-reverse",
+        ColumnReverse => "column-reverse",
+    }
+
+    FlexWrap {
+        NoWrap => "nowrap",
+        Wrap => "wrap",
+        WrapReverse => "wrap-reverse",
+    }
+
+    GridAutoFlow {
+        Row => "row",
+        Column => "column",
+        RowDense => "row-dense",
+        ColumnDense => "column-dense",
+    }
+
+    FontSmoothing {
+        None => "none",
+        AntiAliased => "antialiased",
+    }
+
+    Justify {
+        Left => "left",
+        Right => "right",
+        Center => "center",
+        Justified => "justified",
+    }
+
+    LineBreak {
+        WordBoundary => "word",
+        AnyCharacter => "char",
+        WordOrCharacter => "word-or-char",
+        NoWrap => "nowrap",
+    }
+}
+
+/// Typed property accessors for [`NekoElement`].
+pub trait NekoElementExt {
+    /// Attempts to get a property and automatically convert it to the desired
+    /// type. If the property is not found, returns the default value for the
+    /// type.
+    fn get_as<O>(&self, name: &str) -> O
+    where
+        O: FromPropertyValue + Default;
+
+    /// Attempts to get a property and automatically convert it to the desired
+    /// type. If the property is not found, returns the provided default value.
+    fn get_as_or<O>(&self, name: &str, def: O) -> O
+    where
+        O: FromPropertyValue;
+
+    /// Attempts to get a property, ignoring all default values provided by the
+    /// native widget, and automatically convert it to the desired type. If the
+    /// property is not found, returns the provided value.
+    fn get_no_default<O>(&self, name: &str, def: O) -> O
+    where
+        O: FromPropertyValue;
+}
+
+impl NekoElementExt for NekoElement {
+    fn get_as<O>(&self, name: &str) -> O
+    where
+        O: FromPropertyValue + Default,
+    {
+        self.get_property(name)
+            .map(O::from_property_value)
+            .unwrap_or_default()
+    }
+
+    fn get_as_or<O>(&self, name: &str, def: O) -> O
+    where
+        O: FromPropertyValue,
+    {
+        self.get_property(name)
+            .map(O::from_property_value)
+            .unwrap_or(def)
+    }
+
+    fn get_no_default<O>(&self, name: &str, def: O) -> O
+    where
+        O: FromPropertyValue,
+    {
+        self.get_property_no_default(name)
+            .map(O::from_property_value)
+            .unwrap_or(def)
+    }
+}
+
+impl FromPropertyValue for Val {
+    fn from_property_value(property: &PropertyValue) -> Self {
+        match property {
+            PropertyValue::String(s) if s == "auto" => Val::Auto,
+            PropertyValue::Pixels(n) => Val::Px(*n as f32),
+            PropertyValue::Percent(n) => Val::Percent(*n as f32),
+            PropertyValue::Number(n) => Val::Px(*n as f32),
+            _ => {
+                warn_once!("Failed to convert PropertyValue {} to Val", property);
+                Self::default()
+            }
+        }
+    }
+
+    fn accepted_values() -> (&'static [PropertyType], &'static [&'static str]) {
+        (
+            &[
+                PropertyType::Number,
+                PropertyType::Pixels,
+                PropertyType::Percentage,
+                PropertyType::String,
+            ],
+            &["auto"],
+        )
+    }
+}
+
+impl FromPropertyValue for Color {
+    fn from_property_value(property: &PropertyValue) -> Self {
+        match property {
+            PropertyValue::Color(c) => srgba(c),
+            _ => {
+                warn_once!("Failed to convert PropertyValue {} to Color", property);
+                Self::default()
+            }
+        }
+    }
+
+    fn accepted_values() -> (&'static [PropertyType], &'static [&'static str]) {
+        (&[PropertyType::Color], &[])
+    }
+}
+
+impl FromPropertyValue for f32 {
+    fn from_property_value(property: &PropertyValue) -> Self {
+        match property {
+            PropertyValue::Number(n) => *n as f32,
+            _ => {
+                warn!("Failed to convert PropertyValue {} to f32", property);
+                Self::default()
+            }
+        }
+    }
+
+    fn accepted_values() -> (&'static [PropertyType], &'static [&'static str]) {
+        (&[PropertyType::Number], &[])
+    }
+}
+
+impl FromPropertyValue for f64 {
+    fn from_property_value(property: &PropertyValue) -> Self {
+        match property {
+            PropertyValue::Number(n) => *n,
+            _ => {
+                warn!("Failed to convert PropertyValue {} to f64", property);
+                Self::default()
+            }
+        }
+    }
+
+    fn accepted_values() -> (&'static [PropertyType], &'static [&'static str]) {
+        (&[PropertyType::Number], &[])
+    }
+}
+
+/// Implements the property value conversions for integer types. Numbers that
+/// are not whole or do not fit in the type are rejected.
+macro_rules! impl_integer_property_value {
+    ($($ty:ty),*) => {$(
+        impl FromPropertyValue for $ty {
+            fn from_property_value(property: &PropertyValue) -> Self {
+                match property {
+                    PropertyValue::Number(n)
+                        if n.fract() == 0.0
+                            && *n >= <$ty>::MIN as f64
+                            && *n <= <$ty>::MAX as f64 =>
+                    {
+                        *n as $ty
+                    }
+                    _ => {
+                        warn!(
+                            "Failed to convert PropertyValue {} to {}",
+                            property,
+                            stringify!($ty)
+                        );
+                        Self::default()
+                    }
+                }
+            }
+
+            fn accepted_values() -> (&'static [PropertyType], &'static [&'static str]) {
+                (&[PropertyType::Number], &[])
+            }
+        }
+
+        impl IntoPropertyValue for $ty {
+            fn into_property_value(self) -> PropertyValue {
+                PropertyValue::Number(self as f64)
+            }
+        }
+    )*};
+}
+
+impl_integer_property_value!(u8, u16, u32, u64, usize, i8, i16, i32, i64);
+
+impl FromPropertyValue for bool {
+    fn from_property_value(property: &PropertyValue) -> Self {
+        match property {
+            PropertyValue::Bool(b) => *b,
+            _ => {
+                warn!("Failed to convert PropertyValue {} to bool", property);
+                Self::default()
+            }
+        }
+    }
+
+    fn accepted_values() -> (&'static [PropertyType], &'static [&'static str]) {
+        (&[PropertyType::Boolean], &[])
+    }
+}
+
+impl FromPropertyValue for Option<f32> {
+    fn from_property_value(property: &PropertyValue) -> Self {
+        match property {
+            PropertyValue::Number(n) if *n >= 0.0 => Some(*n as f32),
+            _ => None,
+        }
+    }
+
+    fn accepted_values() -> (&'static [PropertyType], &'static [&'static str]) {
+        (&[PropertyType::Number], &[])
+    }
+}
+
+impl FromPropertyValue for Vec<RepeatedGridTrack> {
+    fn from_property_value(property: &PropertyValue) -> Self {
+        let mut tracks = Vec::new();
+        for value in as_slice(property) {
+            let track = match value {
+                PropertyValue::String(s) if s == "none" => continue,
+                PropertyValue::Function { name, args } if name == "repeat" => {
+                    repeated_grid_track(args)
+                }
+                value => grid_track(value).map(RepeatedGridTrack::from),
+            };
+
+            match track {
+                Some(track) => tracks.push(track),
+                None => {
+                    warn!(
+                        "Failed to convert PropertyValue {} to RepeatedGridTrack",
+                        value
+                    );
+                }
+            }
+        }
+        tracks
+    }
+}
+
+impl FromPropertyValue for Vec<GridTrack> {
+    fn from_property_value(property: &PropertyValue) -> Self {
+        let mut tracks = Vec::new();
+        for value in as_slice(property) {
+            match value {
+                PropertyValue::String(s) if s == "none" => {}
+                value => match grid_track(value) {
+                    Some(track) => tracks.push(track),
+                    None => warn!("Failed to convert PropertyValue {} to GridTrack", value),
+                },
+            }
+        }
+        tracks
+    }
+}
+
+impl FromPropertyValue for GridPlacement {
+    fn from_property_value(property: &PropertyValue) -> Self {
+        let placement = match as_slice(property) {
+            [PropertyValue::String(s)] if s == "auto" => Some(GridPlacement::auto()),
+            [PropertyValue::Number(start)] => grid_line(*start).map(GridPlacement::start),
+            [PropertyValue::String(s), PropertyValue::Number(span)] if s == "span" => {
+                grid_span(*span).map(GridPlacement::span)
+            }
+            [
+                PropertyValue::Number(start),
+                PropertyValue::String(s),
+                PropertyValue::Number(span),
+            ] if s == "span" => grid_line(*start)
+                .zip(grid_span(*span))
+                .map(|(start, span)| GridPlacement::start_span(start, span)),
+            _ => None,
+        };
+
+        placement.unwrap_or_else(|| {
+            warn!(
+                "Failed to convert PropertyValue {} to GridPlacement",
+                property
+            );
+            Self::default()
+        })
+    }
+}
+
+/// Returns the values of a property that may be given as a sequence.
+fn as_slice(property: &PropertyValue) -> &[PropertyValue] {
+    match property {
+        PropertyValue::List(values) => values,
+        value => std::slice::from_ref(value),
+    }
+}
+
+/// Converts a single track size, such as `1fr` or `minmax(100px, 1fr)`.
+fn grid_track(property: &PropertyValue) -> Option<GridTrack> {
+    match property {
+        PropertyValue::Function { name, args } if name == "minmax" => match args.as_slice() {
+            [min, max] => Some(GridTrack::minmax(
+                min_track_sizing(min)?,
+                max_track_sizing(max)?,
+            )),
+            _ => None,
+        },
+        PropertyValue::String(s) if s == "auto" => Some(GridTrack::auto()),
+        PropertyValue::String(s) if s == "min-content" => Some(GridTrack::min_content()),
+        PropertyValue::String(s) if s == "max-content" => Some(GridTrack::max_content()),
+        PropertyValue::Pixels(n) | PropertyValue::Number(n) => Some(GridTrack::px(*n as f32)),
+        PropertyValue::Percent(n) => Some(GridTrack::percent(*n as f32)),
+        PropertyValue::Fraction(n) => Some(GridTrack::fr(*n as f32)),
+        _ => None,
+    }
+}
+
+/// Converts the arguments of `repeat(count, tracks...)`, where the count is
+/// a number, `auto-fill`, or `auto-fit`.
+fn repeated_grid_track(args: &[PropertyValue]) -> Option<RepeatedGridTrack> {
+    let [count, tracks] = args else {
+        return None;
+    };
+
+    let repetition = match count {
+        PropertyValue::Number(n) if *n >= 1.0 && n.fract() == 0.0 => {
+            GridTrackRepetition::Count(*n as u16)
+        }
+        PropertyValue::String(s) if s == "auto-fill" => GridTrackRepetition::AutoFill,
+        PropertyValue::String(s) if s == "auto-fit" => GridTrackRepetition::AutoFit,
+        _ => return None,
+    };
+
+    let tracks = as_slice(tracks)
+        .iter()
+        .map(grid_track)
+        .collect::<Option<Vec<_>>>()?;
+    Some(RepeatedGridTrack::repeat_many(repetition, tracks))
+}
+
+/// Converts the minimum of a `minmax()` track.
+fn min_track_sizing(property: &PropertyValue) -> Option<MinTrackSizingFunction> {
+    match property {
+        PropertyValue::String(s) if s == "auto" => Some(MinTrackSizingFunction::Auto),
+        PropertyValue::String(s) if s == "min-content" => Some(MinTrackSizingFunction::MinContent),
+        PropertyValue::String(s) if s == "max-content" => Some(MinTrackSizingFunction::MaxContent),
+        PropertyValue::Pixels(n) | PropertyValue::Number(n) => {
+            Some(MinTrackSizingFunction::Px(*n as f32))
+        }
+        PropertyValue::Percent(n) => Some(MinTrackSizingFunction::Percent(*n as f32)),
+        _ => None,
+    }
+}
+
+/// Converts the maximum of a `minmax()` track.
+fn max_track_sizing(property: &PropertyValue) -> Option<MaxTrackSizingFunction> {
+    match property {
+        PropertyValue::String(s) if s == "auto" => Some(MaxTrackSizingFunction::Auto),
+        PropertyValue::String(s) if s == "min-content" => Some(MaxTrackSizingFunction::MinContent),
+        PropertyValue::String(s) if s == "max-content" => Some(MaxTrackSizingFunction::MaxContent),
+        PropertyValue::Pixels(n) | PropertyValue::Number(n) => {
+            Some(MaxTrackSizingFunction::Px(*n as f32))
+        }
+        PropertyValue::Percent(n) => Some(MaxTrackSizingFunction::Percent(*n as f32)),
+        PropertyValue::Fraction(n) => Some(MaxTrackSizingFunction::Fraction(*n as f32)),
+        _ => None,
+    }
+}
+
+/// Converts a grid line number. Lines are counted from 1, or from -1 when
+/// counting from the end, so 0 is invalid.
+fn grid_line(n: f64) -> Option<i16> {
+    (n.fract() == 0.0 && n != 0.0 && n.abs() <= i16::MAX as f64).then_some(n as i16)
+}
+
+/// Converts the number of tracks spanned by a grid item, which must be
+/// positive.
+fn grid_span(n: f64) -> Option<u16> {
+    (n.fract() == 0.0 && n >= 1.0 && n <= u16::MAX as f64).then_some(n as u16)
+}
+
+impl FromPropertyValue for BoxShadow {
+    fn from_property_value(property: &PropertyValue) -> Self {
+        let shadows = match property {
+            PropertyValue::String(s) if s == "none" => return BoxShadow::default(),
+            PropertyValue::CommaList(shadows) => shadows.as_slice(),
+            shadow => std::slice::from_ref(shadow),
+        };
+
+        match shadows.iter().map(shadow_style).collect() {
+            Some(shadows) => BoxShadow(shadows),
+            None => {
+                warn!("Failed to convert PropertyValue {} to BoxShadow", property);
+                Self::default()
+            }
+        }
+    }
+}
+
+/// Converts a single shadow, written as `x y [blur [spread]] [color]`. The
+/// color may also come first, and defaults to black.
+fn shadow_style(property: &PropertyValue) -> Option<ShadowStyle> {
+    let mut lengths = Vec::new();
+    let mut color = None;
+
+    for value in as_slice(property) {
+        match value {
+            PropertyValue::Color(c) if color.is_none() => color = Some(srgba(c)),
+            value => lengths.push(length(value)?),
+        }
+    }
+
+    let (x_offset, y_offset, blur_radius, spread_radius) = match lengths.as_slice() {
+        [x, y] => (*x, *y, Val::ZERO, Val::ZERO),
+        [x, y, blur] => (*x, *y, *blur, Val::ZERO),
+        [x, y, blur, spread] => (*x, *y, *blur, *spread),
+        _ => return None,
+    };
+
+    Some(ShadowStyle {
+        color: color.unwrap_or(Color::BLACK),
+        x_offset,
+        y_offset,
+        spread_radius,
+        blur_radius,
+    })
+}
+
+impl FromPropertyValue for TextShadow {
+    fn from_property_value(property: &PropertyValue) -> Self {
+        match text_shadow(property) {
+            Some(shadow) => shadow,
+            None => {
+                warn!("Failed to convert PropertyValue {} to TextShadow", property);
+                Self::default()
+            }
+        }
+    }
+}
+
+/// Converts a text shadow, written as `x y [color]`. The offsets are in
+/// logical pixels, and the color may also come first.
+fn text_shadow(property: &PropertyValue) -> Option<TextShadow> {
+    let mut offsets = Vec::new();
+    let mut color = None;
+
+    for value in as_slice(property) {
+        match value {
+            PropertyValue::Color(c) if color.is_none() => color = Some(srgba(c)),
+            PropertyValue::Number(n) | PropertyValue::Pixels(n) => offsets.push(*n as f32),
+            _ => return None,
+        }
+    }
+
+    let [x, y] = offsets.as_slice() else {
+        return None;
+    };
+
+    Some(TextShadow {
+        offset: Vec2::new(*x, *y),
+        color: color.unwrap_or(TextShadow::default().color),
+    })
+}
+
+/// The grid of a texture atlas layout. Layouts with the same grid are
+/// identical, so the grid is used to share them between widgets.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct AtlasGrid {
+    /// The size of each tile, in pixels.
+    tile_size: UVec2,
+
+    /// The number of columns.
+    columns: u32,
+
+    /// The number of rows.
+    rows: u32,
+}
+
+impl AtlasGrid {
+    /// Creates the texture atlas layout with this grid.
+    pub(crate) fn layout(&self) -> TextureAtlasLayout {
+        TextureAtlasLayout::from_grid(self.tile_size, self.columns, self.rows, None, None)
+    }
+}
+
+/// Invalid grids fall back to an empty grid, which has no tiles.
+impl FromPropertyValue for AtlasGrid {
+    fn from_property_value(property: &PropertyValue) -> Self {
+        match atlas_grid(property) {
+            Some(grid) => grid,
+            None => {
+                warn!("Failed to convert PropertyValue {} to AtlasGrid", property);
+                Self::default()
+            }
+        }
+    }
+}
+
+impl FromPropertyValue for TextureAtlasLayout {
+    fn from_property_value(property: &PropertyValue) -> Self {
+        AtlasGrid::from_property_value(property).layout()
+    }
+}
+
+/// Converts a grid atlas layout, written as `size [height] columns rows`. The
+/// tile size is in pixels, and tiles are square if no height is given.
+fn atlas_grid(property: &PropertyValue) -> Option<AtlasGrid> {
+    let pixels = |value: &PropertyValue| match value {
+        PropertyValue::Pixels(n) if *n > 0.0 => Some(*n as u32),
+        _ => None,
+    };
+    let count = |value: &PropertyValue| match value {
+        PropertyValue::Number(n) if n.fract() == 0.0 && *n > 0.0 => Some(*n as u32),
+        _ => None,
+    };
+
+    let (tile_size, columns, rows) = match as_slice(property) {
+        [size, columns, rows] => (UVec2::splat(pixels(size)?), columns, rows),
+        [width, height, columns, rows] => {
+            (UVec2::new(pixels(width)?, pixels(height)?), columns, rows)
+        }
+        _ => return None,
+    };
+
+    Some(AtlasGrid {
+        tile_size,
+        columns: count(columns)?,
+        rows: count(rows)?,
+    })
+}
+
+impl FromPropertyValue for BackgroundGradient {
+    fn from_property_value(property: &PropertyValue) -> Self {
+        match gradients(property) {
+            Some(gradients) => BackgroundGradient(gradients),
+            None => {
+                warn!(
+                    "Failed to convert PropertyValue {} to BackgroundGradient",
+                    property
+                );
+                Self::default()
+            }
+        }
+    }
+}
+
+impl FromPropertyValue for BorderGradient {
+    fn from_property_value(property: &PropertyValue) -> Self {
+        match gradients(property) {
+            Some(gradients) => BorderGradient(gradients),
+            None => {
+                warn!(
+                    "Failed to convert PropertyValue {} to BorderGradient",
+                    property
+                );
+                Self::default()
+            }
+        }
+    }
+}
+
+/// Converts `none` or a comma list of gradient functions.
+fn gradients(property: &PropertyValue) -> Option<Vec<Gradient>> {
+    let gradients = match property {
+        PropertyValue::String(s) if s == "none" => return Some(Vec::new()),
+        PropertyValue::CommaList(gradients) => gradients.as_slice(),
+        gradient => std::slice::from_ref(gradient),
+    };
+    gradients.iter().map(gradient).collect()
+}
+
+/// Converts a single `linear-gradient()`, `radial-gradient()`, or
+/// `conic-gradient()` function.
+///
+/// The first argument configures the gradient, unless it is already a color
+/// stop, in which case the CSS defaults are used.
+fn gradient(property: &PropertyValue) -> Option<Gradient> {
+    let PropertyValue::Function { name, args } = property else {
+        return None;
+    };
+
+    let (config, stops) = match args.split_first()? {
+        (first, rest) if !is_color_stop(first) => (as_slice(first), rest),
+        _ => (&[][..], args.as_slice()),
+    };
+
+    match name.as_str() {
+        "linear-gradient" => {
+            let angle = match config {
+                [] => 180.0,
+                [PropertyValue::String(to), sides @ ..] if to == "to" => side_angle(sides)?,
+                [value] => angle(value)?,
+                _ => return None,
+            };
+            let stops = color_stops(stops)?;
+            Some(LinearGradient::degrees(angle, stops).into())
+        }
+        "radial-gradient" => {
+            let (shape, position) = split_position(config);
+            let shape = match shape {
+                [] => RadialGradientShape::FarthestCorner,
+                [PropertyValue::String(s)] if s == "closest-side" => {
+                    RadialGradientShape::ClosestSide
+                }
+                [PropertyValue::String(s)] if s == "farthest-side" || s == "circle" => {
+                    RadialGradientShape::FarthestSide
+                }
+                [PropertyValue::String(s)] if s == "closest-corner" => {
+                    RadialGradientShape::ClosestCorner
+                }
+                [PropertyValue::String(s)] if s == "farthest-corner" || s == "ellipse" => {
+                    RadialGradientShape::FarthestCorner
+                }
+                [PropertyValue::String(s), radius] if s == "circle" => {
+                    RadialGradientShape::Circle(length(radius)?)
+                }
+                [PropertyValue::String(s), x, y] if s == "ellipse" => {
+                    RadialGradientShape::Ellipse(length(x)?, length(y)?)
+                }
+                _ => return None,
+            };
+            let stops = color_stops(stops)?;
+            Some(RadialGradient::new(ui_position(position)?, shape, stops).into())
+        }
+        "conic-gradient" => {
+            let (start, position) = split_position(config);
+            let start = match start {
+                [] => 0.0,
+                [PropertyValue::String(from), value] if from == "from" => angle(value)?,
+                _ => return None,
+            };
+            let stops = angular_color_stops(stops)?;
+            let gradient = ConicGradient::new(ui_position(position)?, stops);
+            Some(gradient.with_start(start.to_radians()).into())
+        }
+        _ => None,
+    }
+}
+
+/// Returns true if the given gradient argument is a color stop, which starts
+/// with a color.
+fn is_color_stop(property: &PropertyValue) -> bool {
+    matches!(as_slice(property).first(), Some(PropertyValue::Color(_)))
+}
+
+/// Converts color stops, written as `color [position]`.
+fn color_stops(stops: &[PropertyValue]) -> Option<Vec<ColorStop>> {
+    if stops.is_empty() {
+        return None;
+    }
+
+    stops
+        .iter()
+        .map(|stop| match as_slice(stop) {
+            [PropertyValue::Color(c)] => Some(ColorStop::auto(srgba(c))),
+            [PropertyValue::Color(c), point] => Some(ColorStop::new(srgba(c), length(point)?)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Converts the color stops of a conic gradient, written as `color [angle]`.
+fn angular_color_stops(stops: &[PropertyValue]) -> Option<Vec<AngularColorStop>> {
+    if stops.is_empty() {
+        return None;
+    }
+
+    stops
+        .iter()
+        .map(|stop| match as_slice(stop) {
+            [PropertyValue::Color(c)] => Some(AngularColorStop::auto(srgba(c))),
+            [PropertyValue::Color(c), value] => {
+                Some(AngularColorStop::new(srgba(c), angle(value)?.to_radians()))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Converts the sides of `to top right` and similar into an angle in degrees.
+fn side_angle(sides: &[PropertyValue]) -> Option<f32> {
+    let (mut x, mut y) = (None, None);
+    for side in sides {
+        let (axis, direction) = match side {
+            PropertyValue::String(s) if s == "left" => (&mut x, -1),
+            PropertyValue::String(s) if s == "right" => (&mut x, 1),
+            PropertyValue::String(s) if s == "top" => (&mut y, -1),
+            PropertyValue::String(s) if s == "bottom" => (&mut y, 1),
+            _ => return None,
+        };
+
+        if axis.replace(direction).is_some() {
+            return None;
+        }
+    }
+
+    match (x.unwrap_or(0), y.unwrap_or(0)) {
+        (0, -1) => Some(0.0),
+        (1, -1) => Some(45.0),
+        (1, 0) => Some(90.0),
+        (1, 1) => Some(135.0),
+        (0, 1) => Some(180.0),
+        (-1, 1) => Some(225.0),
+        (-1, 0) => Some(270.0),
+        (-1, -1) => Some(315.0),
+        _ => None,
+    }
+}
+
+/// Splits a gradient configuration at the `at` keyword, returning the values
+/// before it and the position after it.
+fn split_position(config: &[PropertyValue]) -> (&[PropertyValue], Option<&[PropertyValue]>) {
+    let at = config
+        .iter()
+        .position(|value| matches!(value, PropertyValue::String(s) if s == "at"));
+
+    match at {
+        Some(at) => (&config[.. at], Some(&config[at + 1 ..])),
+        None => (config, None),
+    }
+}
+
+/// Converts the position of a gradient, given as up to two of the keywords
+/// `left`, `right`, `top`, `bottom`, and `center`, or as an `x y` offset from
+/// the top left corner. Defaults to the center if no position is given.
+fn ui_position(position: Option<&[PropertyValue]>) -> Option<UiPosition> {
+    let Some(position) = position else {
+        return Some(UiPosition::CENTER);
+    };
+
+    if let [x, y] = position
+        && let (Some(x), Some(y)) = (length(x), length(y))
+    {
+        return Some(UiPosition::TOP_LEFT.at(x, y));
+    }
+
+    if position.is_empty() || position.len() > 2 {
+        return None;
+    }
+
+    let mut anchor = Vec2::ZERO;
+    for value in position {
+        match value {
+            PropertyValue::String(s) if s == "left" => anchor.x = -0.5,
+            PropertyValue::String(s) if s == "right" => anchor.x = 0.5,
+            PropertyValue::String(s) if s == "top" => anchor.y = -0.5,
+            PropertyValue::String(s) if s == "bottom" => anchor.y = 0.5,
+            PropertyValue::String(s) if s == "center" => {}
+            _ => return None,
+        }
+    }
+    Some(UiPosition::anchor(anchor))
+}
+
+/// Converts a length, which is a number of pixels or a percentage.
+fn length(property: &PropertyValue) -> Option<Val> {
+    match property {
+        PropertyValue::Number(n) | PropertyValue::Pixels(n) => Some(Val::Px(*n as f32)),
+        PropertyValue::Percent(n) => Some(Val::Percent(*n as f32)),
+        _ => None,
+    }
+}
+
+/// Converts an angle in degrees. Plain numbers are treated as degrees.
+fn angle(property: &PropertyValue) -> Option<f32> {
+    match property {
+        PropertyValue::Angle(n) | PropertyValue::Number(n) => Some(*n as f32),
+        _ => None,
+    }
+}
+
+/// Converts a color literal into a Bevy color.
+fn srgba(color: &Rgba) -> Color {
+    Color::srgba(color.red, color.green, color.blue, color.alpha)
+}
+
+impl FromPropertyValue for Val2 {
+    fn from_property_value(property: &PropertyValue) -> Self {
+        let translation = match as_slice(property) {
+            [x] => length(x).map(|x| Val2::new(x, Val::ZERO)),
+            [x, y] => length(x).zip(length(y)).map(|(x, y)| Val2::new(x, y)),
+            _ => None,
+        };
+
+        translation.unwrap_or_else(|| {
+            warn!("Failed to convert PropertyValue {} to Val2", property);
+            Val2::ZERO
+        })
+    }
+}
+
+/// Converts scale factors. A single factor scales both axes. Invalid values
+/// fall back to [`Vec2::ONE`], the identity scale.
+impl FromPropertyValue for Vec2 {
+    fn from_property_value(property: &PropertyValue) -> Self {
+        let factor = |value: &PropertyValue| match value {
+            PropertyValue::Number(n) => Some(*n as f32),
+            PropertyValue::Percent(n) => Some(*n as f32 / 100.0),
+            _ => None,
+        };
+
+        let scale = match as_slice(property) {
+            [both] => factor(both).map(Vec2::splat),
+            [x, y] => factor(x).zip(factor(y)).map(|(x, y)| Vec2::new(x, y)),
+            _ => None,
+        };
+
+        scale.unwrap_or_else(|| {
+            warn!("Failed to convert PropertyValue {} to Vec2", property);
+            Vec2::ONE
+        })
+    }
+}
+
+impl FromPropertyValue for Rot2 {
+    fn from_property_value(property: &PropertyValue) -> Self {
+        match angle(property) {
+            Some(degrees) => Rot2::degrees(degrees),
+            None => {
+                warn!("Failed to convert PropertyValue {} to Rot2", property);
+                Self::default()
+            }
+        }
+    }
+}
+
+impl FromPropertyValue for String {
+    fn from_property_value(property: &PropertyValue) -> Self {
+        match property {
+            PropertyValue::String(s) => s.clone(),
+            _ => {
+                warn!("Failed to convert PropertyValue {} to String", property);
+                Self::default()
+            }
+        }
+    }
+
+    fn accepted_values() -> (&'static [PropertyType], &'static [&'static str]) {
+        (&[PropertyType::String], &[])
+    }
+}
+
+impl FromPropertyValue for LineHeight {
+    fn from_property_value(property: &PropertyValue) -> Self {
+        match property {
+            PropertyValue::Number(n) => LineHeight::Px(*n as f32),
+            PropertyValue::Pixels(n) => LineHeight::Px(*n as f32),
+            PropertyValue::Percent(n) => LineHeight::RelativeToFont(*n as f32 / 100.0),
+            _ => {
+                warn!("Failed to convert PropertyValue {} to LineHeight", property);
+                Self::default()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use bevy::log::tracing;
+    use bevy::log::tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+    use bevy::log::tracing_subscriber::registry::Registry;
+
+    use super::*;
+    use crate::parse::native::{NATIVE_WIDGETS, accepted_values};
+
+    /// Keyword properties that are matched while spawning the native widgets,
+    /// rather than converted by [`FromPropertyValue`].
+    const MATCHED_WHILE_SPAWNING: &[&str] = &[
+        "mode",
+        "center-scale-mode",
+        "sides-scale-mode",
+        "text-overflow",
+        "text-transform",
+        "global-z-index",
+        "text-shadow",
+        "atlas-layout",
+    ];
+
+    /// A tracing layer that counts the warnings logged while it is active.
+    struct WarningCounter(Arc<AtomicUsize>);
+
+    impl<S: tracing::Subscriber> Layer<S> for WarningCounter {
+        fn on_event(&self, event: &tracing::Event<'_>, _: Context<'_, S>) {
+            if *event.metadata().level() == tracing::Level::WARN {
+                self.0.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Converts the given value, returning `None` if the conversion logged a
+    /// warning and fell back to the default.
+    fn convert<T: FromPropertyValue>(property: &PropertyValue) -> Option<T> {
+        let warnings = Arc::new(AtomicUsize::new(0));
+        let subscriber = Registry::default().with(WarningCounter(warnings.clone()));
+        let value =
+            tracing::subscriber::with_default(subscriber, || T::from_property_value(property));
+        (warnings.load(Ordering::Relaxed) == 0).then_some(value)
+    }
+
+    /// Returns true if the given value converts for the native widget property
+    /// with the given name.
+    fn converts(name: &str, value: &PropertyValue) -> bool {
+        match name {
+            "display" => convert::<Display>(value).is_some(),
+            "box-sizing" => convert::<BoxSizing>(value).is_some(),
+            "position-type" => convert::<PositionType>(value).is_some(),
+            "overflow-x" | "overflow-y" => convert::<OverflowAxis>(value).is_some(),
+            "overflow-clip-margin-box" => convert::<OverflowClipBox>(value).is_some(),
+            "align-items" => convert::<AlignItems>(value).is_some(),
+            "justify-items" => convert::<JustifyItems>(value).is_some(),
+            "align-self" => convert::<AlignSelf>(value).is_some(),
+            "justify-self" => convert::<JustifySelf>(value).is_some(),
+            "align-content" => convert::<AlignContent>(value).is_some(),
+            "justify-content" => convert::<JustifyContent>(value).is_some(),
+            "flex-direction" => convert::<FlexDirection>(value).is_some(),
+            "flex-wrap" => convert::<FlexWrap>(value).is_some(),
+            "grid-auto-flow" => convert::<GridAutoFlow>(value).is_some(),
+            "grid-template-rows" | "grid-template-columns" => {
+                convert::<Vec<RepeatedGridTrack>>(value).is_some()
+            }
+            "grid-auto-rows" | "grid-auto-columns" => convert::<Vec<GridTrack>>(value).is_some(),
+            "grid-row" | "grid-column" => convert::<GridPlacement>(value).is_some(),
+            "font-smoothing" => convert::<FontSmoothing>(value).is_some(),
+            "justify" => convert::<Justify>(value).is_some(),
+            "line-break" => convert::<LineBreak>(value).is_some(),
+            "box-shadow" => convert::<BoxShadow>(value).is_some(),
+            "background" => convert::<BackgroundGradient>(value).is_some(),
+            "border-gradient" => convert::<BorderGradient>(value).is_some(),
+            "aspect-ratio" => convert::<Option<f32>>(value).is_some(),
+            "left" | "top" | "right" | "bottom" | "width" | "height" | "min-width"
+            | "min-height" | "max-width" | "max-height" | "flex-basis" | "row-gap"
+            | "column-gap" | "border" | "outline" | "outline-width" | "outline-offset" => {
+                convert::<Val>(value).is_some()
+            }
+            p if p.starts_with("margin")
+                || p.starts_with("padding")
+                || p.starts_with("border-thickness")
+                || p.starts_with("border-radius") =>
+            {
+                convert::<Val>(value).is_some()
+            }
+            _ => panic!("{} accepts keywords but has no conversion", name),
+        }
+    }
+
+    /// Returns the keywords of the enum that the native widget property with
+    /// the given name is converted into, if it is an enum.
+    fn enum_keywords(name: &str) -> Option<&'static [&'static str]> {
+        match name {
+            "display" => Some(Display::KEYWORDS),
+            "box-sizing" => Some(BoxSizing::KEYWORDS),
+            "position-type" => Some(PositionType::KEYWORDS),
+            "overflow-x" | "overflow-y" => Some(OverflowAxis::KEYWORDS),
+            "overflow-clip-margin-box" => Some(OverflowClipBox::KEYWORDS),
+            "align-items" => Some(AlignItems::KEYWORDS),
+            "justify-items" => Some(JustifyItems::KEYWORDS),
+            "align-self" => Some(AlignSelf::KEYWORDS),
+            "justify-self" => Some(JustifySelf::KEYWORDS),
+            "align-content" => Some(AlignContent::KEYWORDS),
+            "justify-content" => Some(JustifyContent::KEYWORDS),
+            "flex-direction" => Some(FlexDirection::KEYWORDS),
+            "flex-wrap" => Some(FlexWrap::KEYWORDS),
+            "grid-auto-flow" => Some(GridAutoFlow::KEYWORDS),
+            "font-smoothing" => Some(FontSmoothing::KEYWORDS),
+            "justify" => Some(Justify::KEYWORDS),
+            "line-break" => Some(LineBreak::KEYWORDS),
+            _ => None,
+        }
+    }
+
+    /// Returns a space-separated list of the given values.
+    fn list(values: &[PropertyValue]) -> PropertyValue {
+        PropertyValue::List(values.to_vec())
+    }
+
+    /// Returns a call to the function with the given name and arguments.
+    fn function(name: &str, args: &[PropertyValue]) -> PropertyValue {
+        PropertyValue::Function {
+            name: name.to_string(),
+            args: args.to_vec(),
+        }
+    }
+
+    /// Returns a value of the given property that uses the given keyword.
+    /// Keywords that cannot stand on their own are placed within the list or
+    /// function that accepts them.
+    fn keyword_value(name: &str, keyword: &str) -> PropertyValue {
+        let value = PropertyValue::from(keyword);
+        let color = PropertyValue::Color(Rgba::WHITE);
+
+        match (name, keyword) {
+            ("grid-template-rows" | "grid-template-columns", "auto-fill" | "auto-fit") => {
+                function("repeat", &[value, PropertyValue::Pixels(100.0)])
+            }
+            ("grid-row" | "grid-column", "span") => list(&[value, PropertyValue::Number(2.0)]),
+            ("background" | "border-gradient", "none") => value,
+            ("background" | "border-gradient", "to") => {
+                function("linear-gradient", &[list(&[value, "right".into()]), color])
+            }
+            ("background" | "border-gradient", "left" | "right" | "top" | "bottom") => {
+                function("linear-gradient", &[list(&["to".into(), value]), color])
+            }
+            ("background" | "border-gradient", "at") => {
+                function("radial-gradient", &[list(&[value, "center".into()]), color])
+            }
+            ("background" | "border-gradient", "center") => {
+                function("radial-gradient", &[list(&["at".into(), value]), color])
+            }
+            ("background" | "border-gradient", "from") => function(
+                "conic-gradient",
+                &[list(&[value, PropertyValue::Angle(90.0)]), color],
+            ),
+            ("background" | "border-gradient", _) => function("radial-gradient", &[value, color]),
+            _ => value,
+        }
+    }
+
+    #[test]
+    fn accepted_keywords_convert() {
+        for widget in NATIVE_WIDGETS.iter() {
+            for name in widget.default_properties.keys() {
+                if MATCHED_WHILE_SPAWNING.contains(&name.as_str()) {
+                    continue;
+                }
+
+                let (_, keywords) = accepted_values(name);
+                for keyword in keywords {
+                    assert!(
+                        converts(name, &keyword_value(name, keyword)),
+                        "{}: keyword {} does not convert",
+                        name,
+                        keyword
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn enum_keywords_are_accepted() {
+        let mut checked = 0;
+        for widget in NATIVE_WIDGETS.iter() {
+            for name in widget.default_properties.keys() {
+                let Some(expected) = enum_keywords(name) else {
+                    continue;
+                };
+
+                let (_, keywords) = accepted_values(name);
+                let mut keywords = keywords.to_vec();
+                keywords.sort();
+                let mut expected = expected.to_vec();
+                expected.sort();
+
+                assert_eq!(
+                    keywords, expected,
+                    "{}: keywords do not match the enum",
+                    name
+                );
+                checked += 1;
+            }
+        }
+
+        assert!(checked > 0);
+    }
+
+    /// An enum with a renamed variant, used to test the derive.
+    #[derive(Default, Debug, Clone, Copy, PartialEq, neko_derive::NekoEnum)]
+    enum Shape {
+        /// The default variant.
+        #[default]
+        Circle,
+
+        /// A variant written in kebab-case.
+        RoundedSquare,
+
+        /// A renamed variant.
+        #[neko(rename = "star")]
+        Pentagram,
+    }
+
+    #[test]
+    fn derived_enums_round_trip() {
+        assert_eq!(Shape::KEYWORDS, ["circle", "rounded-square", "star"]);
+        assert_eq!(
+            <Shape as FromPropertyValue>::accepted_values(),
+            (&[PropertyType::String][..], Shape::KEYWORDS)
+        );
+
+        for shape in [Shape::Circle, Shape::RoundedSquare, Shape::Pentagram] {
+            assert_eq!(Shape::from_keyword(shape.keyword()), Some(shape));
+            assert_eq!(convert::<Shape>(&shape.into_property_value()), Some(shape));
+        }
+
+        assert_eq!(
+            Shape::Pentagram.into_property_value(),
+            PropertyValue::from("star")
+        );
+    }
+
+    #[test]
+    fn unknown_enum_keywords_fall_back_to_the_default() {
+        assert_eq!(Shape::from_keyword("pentagram"), None);
+        assert_eq!(Shape::from_keyword("RoundedSquare"), None);
+
+        assert_eq!(convert::<Shape>(&"square".into()), None);
+        assert_eq!(convert::<Shape>(&PropertyValue::Number(1.0)), None);
+        assert_eq!(Shape::from_property_value(&"square".into()), Shape::Circle);
+    }
+
+    #[test]
+    fn grid_tracks_convert() {
+        assert_eq!(grid_track(&"auto".into()), Some(GridTrack::auto()));
+        assert_eq!(
+            grid_track(&"min-content".into()),
+            Some(GridTrack::min_content())
+        );
+        assert_eq!(
+            grid_track(&PropertyValue::Pixels(100.0)),
+            Some(GridTrack::px(100.0))
+        );
+        assert_eq!(
+            grid_track(&PropertyValue::Percent(25.0)),
+            Some(GridTrack::percent(25.0))
+        );
+        assert_eq!(
+            grid_track(&PropertyValue::Fraction(2.0)),
+            Some(GridTrack::fr(2.0))
+        );
+        assert_eq!(
+            grid_track(&function(
+                "minmax",
+                &[PropertyValue::Pixels(100.0), PropertyValue::Fraction(1.0)]
+            )),
+            Some(GridTrack::minmax(
+                MinTrackSizingFunction::Px(100.0),
+                MaxTrackSizingFunction::Fraction(1.0)
+            ))
+        );
+
+        assert_eq!(
+            convert::<Vec<GridTrack>>(&list(&[
+                PropertyValue::Pixels(100.0),
+                PropertyValue::Fraction(1.0)
+            ])),
+            Some(vec![GridTrack::px(100.0), GridTrack::fr(1.0)])
+        );
+        assert_eq!(convert::<Vec<GridTrack>>(&"none".into()), Some(Vec::new()));
+    }
+
+    #[test]
+    fn repeated_grid_tracks_convert() {
+        assert_eq!(
+            repeated_grid_track(&[3.into(), PropertyValue::Fraction(1.0)]),
+            Some(RepeatedGridTrack::repeat_many(
+                GridTrackRepetition::Count(3),
+                vec![GridTrack::fr(1.0)]
+            ))
+        );
+        assert_eq!(
+            repeated_grid_track(&[
+                "auto-fill".into(),
+                list(&[PropertyValue::Pixels(100.0), "auto".into()])
+            ]),
+            Some(RepeatedGridTrack::repeat_many(
+                GridTrackRepetition::AutoFill,
+                vec![GridTrack::px(100.0), GridTrack::auto()]
+            ))
+        );
+        assert_eq!(
+            repeated_grid_track(&["auto-fit".into(), PropertyValue::Pixels(100.0)]),
+            Some(RepeatedGridTrack::repeat_many(
+                GridTrackRepetition::AutoFit,
+                vec![GridTrack::px(100.0)]
+            ))
+        );
+
+        assert_eq!(
+            convert::<Vec<RepeatedGridTrack>>(&list(&[
+                PropertyValue::Pixels(100.0),
+                function("repeat", &[2.into(), PropertyValue::Fraction(1.0)])
+            ])),
+            Some(vec![
+                RepeatedGridTrack::px(1, 100.0),
+                RepeatedGridTrack::repeat_many(
+                    GridTrackRepetition::Count(2),
+                    vec![GridTrack::fr(1.0)]
+                )
+            ])
+        );
+    }
+
+    #[test]
+    fn invalid_grid_tracks_are_rejected() {
+        assert_eq!(
+            repeated_grid_track(&[0.into(), PropertyValue::Fraction(1.0)]),
+            None
+        );
+        assert_eq!(
+            repeated_grid_track(&[1.5.into(), PropertyValue::Fraction(1.0)]),
+            None
+        );
+        assert_eq!(repeated_grid_track(&[3.into()]), None);
+        assert_eq!(
+            convert::<Vec<RepeatedGridTrack>>(&function(
+                "repeat",
+                &[0.into(), PropertyValue::Fraction(1.0)]
+            )),
+            None
+        );
+
+        assert_eq!(min_track_sizing(&PropertyValue::Fraction(1.0)), None);
+        assert_eq!(
+            grid_track(&function(
+                "minmax",
+                &[PropertyValue::Fraction(1.0), PropertyValue::Pixels(100.0)]
+            )),
+            None
+        );
+        assert_eq!(
+            convert::<Vec<GridTrack>>(&function(
+                "minmax",
+                &[PropertyValue::Fraction(1.0), PropertyValue::Pixels(100.0)]
+            )),
+            None
+        );
+        assert_eq!(grid_track(&"auto-fill".into()), None);
+    }
+
+    #[test]
+    fn grid_placements_convert() {
+        assert_eq!(
+            convert::<GridPlacement>(&"auto".into()),
+            Some(GridPlacement::auto())
+        );
+        assert_eq!(
+            convert::<GridPlacement>(&2.into()),
+            Some(GridPlacement::start(2))
+        );
+        assert_eq!(
+            convert::<GridPlacement>(&(-1).into()),
+            Some(GridPlacement::start(-1))
+        );
+        assert_eq!(
+            convert::<GridPlacement>(&list(&["span".into(), 3.into()])),
+            Some(GridPlacement::span(3))
+        );
+        assert_eq!(
+            convert::<GridPlacement>(&list(&[2.into(), "span".into(), 3.into()])),
+            Some(GridPlacement::start_span(2, 3))
+        );
+
+        assert_eq!(convert::<GridPlacement>(&0.into()), None);
+        assert_eq!(
+            convert::<GridPlacement>(&list(&["span".into(), 0.into()])),
+            None
+        );
+        assert_eq!(convert::<GridPlacement>(&"span".into()), None);
+        assert_eq!(convert::<GridPlacement>(&1.5.into()), None);
+    }
+
+    #[test]
+    fn box_shadows_convert() {
+        let red = Rgba::new(1.0, 0.0, 0.0, 1.0);
+        assert_eq!(
+            convert::<BoxShadow>(&list(&[
+                PropertyValue::Pixels(2.0),
+                PropertyValue::Pixels(4.0)
+            ])),
+            Some(BoxShadow(vec![ShadowStyle {
+                color: Color::BLACK,
+                x_offset: Val::Px(2.0),
+                y_offset: Val::Px(4.0),
+                spread_radius: Val::ZERO,
+                blur_radius: Val::ZERO,
+            }]))
+        );
+        assert_eq!(
+            convert::<BoxShadow>(&PropertyValue::CommaList(vec![
+                list(&[
+                    PropertyValue::Pixels(2.0),
+                    PropertyValue::Pixels(4.0),
+                    PropertyValue::Pixels(8.0),
+                    PropertyValue::Percent(10.0),
+                    red.into(),
+                ]),
+                list(&[red.into(), 0.into(), 1.into(), 2.into()]),
+            ])),
+            Some(BoxShadow(vec![
+                ShadowStyle {
+                    color: srgba(&red),
+                    x_offset: Val::Px(2.0),
+                    y_offset: Val::Px(4.0),
+                    spread_radius: Val::Percent(10.0),
+                    blur_radius: Val::Px(8.0),
+                },
+                ShadowStyle {
+                    color: srgba(&red),
+                    x_offset: Val::Px(0.0),
+                    y_offset: Val::Px(1.0),
+                    spread_radius: Val::ZERO,
+                    blur_radius: Val::Px(2.0),
+                },
+            ]))
+        );
+        assert_eq!(
+            convert::<BoxShadow>(&"none".into()),
+            Some(BoxShadow::default())
+        );
+
+        assert_eq!(convert::<BoxShadow>(&PropertyValue::Pixels(2.0)), None);
+        assert_eq!(
+            convert::<BoxShadow>(&list(&[
+                PropertyValue::Pixels(2.0),
+                PropertyValue::Pixels(4.0),
+                red.into(),
+                red.into(),
+            ])),
+            None
+        );
+        assert_eq!(convert::<BoxShadow>(&"auto".into()), None);
+    }
+
+    #[test]
+    fn text_shadows_convert() {
+        let red = Rgba::new(1.0, 0.0, 0.0, 1.0);
+        assert_eq!(
+            convert::<TextShadow>(&list(&[2.into(), PropertyValue::Pixels(3.0)])),
+            Some(TextShadow {
+                offset: Vec2::new(2.0, 3.0),
+                color: TextShadow::default().color,
+            })
+        );
+        assert_eq!(
+            convert::<TextShadow>(&list(&[red.into(), 2.into(), 3.into()])),
+            Some(TextShadow {
+                offset: Vec2::new(2.0, 3.0),
+                color: srgba(&red),
+            })
+        );
+
+        assert_eq!(convert::<TextShadow>(&2.into()), None);
+        assert_eq!(
+            convert::<TextShadow>(&list(&[2.into(), PropertyValue::Percent(3.0)])),
+            None
+        );
+    }
+
+    #[test]
+    fn linear_gradients_convert() {
+        let (red, blue) = (Rgba::new(1.0, 0.0, 0.0, 1.0), Rgba::new(0.0, 0.0, 1.0, 1.0));
+        let stops = vec![ColorStop::auto(srgba(&red)), ColorStop::auto(srgba(&blue))];
+
+        assert_eq!(
+            gradient(&function("linear-gradient", &[red.into(), blue.into()])),
+            Some(LinearGradient::degrees(180.0, stops.clone()).into())
+        );
+        assert_eq!(
+            gradient(&function(
+                "linear-gradient",
+                &[
+                    list(&["to".into(), "top".into(), "right".into()]),
+                    red.into(),
+                    blue.into()
+                ]
+            )),
+            Some(LinearGradient::degrees(45.0, stops.clone()).into())
+        );
+        assert_eq!(
+            gradient(&function(
+                "linear-gradient",
+                &[list(&["to".into(), "left".into()]), red.into(), blue.into()]
+            )),
+            Some(LinearGradient::degrees(270.0, stops.clone()).into())
+        );
+        assert_eq!(
+            gradient(&function(
+                "linear-gradient",
+                &[PropertyValue::Angle(90.0), red.into(), blue.into()]
+            )),
+            Some(LinearGradient::degrees(90.0, stops).into())
+        );
+    }
+
+    #[test]
+    fn radial_and_conic_gradients_convert() {
+        let (red, blue) = (Rgba::new(1.0, 0.0, 0.0, 1.0), Rgba::new(0.0, 0.0, 1.0, 1.0));
+        let stops = vec![ColorStop::auto(srgba(&red)), ColorStop::auto(srgba(&blue))];
+
+        assert_eq!(
+            gradient(&function("radial-gradient", &[red.into(), blue.into()])),
+            Some(
+                RadialGradient::new(
+                    UiPosition::CENTER,
+                    RadialGradientShape::FarthestCorner,
+                    stops.clone()
+                )
+                .into()
+            )
+        );
+        assert_eq!(
+            gradient(&function(
+                "radial-gradient",
+                &[
+                    list(&["circle".into(), "at".into(), "left".into(), "top".into()]),
+                    red.into(),
+                    blue.into()
+                ]
+            )),
+            Some(
+                RadialGradient::new(
+                    UiPosition::anchor(Vec2::new(-0.5, -0.5)),
+                    RadialGradientShape::FarthestSide,
+                    stops.clone()
+                )
+                .into()
+            )
+        );
+        assert_eq!(
+            gradient(&function(
+                "radial-gradient",
+                &[
+                    list(&[
+                        "ellipse".into(),
+                        PropertyValue::Pixels(20.0),
+                        PropertyValue::Percent(50.0),
+                        "at".into(),
+                        PropertyValue::Pixels(10.0),
+                        PropertyValue::Pixels(5.0)
+                    ]),
+                    red.into(),
+                    blue.into()
+                ]
+            )),
+            Some(
+                RadialGradient::new(
+                    UiPosition::TOP_LEFT.at(Val::Px(10.0), Val::Px(5.0)),
+                    RadialGradientShape::Ellipse(Val::Px(20.0), Val::Percent(50.0)),
+                    stops
+                )
+                .into()
+            )
+        );
+
+        assert_eq!(
+            gradient(&function(
+                "conic-gradient",
+                &[
+                    list(&[
+                        "from".into(),
+                        PropertyValue::Angle(90.0),
+                        "at".into(),
+                        "right".into()
+                    ]),
+                    red.into(),
+                    list(&[blue.into(), PropertyValue::Angle(180.0)])
+                ]
+            )),
+            Some(
+                ConicGradient::new(
+                    UiPosition::anchor(Vec2::new(0.5, 0.0)),
+                    vec![
+                        AngularColorStop::auto(srgba(&red)),
+                        AngularColorStop::new(srgba(&blue), 180f32.to_radians())
+                    ]
+                )
+                .with_start(90f32.to_radians())
+                .into()
+            )
+        );
+    }
+
+    #[test]
+    fn color_stops_convert() {
+        let (red, blue) = (Rgba::new(1.0, 0.0, 0.0, 1.0), Rgba::new(0.0, 0.0, 1.0, 1.0));
+
+        assert_eq!(
+            color_stops(&[
+                list(&[red.into(), PropertyValue::Percent(25.0)]),
+                list(&[blue.into(), PropertyValue::Pixels(40.0)]),
+                red.into(),
+            ]),
+            Some(vec![
+                ColorStop::new(srgba(&red), Val::Percent(25.0)),
+                ColorStop::new(srgba(&blue), Val::Px(40.0)),
+                ColorStop::auto(srgba(&red)),
+            ])
+        );
+        assert_eq!(color_stops(&[]), None);
+        assert_eq!(
+            color_stops(&[red.into(), PropertyValue::Percent(50.0)]),
+            None
+        );
+        assert_eq!(color_stops(&[list(&[red.into(), "center".into()])]), None);
+        assert_eq!(angular_color_stops(&[]), None);
+        assert_eq!(
+            angular_color_stops(&[list(&[PropertyValue::Angle(90.0), red.into()])]),
+            None
+        );
+    }
+
+    #[test]
+    fn gradient_lists_convert() {
+        let red = Rgba::new(1.0, 0.0, 0.0, 1.0);
+        let linear = function("linear-gradient", &[red.into()]);
+        let radial = function("radial-gradient", &[red.into()]);
+        let expected = vec![
+            LinearGradient::degrees(180.0, vec![ColorStop::auto(srgba(&red))]).into(),
+            RadialGradient::new(
+                UiPosition::CENTER,
+                RadialGradientShape::FarthestCorner,
+                vec![ColorStop::auto(srgba(&red))],
+            )
+            .into(),
+        ];
+
+        assert_eq!(
+            convert::<BackgroundGradient>(&PropertyValue::CommaList(vec![
+                linear.clone(),
+                radial.clone()
+            ])),
+            Some(BackgroundGradient(expected.clone()))
+        );
+        assert_eq!(
+            convert::<BorderGradient>(&PropertyValue::CommaList(vec![linear, radial])),
+            Some(BorderGradient(expected))
+        );
+        assert_eq!(
+            convert::<BackgroundGradient>(&"none".into()),
+            Some(BackgroundGradient::default())
+        );
+    }
+
+    #[test]
+    fn invalid_gradients_are_rejected() {
+        let red = Rgba::new(1.0, 0.0, 0.0, 1.0);
+
+        assert_eq!(
+            gradient(&function(
+                "linear-gradient",
+                &[red.into(), PropertyValue::Percent(50.0)]
+            )),
+            None
+        );
+        assert_eq!(gradient(&function("linear-gradient", &[])), None);
+        assert_eq!(
+            gradient(&function(
+                "linear-gradient",
+                &[list(&["to".into(), "top".into()])]
+            )),
+            None
+        );
+
+        assert_eq!(
+            gradient(&function(
+                "linear-gradient",
+                &[list(&["to".into(), "diagonal".into()]), red.into()]
+            )),
+            None
+        );
+        assert_eq!(
+            gradient(&function(
+                "linear-gradient",
+                &[
+                    list(&["to".into(), "left".into(), "right".into()]),
+                    red.into()
+                ]
+            )),
+            None
+        );
+        assert_eq!(
+            gradient(&function(
+                "radial-gradient",
+                &[list(&["at".into(), "middle".into()]), red.into()]
+            )),
+            None
+        );
+        assert_eq!(
+            gradient(&function("radial-gradient", &["oval".into(), red.into()])),
+            None
+        );
+        assert_eq!(
+            gradient(&function("diagonal-gradient", &[red.into()])),
+            None
+        );
+        assert_eq!(
+            convert::<BackgroundGradient>(&function(
+                "linear-gradient",
+                &[list(&["to".into(), "diagonal".into()]), red.into()]
+            )),
+            None
+        );
+    }
+}
@@ -0,0 +1,494 @@
+//! This module implements native widgets registered from Rust code.
+//!
+//! Native widgets are the primitives that NekoMaid UI layouts are built from,
+//! such as `div` and `img`. Games can add their own, backed by any Bevy
+//! components:
+//!
+//! ```ignore
+//! app.register_native_widget(
+//!     "minimap",
+//!     HashMap::from([("zoom".to_string(), 1.into())]),
+//!     |mut entity: EntityWorldMut, element: &NekoElement| {
+//!         entity.insert((Node::default(), Minimap {
+//!             zoom: element.get_as("zoom"),
+//!         }));
+//!     },
+//! );
+//! ```
+//!
+//! The widget can then be used in layouts like any other native widget.
+//!
+//! ```text
+//! layout minimap {
+//!     zoom: 2;
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use bevy::prelude::*;
+
+use crate::parse::element::NekoElement;
+use crate::parse::property::PropertyType;
+use crate::parse::value::PropertyValue;
+use crate::parse::widget::NativeWidget;
+
+/// The function used to spawn a native widget registered from Rust code.
+///
+/// The function is given the spawned entity, which is already a child of the
+/// parent widget, along with the element describing the widget. The entity
+/// provides access to the whole world.
+pub type NativeSpawnFunc = Arc<dyn Fn(EntityWorldMut, &NekoElement) + Send + Sync>;
+
+/// A resource for managing native widgets registered from Rust code.
+#[derive(Default, Clone, Resource)]
+pub struct NativeWidgetRegistry {
+    /// The widget definitions, shared with the asset loader.
+    widgets: Arc<RwLock<HashMap<String, NativeWidget>>>,
+
+    /// Maps widget names to their spawn functions.
+    spawn_funcs: HashMap<String, NativeSpawnFunc>,
+}
+
+impl NativeWidgetRegistry {
+    /// Registers a native widget with the given default properties. The
+    /// widget accepts exactly the properties that have a default value.
+    ///
+    /// If a widget with the same name already exists, including a built-in
+    /// one, it is replaced.
+    pub fn register(
+        &mut self,
+        name: impl Into<String>,
+        default_properties: HashMap<String, PropertyValue>,
+        spawn: NativeSpawnFunc,
+    ) {
+        let name = name.into();
+        let widget = NativeWidget {
+            name: name.clone(),
+            default_properties: Arc::new(default_properties),
+            keywords: Arc::default(),
+        };
+
+        self.widgets
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(name.clone(), widget);
+        self.spawn_funcs.insert(name, spawn);
+    }
+
+    /// Declares the keywords accepted by a property of the registered widget
+    /// with the given name, so that tooling can suggest them. Does nothing if
+    /// no such widget is registered.
+    pub fn register_keywords(
+        &mut self,
+        name: &str,
+        property: impl Into<String>,
+        keywords: &[&str],
+    ) {
+        let mut widgets = self.widgets.write().unwrap_or_else(|e| e.into_inner());
+        if let Some(widget) = widgets.get_mut(name) {
+            let keywords = keywords.iter().map(|k| k.to_string()).collect();
+            Arc::make_mut(&mut widget.keywords).insert(property.into(), keywords);
+        }
+    }
+
+    /// Returns the spawn function of the registered widget with the given
+    /// name, if there is one.
+    pub fn spawn_func(&self, name: &str) -> Option<&NativeSpawnFunc> {
+        self.spawn_funcs.get(name)
+    }
+
+    /// Returns a handle to the registered widget definitions, which stays up
+    /// to date as more widgets are registered.
+    pub(crate) fn widgets(&self) -> Arc<RwLock<HashMap<String, NativeWidget>>> {
+        self.widgets.clone()
+    }
+}
+
+/// A native widget backed by a component. It can easily be implemented with
+/// derive, where each field of the component becomes a property.
+///
+/// ```ignore
+/// #[derive(Component, NekoWidget)]
+/// #[neko(name = "minimap")]
+/// #[require(Node)]
+/// pub struct Minimap {
+///     #[neko(default = 1.0)]
+///     zoom: f32,
+///
+///     #[neko(rename = "show-icons", default = true)]
+///     icons: bool,
+///
+///     #[neko(skip)]
+///     camera: Option<Entity>,
+/// }
+/// ```
+///
+/// Fields can be of any type that can be converted from and into a property
+/// value, including enums that derive [`NekoEnum`](crate::value::NekoEnum).
+/// Literal defaults are converted into the field type like any other property
+/// value, so `#[neko(default = 10)]` is 10 pixels on a [`Val`] field.
+///
+/// The default values are checked against the types accepted by their fields
+/// when the widget is registered with
+/// [`add_native_widget`](NativeWidgetAppExt::add_native_widget).
+pub trait NekoWidget: Component + Sized {
+    /// Returns the name of the widget, as used in layouts.
+    fn name() -> &'static str;
+
+    /// Returns the default values of the properties of the widget.
+    fn default_properties() -> HashMap<String, PropertyValue>;
+
+    /// Returns the value types and keywords accepted by the properties of the
+    /// widget, as given by
+    /// [`FromPropertyValue::accepted_values`](crate::value::FromPropertyValue::accepted_values)
+    /// for the type of their field. Properties that are not listed are not
+    /// checked.
+    fn accepted_values() -> HashMap<String, (&'static [PropertyType], &'static [&'static str])> {
+        HashMap::new()
+    }
+
+    /// Creates the component from the properties of an element.
+    fn from_element(element: &NekoElement) -> Self;
+
+    /// Spawns the widget by inserting the component. Any other components
+    /// the widget needs, such as [`Node`], can be added as required
+    /// components.
+    fn spawn(mut entity: EntityWorldMut, element: &NekoElement) {
+        entity.insert(Self::from_element(element));
+    }
+}
+
+/// A trait to easily register native widgets.
+///
+/// ```ignore
+/// app.register_native_widget("minimap", default_properties, spawn_minimap);
+/// app.add_native_widget::<Minimap>();
+/// ```
+pub trait NativeWidgetAppExt {
+    /// Registers a native widget with the [`NativeWidgetRegistry`].
+    fn register_native_widget(
+        &mut self,
+        name: impl Into<String>,
+        default_properties: HashMap<String, PropertyValue>,
+        spawn: impl Fn(EntityWorldMut, &NekoElement) + Send + Sync + 'static,
+    ) -> &mut Self;
+
+    /// Registers a type that implements [`NekoWidget`] as a native widget.
+    /// The keywords accepted by its fields, such as the variants of
+    /// [`NekoEnum`](crate::value::NekoEnum) fields, are registered as well.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the default value of a property is not accepted by the type
+    /// of its field.
+    fn add_native_widget<T: NekoWidget>(&mut self) -> &mut Self;
+}
+
+impl NativeWidgetAppExt for App {
+    fn register_native_widget(
+        &mut self,
+        name: impl Into<String>,
+        default_properties: HashMap<String, PropertyValue>,
+        spawn: impl Fn(EntityWorldMut, &NekoElement) + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.init_resource::<NativeWidgetRegistry>()
+            .world_mut()
+            .resource_mut::<NativeWidgetRegistry>()
+            .register(name, default_properties, Arc::new(spawn));
+        self
+    }
+
+    fn add_native_widget<T: NekoWidget>(&mut self) -> &mut Self {
+        let default_properties = T::default_properties();
+        let accepted_values = T::accepted_values();
+        check_default_properties(T::name(), &default_properties, &accepted_values);
+        self.register_native_widget(T::name(), default_properties, T::spawn);
+
+        let mut registry = self.world_mut().resource_mut::<NativeWidgetRegistry>();
+        for (property, (_, keywords)) in accepted_values {
+            if !keywords.is_empty() {
+                registry.register_keywords(T::name(), property, keywords);
+            }
+        }
+
+        self
+    }
+}
+
+/// Checks that the default value of each property of a widget has a type and
+/// keyword accepted by the property.
+///
+/// # Panics
+///
+/// Panics with a description of the first default value that is not
+/// accepted.
+fn check_default_properties(
+    widget: &str,
+    default_properties: &HashMap<String, PropertyValue>,
+    accepted_values: &HashMap<String, (&'static [PropertyType], &'static [&'static str])>,
+) {
+    for (name, value) in default_properties {
+        let Some((types, keywords)) = accepted_values.get(name) else {
+            continue;
+        };
+
+        if !types.is_empty() && !types.contains(&value.value_type()) {
+            let types: Vec<_> = types.iter().map(|t| t.to_string()).collect();
+            panic!(
+                "The default value {} of {}.{} is a {}, but the property accepts {}",
+                value,
+                widget,
+                name,
+                value.value_type(),
+                types.join(", ")
+            );
+        }
+
+        if let PropertyValue::String(keyword) = value
+            && !keywords.is_empty()
+            && !keywords.contains(&keyword.as_str())
+        {
+            panic!(
+                "The default value {} of {}.{} is not one of the keywords {}",
+                value,
+                widget,
+                name,
+                keywords.join(", ")
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use neko_derive::{NekoEnum, NekoWidget};
+
+    use super::*;
+    use crate::parse::NekoMaidParser;
+    use crate::value::{IntoPropertyValue, NekoElementExt};
+
+    /// A component spawned by the test widget.
+    #[derive(Component, Debug, PartialEq)]
+    struct Minimap {
+        /// The zoom read from the element.
+        zoom: f32,
+    }
+
+    /// Parses the given code with the widgets of the given registry, and
+    /// returns the first element with its properties resolved.
+    fn element(registry: &NativeWidgetRegistry, code: &str) -> NekoElement {
+        let mut parser = NekoMaidParser::tokenize(code).unwrap();
+        for widget in registry.widgets().read().unwrap().values() {
+            parser.register_native_widget(widget.clone());
+        }
+
+        let module = parser.finish().unwrap();
+        let mut element = module.elements[0].element.clone();
+        element.resolve(&HashMap::new()).unwrap();
+        element
+    }
+
+    /// Returns a registry with the test widget registered.
+    fn registry() -> NativeWidgetRegistry {
+        let mut registry = NativeWidgetRegistry::default();
+        registry.register(
+            "minimap",
+            HashMap::from([(String::from("zoom"), PropertyValue::Number(1.0))]),
+            Arc::new(|mut entity: EntityWorldMut, element: &NekoElement| {
+                entity.insert(Minimap {
+                    zoom: element.get_as("zoom"),
+                });
+            }),
+        );
+        registry
+    }
+
+    #[test]
+    fn registered_widgets_are_shared() {
+        let registry = registry();
+        let widgets = registry.widgets();
+        assert_eq!(
+            widgets.read().unwrap()["minimap"].default_properties["zoom"],
+            PropertyValue::Number(1.0)
+        );
+
+        let mut registry = registry.clone();
+        registry.register("compass", HashMap::new(), Arc::new(|_, _| {}));
+        assert!(widgets.read().unwrap().contains_key("compass"));
+    }
+
+    #[test]
+    fn spawn_func_spawns_registered_widgets() {
+        let registry = registry();
+        assert!(registry.spawn_func("compass").is_none());
+
+        let spawn = registry.spawn_func("minimap").unwrap();
+        let mut world = World::new();
+
+        let entity = world.spawn_empty().id();
+        spawn(
+            world.entity_mut(entity),
+            &element(&registry, "layout minimap {}"),
+        );
+        assert_eq!(world.get::<Minimap>(entity), Some(&Minimap { zoom: 1.0 }));
+
+        let entity = world.spawn_empty().id();
+        let element = element(&registry, "layout minimap { zoom: 3; }");
+        spawn(world.entity_mut(entity), &element);
+        assert_eq!(world.get::<Minimap>(entity), Some(&Minimap { zoom: 3.0 }));
+    }
+
+    #[test]
+    fn registering_a_widget_replaces_it() {
+        let mut registry = registry();
+        registry.register(
+            "minimap",
+            HashMap::new(),
+            Arc::new(|mut entity: EntityWorldMut, _: &NekoElement| {
+                entity.insert(Minimap { zoom: 0.0 });
+            }),
+        );
+
+        let widgets = registry.widgets();
+        assert!(
+            widgets.read().unwrap()["minimap"]
+                .default_properties
+                .is_empty()
+        );
+
+        let mut world = World::new();
+        let entity = world.spawn_empty().id();
+        registry.spawn_func("minimap").unwrap()(
+            world.entity_mut(entity),
+            &element(&registry, "layout minimap {}"),
+        );
+        assert_eq!(world.get::<Minimap>(entity), Some(&Minimap { zoom: 0.0 }));
+    }
+
+    /// The side a [`Gauge`] fills from.
+    #[derive(Default, Debug, PartialEq, NekoEnum)]
+    enum Side {
+        /// Fills from the left.
+        #[default]
+        Left,
+
+        /// Fills from the right.
+        Right,
+    }
+
+    /// A derived widget with literal and expression defaults.
+    #[derive(Component, NekoWidget)]
+    #[neko(name = "gauge")]
+    struct Gauge {
+        /// A length with a numeric default.
+        #[neko(default = 10)]
+        width: Val,
+
+        /// A keyword with a string default.
+        #[neko(default = "right")]
+        side: Side,
+
+        /// A color with an expression default.
+        #[neko(default = Color::srgb(1.0, 0.5, 0.0))]
+        color: Color,
+
+        /// A negative number.
+        #[neko(default = -1)]
+        offset: i32,
+
+        /// A field that is not a property.
+        #[neko(skip)]
+        cache: Option<Entity>,
+    }
+
+    /// A derived widget whose default is not accepted by its field.
+    #[derive(Component, NekoWidget)]
+    #[allow(dead_code)]
+    struct WrongType {
+        /// A number with a string default.
+        #[neko(default = "wide")]
+        width: f32,
+    }
+
+    /// A derived widget whose default is not one of the keywords of its
+    /// field.
+    #[derive(Component, NekoWidget)]
+    #[allow(dead_code)]
+    struct WrongKeyword {
+        /// A keyword with an unknown default.
+        #[neko(default = "middle")]
+        side: Side,
+    }
+
+    #[test]
+    fn derived_defaults_convert_through_property_values() {
+        assert_eq!(
+            Gauge::default_properties(),
+            HashMap::from([
+                (String::from("width"), PropertyValue::Number(10.0)),
+                (
+                    String::from("side"),
+                    PropertyValue::String(String::from("right"))
+                ),
+                (
+                    String::from("color"),
+                    Color::srgb(1.0, 0.5, 0.0).into_property_value()
+                ),
+                (String::from("offset"), PropertyValue::Number(-1.0)),
+            ])
+        );
+
+        let mut registry = NativeWidgetRegistry::default();
+        registry.register(
+            Gauge::name(),
+            Gauge::default_properties(),
+            Arc::new(Gauge::spawn),
+        );
+        let gauge = Gauge::from_element(&element(&registry, "layout gauge {}"));
+        assert_eq!(gauge.width, Val::Px(10.0));
+        assert_eq!(gauge.side, Side::Right);
+        assert_eq!(gauge.color, Color::srgb(1.0, 0.5, 0.0));
+        assert_eq!(gauge.offset, -1);
+        assert_eq!(gauge.cache, None);
+
+        let code = "layout gauge { width: 50%; side: left; }";
+        let gauge = Gauge::from_element(&element(&registry, code));
+        assert_eq!(gauge.width, Val::Percent(50.0));
+        assert_eq!(gauge.side, Side::Left);
+    }
+
+    #[test]
+    fn derived_fields_report_accepted_values() {
+        let values = Gauge::accepted_values();
+        assert_eq!(values.len(), 4);
+        assert_eq!(
+            values["side"],
+            (&[PropertyType::String][..], &["left", "right"][..])
+        );
+        assert_eq!(values["color"].0, &[PropertyType::Color]);
+        assert!(values["width"].0.contains(&PropertyType::Number));
+
+        let mut app = App::new();
+        app.add_native_widget::<Gauge>();
+
+        let widgets = app.world().resource::<NativeWidgetRegistry>().widgets();
+        let gauge = &widgets.read().unwrap()["gauge"];
+        assert_eq!(gauge.property_keywords("side"), ["left", "right"]);
+        assert_eq!(gauge.property_keywords("width"), ["auto"]);
+        assert!(gauge.property_keywords("color").is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "wrong_type.width is a string, but the property accepts number")]
+    fn defaults_of_the_wrong_type_are_rejected() {
+        App::new().add_native_widget::<WrongType>();
+    }
+
+    #[test]
+    #[should_panic(expected = "wrong_keyword.side is not one of the keywords left, right")]
+    fn unknown_default_keywords_are_rejected() {
+        App::new().add_native_widget::<WrongKeyword>();
+    }
+}
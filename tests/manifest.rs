@@ -0,0 +1,35 @@
+//! Checks that the manifest read by the tooling matches the widgets and
+//! markers registered by the examples.
+
+#[allow(dead_code)]
+#[path = "../examples/marker.rs"]
+mod marker;
+
+#[allow(dead_code)]
+#[path = "../examples/widget.rs"]
+mod widget;
+
+use std::path::Path;
+
+use bevy::prelude::*;
+use neko_maid::manifest::ManifestAppExt;
+use neko_maid::marker::MarkerAppExt;
+use neko_maid::parse::manifest::{MANIFEST_FILE, Manifest};
+use neko_maid::widget::NativeWidgetAppExt;
+
+#[test]
+fn manifest_matches_registered_widgets() {
+    let mut app = App::new();
+    app.add_native_widget::<widget::HealthBar>()
+        .add_marker::<marker::Test>();
+
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(MANIFEST_FILE);
+    let expected = app.manifest().unwrap().to_json();
+    let actual = Manifest::load(&path).unwrap().to_json();
+    assert!(
+        actual == expected,
+        "{} is out of date, it should be:\n{}",
+        MANIFEST_FILE,
+        expected
+    );
+}